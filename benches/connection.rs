@@ -0,0 +1,34 @@
+//! Compares sequential requests with and without connection reuse
+//!
+//! Talks to gpodder.net, so results depend on the network. Without reuse every request pays for a TCP and TLS handshake, which typically doubles or triples its duration.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use mygpoclient::client::{ConnectionOptions, PublicClient};
+use mygpoclient::directory::RetrieveTopTags;
+
+const REQUESTS: usize = 5;
+
+fn sequential_requests(client: &PublicClient) {
+    for _ in 0..REQUESTS {
+        client.retrieve_top_tags(1).unwrap();
+    }
+}
+
+fn connection_reuse(c: &mut Criterion) {
+    let reusing = PublicClient::new()
+        .with_connection_options(&ConnectionOptions::default())
+        .unwrap();
+    let not_reusing = PublicClient::new()
+        .with_connection_options(&ConnectionOptions::default().with_pool_max_idle_per_host(0))
+        .unwrap();
+
+    let mut group = c.benchmark_group("connection");
+    group.sample_size(10);
+    group.bench_function("reuse", |b| b.iter(|| sequential_requests(&reusing)));
+    group.bench_function("no reuse", |b| b.iter(|| sequential_requests(&not_reusing)));
+    group.finish();
+}
+
+criterion_group!(benches, connection_reuse);
+criterion_main!(benches);
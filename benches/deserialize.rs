@@ -0,0 +1,57 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use mygpoclient::episode::GetEpisodeActionsResponse;
+use mygpoclient::subscription::Podcast;
+#[cfg(feature = "zero-copy")]
+use mygpoclient::subscription::PodcastRef;
+
+const COUNT: usize = 1000;
+
+fn toplist_payload() -> String {
+    let podcasts: Vec<String> = (0..COUNT)
+        .map(|i| {
+            format!(
+                r#"{{"url":"http://example.com/{i}/feed.rss","title":"Podcast {i}","author":"Author {i}","description":"Description of podcast {i}","subscribers":{s},"subscribers_last_week":{s},"logo_url":"http://example.com/{i}/logo.png","scaled_logo_url":"http://gpodder.net/logo/64/{i}.jpg","website":"http://example.com/{i}","mygpo_link":"http://gpodder.net/podcast/{i}"}}"#,
+                i = i,
+                s = i % 1000
+            )
+        })
+        .collect();
+    format!("[{}]", podcasts.join(","))
+}
+
+fn episode_actions_payload() -> String {
+    let actions: Vec<String> = (0..COUNT)
+        .map(|i| {
+            format!(
+                r#"{{"podcast":"http://example.com/{i}/feed.rss","episode":"http://example.com/{i}/episode.mp3","device":"phone","action":"play","position":120,"started":15,"total":500,"timestamp":"2009-12-12T09:00:00"}}"#,
+                i = i
+            )
+        })
+        .collect();
+    format!(r#"{{"actions":[{}],"timestamp":12345}}"#, actions.join(","))
+}
+
+fn deserialize_toplist(c: &mut Criterion) {
+    let payload = toplist_payload();
+
+    c.bench_function("deserialize toplist", |b| {
+        b.iter(|| serde_json::from_str::<Vec<Podcast>>(black_box(&payload)).unwrap())
+    });
+
+    #[cfg(feature = "zero-copy")]
+    c.bench_function("deserialize toplist borrowed", |b| {
+        b.iter(|| serde_json::from_str::<Vec<PodcastRef<'_>>>(black_box(&payload)).unwrap())
+    });
+}
+
+fn deserialize_episode_actions(c: &mut Criterion) {
+    let payload = episode_actions_payload();
+
+    c.bench_function("deserialize episode actions", |b| {
+        b.iter(|| serde_json::from_str::<GetEpisodeActionsResponse>(black_box(&payload)).unwrap())
+    });
+}
+
+criterion_group!(benches, deserialize_toplist, deserialize_episode_actions);
+criterion_main!(benches);
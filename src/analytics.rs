@@ -0,0 +1,267 @@
+//! Listening statistics derived from [episode actions](crate::episode::EpisodeAction)
+//!
+//! Only [Play](crate::episode::EpisodeActionType::Play) actions are taken into account. An action contributes its listening time `position - started`, so play actions without `started` are ignored for listening time.
+
+use crate::episode::{EpisodeAction, EpisodeActionType};
+use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+use url::Url;
+
+/// Minimum percentage of an episode that has to be played to count as completed
+pub const COMPLETION_PERCENTAGE: u64 = 95;
+
+/// Period for aggregating listening time with [listening_time_per_period]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum Period {
+    /// calendar day
+    Day,
+    /// week starting on Monday
+    Week,
+    /// calendar month
+    Month,
+    /// calendar year
+    Year,
+}
+
+impl Period {
+    /// Returns the first day of the period containing `date`
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => {
+                date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            Period::Month => NaiveDate::from_ymd(date.year(), date.month(), 1),
+            Period::Year => NaiveDate::from_ymd(date.year(), 1, 1),
+        }
+    }
+}
+
+/// Sums up the listening time per podcast feed URL
+pub fn listening_time_per_podcast(actions: &[EpisodeAction]) -> HashMap<Url, Duration> {
+    let mut listening_times = HashMap::new();
+    for action in actions {
+        if let Some(seconds) = listened_seconds(action) {
+            *listening_times
+                .entry(action.podcast.clone())
+                .or_insert_with(Duration::default) += Duration::from_secs(seconds);
+        }
+    }
+    listening_times
+}
+
+/// Sums up the listening time per period, keyed by the first day of each period
+///
+/// Actions without timestamp are ignored.
+pub fn listening_time_per_period(
+    actions: &[EpisodeAction],
+    period: Period,
+) -> BTreeMap<NaiveDate, Duration> {
+    let mut listening_times = BTreeMap::new();
+    for action in actions {
+        if let (Some(seconds), Some(timestamp)) = (listened_seconds(action), action.timestamp) {
+            *listening_times
+                .entry(period.start(timestamp.date()))
+                .or_insert_with(Duration::default) += Duration::from_secs(seconds);
+        }
+    }
+    listening_times
+}
+
+/// Calculates the share of played episodes which have been completed, per podcast feed URL
+///
+/// An episode counts as played if there is a play action with `total` set, and as completed if [COMPLETION_PERCENTAGE] of it has been reached. The result is between 0 and 1.
+pub fn completion_rate_per_podcast(actions: &[EpisodeAction]) -> HashMap<Url, f64> {
+    let mut played: HashMap<&Url, HashSet<&Url>> = HashMap::new();
+    let mut completed: HashMap<&Url, HashSet<&Url>> = HashMap::new();
+
+    for action in actions {
+        if let Some(is_completed) = is_completed(action) {
+            played
+                .entry(&action.podcast)
+                .or_default()
+                .insert(&action.episode);
+            if is_completed {
+                completed
+                    .entry(&action.podcast)
+                    .or_default()
+                    .insert(&action.episode);
+            }
+        }
+    }
+
+    played
+        .into_iter()
+        .map(|(podcast, episodes)| {
+            let completed_count = completed.get(podcast).map_or(0, HashSet::len);
+            (
+                podcast.clone(),
+                completed_count as f64 / episodes.len() as f64,
+            )
+        })
+        .collect()
+}
+
+/// Returns up to `count` episode media URLs with the highest listening time, in descending order
+pub fn most_played_episodes(actions: &[EpisodeAction], count: usize) -> Vec<(Url, Duration)> {
+    let mut listening_times: HashMap<&Url, Duration> = HashMap::new();
+    for action in actions {
+        if let Some(seconds) = listened_seconds(action) {
+            *listening_times.entry(&action.episode).or_default() += Duration::from_secs(seconds);
+        }
+    }
+
+    let mut episodes: Vec<(Url, Duration)> = listening_times
+        .into_iter()
+        .map(|(episode, duration)| (episode.clone(), duration))
+        .collect();
+    episodes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    episodes.truncate(count);
+    episodes
+}
+
+fn listened_seconds(action: &EpisodeAction) -> Option<u64> {
+    match action.action {
+        EpisodeActionType::Play {
+            position,
+            started: Some(started),
+            ..
+        } => Some(u64::from(position.saturating_sub(started))),
+        _ => None,
+    }
+}
+
+fn is_completed(action: &EpisodeAction) -> Option<bool> {
+    match action.action {
+        EpisodeActionType::Play {
+            position,
+            total: Some(total),
+            ..
+        } if total > 0 => {
+            Some(u64::from(position) * 100 >= u64::from(total) * COMPLETION_PERCENTAGE)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        completion_rate_per_podcast, listening_time_per_period, listening_time_per_podcast,
+        most_played_episodes, Period,
+    };
+    use crate::episode::EpisodeAction;
+    use chrono::NaiveDate;
+    use std::time::Duration;
+    use url::Url;
+
+    fn play(podcast: &str, episode: &str, day: u32, started: u32, position: u32) -> EpisodeAction {
+        EpisodeAction::new_play(
+            Url::parse(podcast).unwrap(),
+            Url::parse(episode).unwrap(),
+            Some(NaiveDate::from_ymd(2020, 6, day).and_hms(9, 0, 0)),
+            position,
+            started,
+            1000,
+        )
+    }
+
+    fn example_actions() -> Vec<EpisodeAction> {
+        vec![
+            play(
+                "http://example.com/feed1.rss",
+                "http://example.com/e1.mp3",
+                1,
+                0,
+                500,
+            ),
+            play(
+                "http://example.com/feed1.rss",
+                "http://example.com/e1.mp3",
+                2,
+                500,
+                1000,
+            ),
+            play(
+                "http://example.com/feed1.rss",
+                "http://example.com/e2.mp3",
+                8,
+                0,
+                100,
+            ),
+            play(
+                "http://example.org/feed2.rss",
+                "http://example.org/e3.mp3",
+                8,
+                0,
+                300,
+            ),
+            EpisodeAction::new_play_stop(
+                Url::parse("http://example.org/feed2.rss").unwrap(),
+                Url::parse("http://example.org/e4.mp3").unwrap(),
+                None,
+                300,
+            ),
+        ]
+    }
+
+    #[test]
+    fn listening_time_by_podcast() {
+        let listening_times = listening_time_per_podcast(&example_actions());
+
+        assert_eq!(2, listening_times.len());
+        assert_eq!(
+            Duration::from_secs(1100),
+            listening_times[&Url::parse("http://example.com/feed1.rss").unwrap()]
+        );
+        assert_eq!(
+            Duration::from_secs(300),
+            listening_times[&Url::parse("http://example.org/feed2.rss").unwrap()]
+        );
+    }
+
+    #[test]
+    fn listening_time_by_week() {
+        let listening_times = listening_time_per_period(&example_actions(), Period::Week);
+
+        assert_eq!(
+            vec![
+                (NaiveDate::from_ymd(2020, 6, 1), Duration::from_secs(1000)),
+                (NaiveDate::from_ymd(2020, 6, 8), Duration::from_secs(400)),
+            ],
+            listening_times.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn completion_rate() {
+        let completion_rates = completion_rate_per_podcast(&example_actions());
+
+        assert_eq!(
+            Some(&0.5),
+            completion_rates.get(&Url::parse("http://example.com/feed1.rss").unwrap())
+        );
+        assert_eq!(
+            Some(&0.0),
+            completion_rates.get(&Url::parse("http://example.org/feed2.rss").unwrap())
+        );
+    }
+
+    #[test]
+    fn most_played() {
+        assert_eq!(
+            vec![
+                (
+                    Url::parse("http://example.com/e1.mp3").unwrap(),
+                    Duration::from_secs(1000)
+                ),
+                (
+                    Url::parse("http://example.org/e3.mp3").unwrap(),
+                    Duration::from_secs(300)
+                ),
+            ],
+            most_played_episodes(&example_actions(), 2)
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Parallel execution of independent blocking requests
+//!
+//! A [BlockingBatch] runs requests on a small pool of scoped threads and returns their results in order, giving parallelism without an async runtime.
+
+use crate::error::Error;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+/// Number of threads used by [BlockingBatch::default]
+pub const DEFAULT_THREADS: usize = 4;
+
+type Job<'a, T> = Box<dyn FnOnce() -> Result<T, Error> + Send + 'a>;
+
+/// Set of independent requests executed in parallel by [run](BlockingBatch::run)
+///
+/// Jobs may borrow clients and other data, as all threads are joined before [run](BlockingBatch::run) returns. A panicking job is propagated to the caller after the other jobs have finished.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::batch::BlockingBatch;
+/// use mygpoclient::client::PublicClient;
+/// use mygpoclient::directory::RetrievePodcastData;
+/// use url::Url;
+///
+/// let client = PublicClient::default();
+/// let feeds = vec![
+///     Url::parse("http://feeds.feedburner.com/linuxoutlaws").unwrap(),
+///     Url::parse("http://goinglinux.com/mp3podcast.xml").unwrap(),
+/// ];
+///
+/// let mut batch = BlockingBatch::new(2);
+/// for feed in &feeds {
+///     let client = &client;
+///     batch.push(move || client.retrieve_podcast_data(feed.clone()));
+/// }
+///
+/// for podcast in batch.run() {
+///     println!("{}", podcast?);
+/// }
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub struct BlockingBatch<'a, T> {
+    threads: usize,
+    jobs: Vec<Job<'a, T>>,
+}
+
+impl<'a, T: Send> BlockingBatch<'a, T> {
+    /// Create an empty batch running at most `threads` jobs at the same time
+    ///
+    /// A `threads` of 0 is treated as 1.
+    pub fn new(threads: usize) -> Self {
+        BlockingBatch {
+            threads: threads.max(1),
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Add a job to the batch
+    pub fn push<F: FnOnce() -> Result<T, Error> + Send + 'a>(&mut self, job: F) {
+        self.jobs.push(Box::new(job));
+    }
+
+    /// Returns the number of jobs in the batch
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Returns `true` if the batch contains no jobs
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Execute all jobs and return their results in the order they were pushed
+    pub fn run(self) -> Vec<Result<T, Error>> {
+        let len = self.jobs.len();
+        let threads = self.threads.min(len);
+        let pending = Mutex::new(self.jobs.into_iter().enumerate().collect::<VecDeque<_>>());
+        let results = Mutex::new((0..len).map(|_| None).collect::<Vec<_>>());
+
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| loop {
+                    let next = lock(&pending).pop_front();
+                    match next {
+                        Some((index, job)) => {
+                            let result = job();
+                            lock(&results)[index] = Some(result);
+                        }
+                        None => break,
+                    }
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+impl<T: Send> Default for BlockingBatch<'_, T> {
+    fn default() -> Self {
+        BlockingBatch::new(DEFAULT_THREADS)
+    }
+}
+
+impl<T> fmt::Debug for BlockingBatch<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingBatch")
+            .field("threads", &self.threads)
+            .field("jobs", &self.jobs.len())
+            .finish()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // jobs run outside of the lock, so a panicking job can't leave the data in an inconsistent state
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockingBatch;
+    use crate::error::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn results_keep_order_and_threads_are_bounded() {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let mut batch = BlockingBatch::new(2);
+        for index in 0..6u64 {
+            let running = &running;
+            let max_running = &max_running;
+            batch.push(move || {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(10 * (6 - index)));
+                running.fetch_sub(1, Ordering::SeqCst);
+                if index == 3 {
+                    Err(Error::DeviceNotFound(index.to_string()))
+                } else {
+                    Ok(index)
+                }
+            });
+        }
+
+        let results = batch.run();
+
+        assert_eq!(6, results.len());
+        assert!(max_running.load(Ordering::SeqCst) <= 2);
+        for (index, result) in (0..6u64).zip(results) {
+            match result {
+                Ok(value) => assert_eq!(index, value),
+                Err(Error::DeviceNotFound(value)) => assert_eq!("3", value),
+                Err(e) => panic!("unexpected error {}", e),
+            }
+        }
+    }
+}
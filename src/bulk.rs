@@ -0,0 +1,98 @@
+//! Results of bulk operations which continue after individual failures
+//!
+//! Bulk operations like [upload_episode_actions_in_chunks](crate::episode::UploadEpisodeActions::upload_episode_actions_in_chunks) send several requests. Instead of stopping at the first error, they report which items succeeded and which failed, so the failed items can be retried later.
+
+use crate::error::Error;
+use std::ops::Range;
+
+/// Successes and failures of a bulk operation
+///
+/// Items are identified by their indices in the input of the operation.
+#[derive(Debug)]
+pub struct BulkResult<T> {
+    /// indices of the items of each successful request together with its result
+    pub successes: Vec<(Range<usize>, T)>,
+    /// failed requests
+    pub failures: Vec<BulkFailure>,
+}
+
+/// Failed request of a bulk operation
+#[derive(Debug)]
+pub struct BulkFailure {
+    /// indices of the items sent in the failed request
+    pub items: Range<usize>,
+    /// cause of the failure
+    pub error: Error,
+    /// `true` if sending the same items again may succeed, e.g. after a network error
+    pub retriable: bool,
+}
+
+impl<T> BulkResult<T> {
+    /// Returns `true` if no request failed
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns the indices of all items of failed requests
+    pub fn failed_items(&self) -> Vec<usize> {
+        self.failures
+            .iter()
+            .flat_map(|failure| failure.items.clone())
+            .collect()
+    }
+
+    /// Returns the indices of all items of failed requests which may succeed when retried
+    pub fn retriable_items(&self) -> Vec<usize> {
+        self.failures
+            .iter()
+            .filter(|failure| failure.retriable)
+            .flat_map(|failure| failure.items.clone())
+            .collect()
+    }
+
+    pub(crate) fn push(&mut self, items: Range<usize>, result: Result<T, Error>) {
+        match result {
+            Ok(value) => self.successes.push((items, value)),
+            Err(error) => self.failures.push(BulkFailure {
+                items,
+                retriable: error.is_retryable(),
+                error,
+            }),
+        }
+    }
+}
+
+impl<T> Default for BulkResult<T> {
+    fn default() -> Self {
+        BulkResult {
+            successes: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BulkResult;
+    use crate::error::{Error, ValidationError};
+    use std::io;
+    use url::Url;
+
+    #[test]
+    fn collect_successes_and_failures() {
+        let mut result = BulkResult::default();
+        result.push(0..2, Ok(1));
+        result.push(2..4, Err(Error::IoError(io::Error::other("reset"))));
+        result.push(
+            4..5,
+            Err(Error::ValidationError(ValidationError::ConflictingChange(
+                Url::parse("http://example.com/feed.rss").unwrap(),
+            ))),
+        );
+
+        assert!(!result.is_complete());
+        assert_eq!(vec![(0..2, 1)], result.successes);
+        assert_eq!(vec![2, 3, 4], result.failed_items());
+        assert_eq!(vec![2, 3], result.retriable_items());
+    }
+}
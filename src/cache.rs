@@ -0,0 +1,453 @@
+//! In-memory cache of podcast and episode metadata
+//!
+//! Attach a [MetadataCache] to a client with e.g. [with_metadata_cache](crate::client::PublicClient::with_metadata_cache) to answer repeated [retrieve_podcast_data](crate::directory::RetrievePodcastData::retrieve_podcast_data) and [retrieve_episode_data](crate::directory::RetrieveEpisodeData::retrieve_episode_data) requests for the same URLs locally, e.g. while rendering lists.
+//!
+//! The cache also keeps the last successful result of read endpoints like [get_all_subscriptions_or_cached](AuthenticatedClient::get_all_subscriptions_or_cached), which is returned as [stale](CachedResult::stale) [CachedResult] while the server can't be reached or is down for maintenance, so apps can present data immediately when offline.
+
+use crate::client::{AuthenticatedClient, DeviceClient, PublicClient};
+use crate::directory::Episode;
+use crate::error::{Error, ErrorCode};
+#[cfg(feature = "favorites")]
+use crate::favorite::GetFavoriteEpisodes;
+use crate::reference::EpisodeRef;
+use crate::subscription::{GetAllSubscriptions, Podcast, SubscriptionsOfDevice};
+use chrono::NaiveDateTime;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Size- and age-bounded cache of podcast and episode metadata
+///
+/// Keeps at most `capacity` podcasts and `capacity` episodes, evicting the least recently used ones first. Entries older than the time to live are requested again. Failed requests aren't cached.
+///
+/// Clones share the same entries, so the cache can be kept by the application after attaching it to a client.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::cache::MetadataCache;
+/// use mygpoclient::client::PublicClient;
+/// use mygpoclient::directory::RetrievePodcastData;
+/// use std::time::Duration;
+/// use url::Url;
+///
+/// let cache = MetadataCache::new(100, Duration::from_secs(60 * 60));
+/// let client = PublicClient::default().with_metadata_cache(cache.clone());
+///
+/// let url = Url::parse("http://feeds.feedburner.com/linuxoutlaws").unwrap();
+/// let podcast = client.retrieve_podcast_data(url.clone())?;
+/// let cached_podcast = client.retrieve_podcast_data(url.clone())?;
+/// assert_eq!(podcast.subscribers, cached_podcast.subscribers);
+///
+/// cache.invalidate(&url);
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    capacity: usize,
+    time_to_live: Duration,
+    entries: Arc<Mutex<Entries>>,
+}
+
+/// Result of a read endpoint, possibly taken from the [MetadataCache] while offline
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResult<T> {
+    /// returned data
+    pub data: T,
+    /// UTC time at which the data was received from the server
+    pub fetched_at: NaiveDateTime,
+    /// `true` if the server couldn't be reached and the data was taken from the cache
+    pub stale: bool,
+}
+
+/// Subscriptions of a device, keyed by username and device ID
+type DeviceSubscriptions = HashMap<(Arc<str>, Arc<str>), CachedResult<Vec<Url>>>;
+
+#[derive(Debug, Default)]
+struct Entries {
+    podcasts: VecDeque<(Url, Instant, Podcast)>,
+    episodes: VecDeque<(EpisodeRef, Instant, Episode)>,
+    all_subscriptions: HashMap<Arc<str>, CachedResult<Vec<Podcast>>>,
+    device_subscriptions: DeviceSubscriptions,
+    #[cfg(feature = "favorites")]
+    favorite_episodes: HashMap<Arc<str>, CachedResult<Vec<Episode>>>,
+}
+
+impl MetadataCache {
+    /// Create a cache keeping `capacity` podcasts and episodes for at most `time_to_live`
+    pub fn new(capacity: usize, time_to_live: Duration) -> MetadataCache {
+        MetadataCache {
+            capacity,
+            time_to_live,
+            entries: Arc::new(Mutex::new(Entries::default())),
+        }
+    }
+
+    /// Remove the podcast with the given feed URL and all episodes with the given media or feed URL
+    pub fn invalidate(&self, url: &Url) {
+        let mut entries = self.lock();
+        entries.podcasts.retain(|(feed, _, _)| feed != url);
+        entries
+            .episodes
+            .retain(|(episode, _, _)| episode.episode != *url && episode.podcast != *url);
+    }
+
+    /// Remove all entries, including the last results of read endpoints
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.podcasts.clear();
+        entries.episodes.clear();
+        entries.all_subscriptions.clear();
+        entries.device_subscriptions.clear();
+        #[cfg(feature = "favorites")]
+        entries.favorite_episodes.clear();
+    }
+
+    pub(crate) fn podcast(&self, url: &Url) -> Option<Podcast> {
+        get(&mut self.lock().podcasts, url, self.time_to_live)
+    }
+
+    pub(crate) fn insert_podcast(&self, url: Url, podcast: Podcast) {
+        insert(&mut self.lock().podcasts, url, podcast, self.capacity);
+    }
+
+    pub(crate) fn episode(&self, episode: &EpisodeRef) -> Option<Episode> {
+        get(&mut self.lock().episodes, episode, self.time_to_live)
+    }
+
+    pub(crate) fn insert_episode(&self, key: EpisodeRef, episode: Episode) {
+        insert(&mut self.lock().episodes, key, episode, self.capacity);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        // a panic while holding the lock can't leave the entries in an inconsistent state
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Returns a fresh entry and marks it as most recently used
+fn get<K: PartialEq, V: Clone>(
+    entries: &mut VecDeque<(K, Instant, V)>,
+    key: &K,
+    time_to_live: Duration,
+) -> Option<V> {
+    let index = entries.iter().position(|(cached, _, _)| cached == key)?;
+    let entry = entries.remove(index)?;
+    if entry.1.elapsed() >= time_to_live {
+        return None;
+    }
+    let value = entry.2.clone();
+    entries.push_back(entry);
+    Some(value)
+}
+
+fn insert<K: PartialEq, V>(
+    entries: &mut VecDeque<(K, Instant, V)>,
+    key: K,
+    value: V,
+    capacity: usize,
+) {
+    entries.retain(|(cached, _, _)| *cached != key);
+    if capacity == 0 {
+        return;
+    }
+    while entries.len() >= capacity {
+        entries.pop_front();
+    }
+    entries.push_back((key, Instant::now(), value));
+}
+
+/// Returns the fresh result of `request` and keeps it in `cache`, or the last kept result if the server can't be reached or is down for maintenance
+///
+/// Kept results don't expire, since stale data is preferable to no data while offline.
+fn or_cached<K: Eq + Hash, T: Clone>(
+    cache: Option<&MetadataCache>,
+    results: fn(&mut Entries) -> &mut HashMap<K, CachedResult<T>>,
+    key: K,
+    now: NaiveDateTime,
+    request: impl FnOnce() -> Result<T, Error>,
+) -> Result<CachedResult<T>, Error> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => {
+            return Ok(CachedResult {
+                data: request()?,
+                fetched_at: now,
+                stale: false,
+            })
+        }
+    };
+
+    match request() {
+        Ok(data) => {
+            let result = CachedResult {
+                data,
+                fetched_at: now,
+                stale: false,
+            };
+            results(&mut cache.lock()).insert(key, result.clone());
+            Ok(result)
+        }
+        Err(error) => match error.code() {
+            ErrorCode::Network | ErrorCode::Timeout | ErrorCode::Maintenance => {
+                match results(&mut cache.lock()).get(&key) {
+                    Some(cached) => Ok(CachedResult {
+                        stale: true,
+                        ..cached.clone()
+                    }),
+                    None => Err(error),
+                }
+            }
+            _ => Err(error),
+        },
+    }
+}
+
+impl PublicClient {
+    /// Answer repeated podcast and episode data requests from the given [MetadataCache]
+    pub fn with_metadata_cache(mut self, metadata_cache: MetadataCache) -> PublicClient {
+        self.metadata_cache = Some(metadata_cache);
+        self
+    }
+}
+
+impl AuthenticatedClient {
+    /// Answer repeated podcast and episode data requests from the given [MetadataCache]
+    pub fn with_metadata_cache(mut self, metadata_cache: MetadataCache) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_metadata_cache(metadata_cache);
+        self
+    }
+
+    /// [Get all subscriptions](GetAllSubscriptions::get_all_subscriptions), falling back to the last result kept in the [MetadataCache] if the server can't be reached or is down for maintenance
+    ///
+    /// Without an attached cache, this only annotates the fresh result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::cache::MetadataCache;
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use std::time::Duration;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password)
+    ///     .with_metadata_cache(MetadataCache::new(100, Duration::from_secs(60 * 60)));
+    ///
+    /// let subscriptions = client.get_all_subscriptions_or_cached()?;
+    /// if subscriptions.stale {
+    ///     println!("offline, showing subscriptions of {}", subscriptions.fetched_at);
+    /// }
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn get_all_subscriptions_or_cached(&self) -> Result<CachedResult<Vec<Podcast>>, Error> {
+        or_cached(
+            self.public_client.metadata_cache.as_ref(),
+            |entries| &mut entries.all_subscriptions,
+            self.username.clone(),
+            self.now(),
+            || self.get_all_subscriptions(),
+        )
+    }
+
+    /// [Get favorite episodes](GetFavoriteEpisodes::get_favorite_episodes), falling back to the last result kept in the [MetadataCache] if the server can't be reached or is down for maintenance
+    #[cfg(feature = "favorites")]
+    pub fn get_favorite_episodes_or_cached(&self) -> Result<CachedResult<Vec<Episode>>, Error> {
+        or_cached(
+            self.public_client.metadata_cache.as_ref(),
+            |entries| &mut entries.favorite_episodes,
+            self.username.clone(),
+            self.now(),
+            || self.get_favorite_episodes(),
+        )
+    }
+}
+
+impl DeviceClient {
+    /// Answer repeated podcast and episode data requests from the given [MetadataCache]
+    pub fn with_metadata_cache(mut self, metadata_cache: MetadataCache) -> DeviceClient {
+        self.authenticated_client = self
+            .authenticated_client
+            .with_metadata_cache(metadata_cache);
+        self
+    }
+
+    /// [Get all subscriptions](GetAllSubscriptions::get_all_subscriptions), falling back to the last result kept in the [MetadataCache] if the server can't be reached or is down for maintenance
+    pub fn get_all_subscriptions_or_cached(&self) -> Result<CachedResult<Vec<Podcast>>, Error> {
+        self.authenticated_client.get_all_subscriptions_or_cached()
+    }
+
+    /// [Get subscriptions of device](SubscriptionsOfDevice::get_subscriptions_of_device), falling back to the last result kept in the [MetadataCache] if the server can't be reached or is down for maintenance
+    pub fn get_subscriptions_of_device_or_cached(&self) -> Result<CachedResult<Vec<Url>>, Error> {
+        or_cached(
+            self.authenticated_client
+                .public_client
+                .metadata_cache
+                .as_ref(),
+            |entries| &mut entries.device_subscriptions,
+            (
+                self.authenticated_client.username.clone(),
+                self.device_id.clone(),
+            ),
+            self.now(),
+            || self.get_subscriptions_of_device(),
+        )
+    }
+
+    /// [Get favorite episodes](GetFavoriteEpisodes::get_favorite_episodes), falling back to the last result kept in the [MetadataCache] if the server can't be reached or is down for maintenance
+    #[cfg(feature = "favorites")]
+    pub fn get_favorite_episodes_or_cached(&self) -> Result<CachedResult<Vec<Episode>>, Error> {
+        self.authenticated_client.get_favorite_episodes_or_cached()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{or_cached, MetadataCache};
+    use crate::error::Error;
+    use crate::subscription::Podcast;
+    use chrono::NaiveDate;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use url::Url;
+
+    fn podcast(url: &str) -> Podcast {
+        Podcast {
+            url: Url::parse(url).unwrap(),
+            title: String::from("Podcast"),
+            author: None,
+            description: String::new(),
+            subscribers: 1,
+            subscribers_last_week: 1,
+            logo_url: None,
+            scaled_logo_url: None,
+            website: None,
+            mygpo_link: Url::parse("http://gpodder.net/podcast/1").unwrap(),
+        }
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let cache = MetadataCache::new(2, Duration::from_secs(60));
+        let a = Url::parse("http://example.com/a.rss").unwrap();
+        let b = Url::parse("http://example.com/b.rss").unwrap();
+        let c = Url::parse("http://example.com/c.rss").unwrap();
+
+        cache.insert_podcast(a.clone(), podcast(a.as_str()));
+        cache.insert_podcast(b.clone(), podcast(b.as_str()));
+        assert!(cache.podcast(&a).is_some());
+        cache.insert_podcast(c.clone(), podcast(c.as_str()));
+
+        assert!(cache.podcast(&a).is_some());
+        assert!(cache.podcast(&b).is_none());
+        assert!(cache.podcast(&c).is_some());
+
+        cache.invalidate(&a);
+        assert!(cache.podcast(&a).is_none());
+    }
+
+    #[test]
+    fn expire_old_entries() {
+        let cache = MetadataCache::new(2, Duration::from_secs(0));
+        let a = Url::parse("http://example.com/a.rss").unwrap();
+
+        cache.insert_podcast(a.clone(), podcast(a.as_str()));
+
+        assert!(cache.podcast(&a).is_none());
+    }
+
+    #[test]
+    fn fall_back_to_cached_result_while_offline() {
+        let cache = MetadataCache::new(2, Duration::from_secs(0));
+        let key = || (Arc::from("username"), Arc::from("device"));
+        let fetched_at = NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0);
+        let later = NaiveDate::from_ymd(2020, 1, 2).and_hms(9, 0, 0);
+        let url = Url::parse("http://example.com/a.rss").unwrap();
+        let offline = || {
+            Err(Error::Timeout {
+                endpoint: String::from("/api/2/subscriptions/username/device.json"),
+                elapsed: Duration::from_secs(30),
+            })
+        };
+
+        let missing = or_cached(
+            Some(&cache),
+            |entries| &mut entries.device_subscriptions,
+            key(),
+            fetched_at,
+            offline,
+        );
+        assert!(missing.is_err());
+
+        let fresh = or_cached(
+            Some(&cache),
+            |entries| &mut entries.device_subscriptions,
+            key(),
+            fetched_at,
+            || Ok(vec![url.clone()]),
+        )
+        .unwrap();
+        assert!(!fresh.stale);
+
+        let cached = or_cached(
+            Some(&cache),
+            |entries| &mut entries.device_subscriptions,
+            key(),
+            later,
+            offline,
+        )
+        .unwrap();
+        assert!(cached.stale);
+        assert_eq!(fetched_at, cached.fetched_at);
+        assert_eq!(vec![url], cached.data);
+
+        cache.clear();
+        assert!(or_cached(
+            Some(&cache),
+            |entries| &mut entries.device_subscriptions,
+            key(),
+            later,
+            offline,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn fall_back_to_cached_result_during_maintenance() {
+        let cache = MetadataCache::new(2, Duration::from_secs(0));
+        let key = || (Arc::from("username"), Arc::from("device"));
+        let fetched_at = NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0);
+        let url = Url::parse("http://example.com/a.rss").unwrap();
+
+        or_cached(
+            Some(&cache),
+            |entries| &mut entries.device_subscriptions,
+            key(),
+            fetched_at,
+            || Ok(vec![url.clone()]),
+        )
+        .unwrap();
+        let cached = or_cached(
+            Some(&cache),
+            |entries| &mut entries.device_subscriptions,
+            key(),
+            NaiveDate::from_ymd(2020, 1, 2).and_hms(9, 0, 0),
+            || {
+                Err(Error::Maintenance {
+                    endpoint: String::from("/api/2/subscriptions/username/device.json"),
+                    retry_after: Some(Duration::from_secs(120)),
+                })
+            },
+        )
+        .unwrap();
+
+        assert!(cached.stale);
+        assert_eq!(vec![url], cached.data);
+    }
+}
@@ -1,42 +1,658 @@
 //! Clients for communication with the service
+//!
+//! All clients are cheap to clone: credentials and device IDs are stored behind an [Arc] and the underlying HTTP client shares its connection pool between clones.
+//! Every client is [Send] and [Sync], so a single instance (or its clones) can be used from several threads at once.
 
-use reqwest::blocking::{Client, Response};
-use reqwest::IntoUrl;
+#[cfg(feature = "directory")]
+use crate::cache::MetadataCache;
+use crate::clock::{Clock, SystemClock};
+use crate::credentials::CredentialProvider;
+use crate::debug::{DebugRecorder, RecordedExchange};
+use crate::error::Error;
+use crate::latency::LatencyMonitor;
+use crate::redaction::{redact_url, Secret};
+use crate::reference::{DeviceId, Username};
+use crate::retry::{RetryAttempt, RetryPolicy};
+#[cfg(feature = "directory")]
+use crate::rewrite::UrlRewriter;
+use chrono::{DateTime, NaiveDateTime};
+use reqwest::blocking::{Client, Request, RequestBuilder};
+#[cfg(feature = "digest-auth")]
+use reqwest::header::AUTHORIZATION;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE, RETRY_AFTER, WWW_AUTHENTICATE,
+};
+use reqwest::redirect::Policy;
+use reqwest::{IntoUrl, Method, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io::{self, Read};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use url::Url;
 
 const PACKAGE_NAME: &str = env!("CARGO_PKG_NAME");
 const PACKAGE_VERSION: &str = env!("CARGO_PKG_VERSION");
+const BASE_URL: &str = "https://gpodder.net";
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// Version of the gpodder.net API used by default
+pub const DEFAULT_API_VERSION: u8 = crate::protocol::API_VERSION;
+
+/// Level of client-side validation before uploading data
+///
+/// The default is [Strict](Strictness::Strict).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum Strictness {
+    /// Reject data which violates the gpodder.net API documentation, e.g. [play actions](crate::episode::EpisodeActionType::Play) with `total` but without `started`
+    #[default]
+    Strict,
+    /// Only reject data which can't be processed by any known server implementation. Useful for forks of gpodder.net which accept more.
+    Lenient,
+}
+
+/// When credentials are sent to the server
+///
+/// The default is [Preemptive](AuthenticationMode::Preemptive).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum AuthenticationMode {
+    /// Send Basic credentials with every request, as expected by gpodder.net
+    #[default]
+    Preemptive,
+    /// Send every request without credentials first and repeat it with credentials if the server responds with `401 Unauthorized`
+    ///
+    /// Useful behind proxies which only accept credentials after a challenge. If the feature `digest-auth` is enabled, `Digest` challenges are answered with Digest credentials; all other challenges with Basic credentials. Requests with streaming bodies can't be repeated and are always sent with Basic credentials.
+    Challenge,
+}
+
+/// Server implementation of the gpodder.net API
+///
+/// Each profile knows the deviations of its server from gpodder.net. Deviations in the format of responses, e.g. timestamps as strings instead of integers or missing `update_urls`, are handled for all profiles. The profile additionally determines the default [Strictness] and the [supported API areas](ServerProfile::supports). Servers mounted below a subpath additionally need [EndpointPaths].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ServerProfile {
+    /// [gpodder.net](https://gpodder.net/)
+    #[default]
+    GpodderNet,
+    /// [micro-gpodder-server](https://github.com/bohwaz/micro-gpodder-server)
+    MicroGpodderServer,
+    /// [oPodSync](https://github.com/kd2org/opodsync)
+    Opodsync,
+    /// [Nextcloud gPodder Sync](https://github.com/thrillfall/nextcloud-gpodder)
+    Nextcloud,
+}
+
+/// Area of the API, corresponding to a module of this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ApiArea {
+    /// see [directory](crate::directory)
+    Directory,
+    /// see [suggestion](crate::suggestion)
+    Suggestions,
+    /// see [device](crate::device)
+    Devices,
+    /// see [subscription](crate::subscription)
+    Subscriptions,
+    /// see [episode](crate::episode)
+    EpisodeActions,
+    /// see [settings](crate::settings)
+    Settings,
+    /// see [favorite](crate::favorite)
+    Favorites,
+}
+
+/// Path prefixes of the endpoints of a server, e.g. for self-hosted servers behind a reverse proxy which mounts the API below a subpath
+///
+/// The prefix of an [ApiArea] is inserted between the host and the path of its endpoints, e.g. `/gpodder` turns `/api/2/devices/username.json` into `/gpodder/api/2/devices/username.json`. Areas without a prefix of their own use the [common prefix](EndpointPaths::with_prefix).
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::{ApiArea, EndpointPaths, PublicClient, ServerProfile};
+///
+/// let paths = EndpointPaths::new()
+///     .with_prefix("/gpodder")
+///     .with_area_prefix(ApiArea::Directory, "/directory/");
+/// assert_eq!("/gpodder", paths.prefix(ApiArea::Devices));
+/// assert_eq!("/directory", paths.prefix(ApiArea::Directory));
+///
+/// let client = PublicClient::default()
+///     .with_server_profile(ServerProfile::Opodsync)
+///     .with_endpoint_paths(paths);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct EndpointPaths {
+    prefix: String,
+    area_prefixes: BTreeMap<ApiArea, String>,
+}
+
+/// Connection reuse settings of the HTTP client
+///
+/// Reusing connections avoids a TCP and TLS handshake per request, which dominates the duration of small requests. This matters most when syncing many accounts against the same server, see the `connection` benchmark. The defaults of [reqwest] keep idle connections open for 90 seconds without limiting their number.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::{ConnectionOptions, PublicClient};
+/// use std::time::Duration;
+///
+/// let options = ConnectionOptions::default()
+///     .with_pool_idle_timeout(Duration::from_secs(300))
+///     .with_pool_max_idle_per_host(32);
+/// let client = PublicClient::new().with_connection_options(&options)?;
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ConnectionOptions {
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    http2_prior_knowledge: bool,
+    max_redirects: Option<usize>,
+    https_only_redirects: bool,
+}
 
 /// Client without authenticatication
-#[derive(Debug, Clone, Default)]
+///
+/// Clones share the same connection pool.
+#[derive(Debug, Clone)]
 pub struct PublicClient {
     pub(crate) client: Client,
+    pub(crate) api_version: u8,
+    pub(crate) base_url: Arc<str>,
+    pub(crate) server_profile: ServerProfile,
+    pub(crate) endpoint_paths: Arc<EndpointPaths>,
+    pub(crate) debug_recorder: Option<DebugRecorder>,
+    pub(crate) retry_policy: Option<Arc<dyn RetryPolicy>>,
+    pub(crate) clock: Arc<dyn Clock>,
+    pub(crate) read_only: bool,
+    pub(crate) max_response_size: Option<u64>,
+    pub(crate) default_headers: HeaderMap,
+    pub(crate) default_query: Vec<(String, String)>,
+    pub(crate) latency_monitor: Option<Arc<LatencyMonitor>>,
+    #[cfg(feature = "directory")]
+    pub(crate) metadata_cache: Option<MetadataCache>,
+    #[cfg(feature = "directory")]
+    pub(crate) url_rewriter: Option<Arc<dyn UrlRewriter>>,
+}
+
+/// Response with fully read body
+#[derive(Debug)]
+pub(crate) struct ApiResponse {
+    /// URL of the response, after following redirects
+    pub(crate) url: Url,
+    pub(crate) status: StatusCode,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Vec<u8>,
 }
 
 /// Client authenticated with username and password
+///
+/// Clones share credentials and connection pool. The password is redacted in [Debug](std::fmt::Debug) output.
 #[derive(Debug, Clone)]
 pub struct AuthenticatedClient {
-    pub(crate) username: String,
-    pub(crate) password: String,
+    pub(crate) username: Arc<str>,
+    pub(crate) password: Secret,
+    pub(crate) strictness: Strictness,
+    pub(crate) authentication_mode: AuthenticationMode,
+    pub(crate) credential_provider: Option<Arc<dyn CredentialProvider>>,
     pub(crate) public_client: PublicClient,
 }
 
 /// Device-specific [AuthenticatedClient]
+///
+/// Clones share device ID, credentials and connection pool.
 #[derive(Debug, Clone)]
 pub struct DeviceClient {
-    pub(crate) device_id: String,
+    pub(crate) device_id: Arc<str>,
     pub(crate) authenticated_client: AuthenticatedClient,
 }
 
+impl ServerProfile {
+    /// Returns `true` if the server implements the given [ApiArea]
+    pub fn supports(self, area: ApiArea) -> bool {
+        match self {
+            ServerProfile::GpodderNet => true,
+            ServerProfile::MicroGpodderServer | ServerProfile::Opodsync => matches!(
+                area,
+                ApiArea::Devices | ApiArea::Subscriptions | ApiArea::EpisodeActions
+            ),
+            ServerProfile::Nextcloud => {
+                matches!(area, ApiArea::Subscriptions | ApiArea::EpisodeActions)
+            }
+        }
+    }
+
+    /// [Strictness] suitable for the server
+    pub fn default_strictness(self) -> Strictness {
+        match self {
+            ServerProfile::GpodderNet => Strictness::Strict,
+            _ => Strictness::Lenient,
+        }
+    }
+}
+
+impl EndpointPaths {
+    /// Create [EndpointPaths] without prefixes, as used by gpodder.net
+    pub fn new() -> EndpointPaths {
+        EndpointPaths::default()
+    }
+
+    /// Use `prefix` for all areas without a prefix of their own
+    pub fn with_prefix(mut self, prefix: &str) -> EndpointPaths {
+        self.prefix = normalize_prefix(prefix);
+        self
+    }
+
+    /// Use `prefix` for the endpoints of `area`
+    ///
+    /// An empty prefix mounts the area at the root, even if a [common prefix](EndpointPaths::with_prefix) is set.
+    pub fn with_area_prefix(mut self, area: ApiArea, prefix: &str) -> EndpointPaths {
+        self.area_prefixes.insert(area, normalize_prefix(prefix));
+        self
+    }
+
+    /// Returns the prefix of the endpoints of `area`, starting with `/` unless empty
+    pub fn prefix(&self, area: ApiArea) -> &str {
+        self.area_prefixes.get(&area).unwrap_or(&self.prefix)
+    }
+}
+
+fn normalize_prefix(prefix: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", prefix)
+    }
+}
+
 impl PublicClient {
     /// Create [PublicClient] locally
     pub fn new() -> PublicClient {
         PublicClient {
             client: Default::default(),
+            api_version: DEFAULT_API_VERSION,
+            base_url: Arc::from(BASE_URL),
+            server_profile: ServerProfile::default(),
+            endpoint_paths: Arc::default(),
+            debug_recorder: None,
+            retry_policy: None,
+            clock: Arc::new(SystemClock),
+            read_only: false,
+            max_response_size: None,
+            default_headers: HeaderMap::new(),
+            default_query: Vec::new(),
+            latency_monitor: None,
+            #[cfg(feature = "directory")]
+            metadata_cache: None,
+            #[cfg(feature = "directory")]
+            url_rewriter: None,
+        }
+    }
+
+    /// Send requests with an HTTP client built from the given [ConnectionOptions]
+    pub fn with_connection_options(
+        self,
+        options: &ConnectionOptions,
+    ) -> Result<PublicClient, Error> {
+        Ok(self.with_http_client(options.build_http_client()?))
+    }
+
+    /// Send requests with the given preconfigured HTTP client
+    ///
+    /// Use this for custom TLS settings, e.g. root certificates of a self-hosted server or certificate pinning. The `User-Agent` header is still set by this crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    ///
+    /// let http_client = reqwest::blocking::Client::builder()
+    ///     .danger_accept_invalid_certs(false)
+    ///     .build()?;
+    /// let client = PublicClient::new().with_http_client(http_client);
+    /// #
+    /// # Ok::<(), reqwest::Error>(())
+    /// ```
+    pub fn with_http_client(mut self, client: Client) -> PublicClient {
+        self.client = client;
+        self
+    }
+
+    /// Record requests and responses with the given [DebugRecorder]
+    pub fn with_debug_recorder(mut self, debug_recorder: DebugRecorder) -> PublicClient {
+        self.debug_recorder = Some(debug_recorder);
+        self
+    }
+
+    /// Retry failed requests according to the given [RetryPolicy]
+    pub fn with_retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> PublicClient {
+        self.retry_policy = Some(Arc::new(retry_policy));
+        self
+    }
+
+    /// Read the current time from the given [Clock] instead of the [SystemClock]
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> PublicClient {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Returns the current UTC time of the attached [Clock], e.g. for timestamping episode actions
+    pub fn now(&self) -> NaiveDateTime {
+        self.clock.now()
+    }
+
+    /// Returns the exchanges recorded by the attached [DebugRecorder], oldest first
+    ///
+    /// Empty if no [DebugRecorder] is attached.
+    pub fn debug_log(&self) -> Vec<RecordedExchange> {
+        self.debug_recorder
+            .as_ref()
+            .map(DebugRecorder::exchanges)
+            .unwrap_or_default()
+    }
+
+    /// Talk to a server of the given [ServerProfile]
+    pub fn with_server_profile(mut self, server_profile: ServerProfile) -> PublicClient {
+        self.server_profile = server_profile;
+        self
+    }
+
+    /// Returns the configured [ServerProfile]
+    pub fn server_profile(&self) -> ServerProfile {
+        self.server_profile
+    }
+
+    /// Send all requests to the server at `base_url` instead of `https://gpodder.net`, e.g. a self-hosted server
+    ///
+    /// A trailing `/` is ignored. Servers mounted below a subpath can either include it in `base_url` or use [EndpointPaths]. Fails with [InvalidConfiguration](Error::InvalidConfiguration) if `base_url` isn't an absolute `http` or `https` URL without query and fragment.
+    pub fn with_base_url(mut self, base_url: &str) -> Result<PublicClient, Error> {
+        let url = Url::parse(base_url).map_err(|_| invalid_configuration("invalid base URL"))?;
+        if !matches!(url.scheme(), "http" | "https")
+            || !url.has_host()
+            || url.query().is_some()
+            || url.fragment().is_some()
+        {
+            return Err(invalid_configuration("invalid base URL"));
+        }
+        self.base_url = Arc::from(base_url.trim_end_matches('/'));
+        Ok(self)
+    }
+
+    /// Returns the configured [base URL](PublicClient::with_base_url)
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Reject all requests except `GET` and `HEAD` with [ReadOnly](Error::ReadOnly) before sending them
+    ///
+    /// Use this e.g. for monitoring dashboards which must never alter account state. Clones made afterwards are read-only as well.
+    pub fn with_read_only(mut self) -> PublicClient {
+        self.read_only = true;
+        self
+    }
+
+    /// Returns `true` if the client was made [read-only](PublicClient::with_read_only)
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Fail with [ResponseTooLarge](Error::ResponseTooLarge) instead of reading bodies larger than `max_response_size` bytes
+    ///
+    /// Protects against misbehaving servers and captive portals returning enormous bodies. The limit applies to all requests, including [logos](crate::directory::FetchLogo). Responses are unbounded by default.
+    pub fn with_max_response_size(mut self, max_response_size: u64) -> PublicClient {
+        self.max_response_size = Some(max_response_size);
+        self
+    }
+
+    /// Returns the configured [maximum response size](PublicClient::with_max_response_size)
+    pub fn max_response_size(&self) -> Option<u64> {
+        self.max_response_size
+    }
+
+    /// Send the header `name` with every request to the server which doesn't set it already, e.g. an API key required by a reverse proxy in front of a self-hosted server
+    ///
+    /// Only requests to the origin of the [base URL](PublicClient::with_base_url) carry the header, so it isn't leaked to other hosts, e.g. when fetching podcast logos. Setting a header again replaces its value. Values are [redacted](crate::redaction::redact_headers) in [debug logs](PublicClient::debug_log). Fails with [InvalidConfiguration](Error::InvalidConfiguration) if `name` or `value` isn't valid in an HTTP header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    ///
+    /// let client = PublicClient::new()
+    ///     .with_base_url("https://gpodder.example.org")?
+    ///     .with_default_header("X-Api-Key", "secret-key")?
+    ///     .with_default_query("tenant", "podcasts");
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn with_default_header(mut self, name: &str, value: &str) -> Result<PublicClient, Error> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| invalid_configuration("invalid header name"))?;
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|_| invalid_configuration("invalid header value"))?;
+        value.set_sensitive(true);
+        self.default_headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Append the query parameter `name` with `value` to the URL of every request to the server
+    ///
+    /// Like [default headers](PublicClient::with_default_header), it's only added to requests to the origin of the [base URL](PublicClient::with_base_url).
+    pub fn with_default_query(mut self, name: &str, value: &str) -> PublicClient {
+        self.default_query.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Use the given version of the API instead of [DEFAULT_API_VERSION]
+    ///
+    /// Only endpoints below `/api/<version>/` are affected.
+    pub fn with_api_version(mut self, api_version: u8) -> PublicClient {
+        self.api_version = api_version;
+        self
+    }
+
+    /// Insert the prefixes of the given [EndpointPaths] into the paths of all requests
+    pub fn with_endpoint_paths(mut self, endpoint_paths: EndpointPaths) -> PublicClient {
+        self.endpoint_paths = Arc::new(endpoint_paths);
+        self
+    }
+
+    /// Returns the configured [EndpointPaths]
+    pub fn endpoint_paths(&self) -> &EndpointPaths {
+        &self.endpoint_paths
+    }
+
+    pub(crate) fn api_url(&self, area: ApiArea, path: &str) -> String {
+        self.url(area, &format!("api/{}/{}", self.api_version, path))
+    }
+
+    pub(crate) fn url(&self, area: ApiArea, path: &str) -> String {
+        format!(
+            "{}{}/{}",
+            self.base_url,
+            self.endpoint_paths.prefix(area),
+            path
+        )
+    }
+
+    pub(crate) fn send(&self, mut request: RequestBuilder) -> Result<ApiResponse, Error> {
+        let retry_policy = match &self.retry_policy {
+            Some(retry_policy) => retry_policy,
+            None => return self.send_once(request.build()?),
+        };
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            // requests with streaming bodies can't be cloned and are sent once
+            let retry = request.try_clone();
+            let built = request.build()?;
+            let method = built.method().clone();
+            let url = built.url().clone();
+            let result = self.send_once(built);
+
+            let delay = retry_policy.retry_delay(&RetryAttempt {
+                method: &method,
+                url: &url,
+                attempt,
+                status: result
+                    .as_ref()
+                    .ok()
+                    .map(|response| response.status.as_u16()),
+                error: result.as_ref().err(),
+            });
+            match (retry, delay) {
+                (Some(retry), Some(delay)) => {
+                    thread::sleep(delay);
+                    request = retry;
+                }
+                _ => return result,
+            }
+        }
+    }
+
+    fn send_once(&self, mut request: Request) -> Result<ApiResponse, Error> {
+        if self.read_only && request.method() != Method::GET && request.method() != Method::HEAD {
+            return Err(Error::ReadOnly {
+                method: request.method().to_string(),
+                endpoint: request.url().path().to_owned(),
+            });
+        }
+
+        self.apply_defaults(&mut request);
+
+        // all JSON endpoints end with .json, other formats must not be negotiated away
+        if request.url().path().ends_with(".json") {
+            request
+                .headers_mut()
+                .entry(ACCEPT)
+                .or_insert(HeaderValue::from_static(JSON_CONTENT_TYPE));
+        }
+
+        let timed = self.latency_monitor.as_ref().map(|monitor| {
+            (
+                monitor,
+                request.method().clone(),
+                request.url().path().to_owned(),
+                Instant::now(),
+            )
+        });
+
+        let result = match &self.debug_recorder {
+            Some(debug_recorder) => {
+                let mut exchange = RecordedExchange::from_request(&request);
+                let result = self.execute(request);
+                exchange.record_result(&result);
+                debug_recorder.push(exchange);
+                result
+            }
+            None => self.execute(request),
+        };
+
+        if let Some((monitor, method, endpoint, start)) = timed {
+            let status = result
+                .as_ref()
+                .ok()
+                .map(|response| response.status.as_u16());
+            monitor.observe(&method, &endpoint, start.elapsed(), status);
+        }
+        result
+    }
+
+    /// Adds the [default headers](PublicClient::with_default_header) and [query parameters](PublicClient::with_default_query) to requests to the server
+    fn apply_defaults(&self, request: &mut Request) {
+        if !self.is_server_url(request.url()) {
+            return;
+        }
+        for (name, value) in &self.default_headers {
+            if !request.headers().contains_key(name) {
+                request.headers_mut().insert(name.clone(), value.clone());
+            }
+        }
+        if !self.default_query.is_empty() {
+            request
+                .url_mut()
+                .query_pairs_mut()
+                .extend_pairs(&self.default_query);
+        }
+    }
+
+    /// Returns whether `url` has the same origin as the [base URL](PublicClient::with_base_url), as opposed to e.g. logo or feed hosts
+    fn is_server_url(&self, url: &Url) -> bool {
+        Url::parse(&self.base_url).is_ok_and(|base_url| base_url.origin() == url.origin())
+    }
+
+    fn execute(&self, request: Request) -> Result<ApiResponse, Error> {
+        let endpoint = request.url().path().to_owned();
+        let server = self.is_server_url(request.url());
+        let start = Instant::now();
+        let timeout = |error: reqwest::Error| {
+            if error.is_timeout() {
+                Error::Timeout {
+                    endpoint: endpoint.clone(),
+                    elapsed: start.elapsed(),
+                }
+            } else {
+                Error::ReqwestError(error)
+            }
+        };
+
+        let response = self.client.execute(request).map_err(timeout)?;
+        let url = response.url().clone();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = match self.max_response_size {
+            Some(limit) => {
+                let too_large = || Error::ResponseTooLarge {
+                    endpoint: endpoint.clone(),
+                    limit,
+                };
+                // the declared length is checked first, so oversized bodies aren't even started
+                if response
+                    .content_length()
+                    .is_some_and(|length| length > limit)
+                {
+                    return Err(too_large());
+                }
+                let mut body = Vec::new();
+                response
+                    .take(limit.saturating_add(1))
+                    .read_to_end(&mut body)
+                    .map_err(|e| match e.kind() {
+                        io::ErrorKind::TimedOut => Error::Timeout {
+                            endpoint: endpoint.clone(),
+                            elapsed: start.elapsed(),
+                        },
+                        _ => Error::IoError(e),
+                    })?;
+                if u64::try_from(body.len()).map_or(true, |length| length > limit) {
+                    return Err(too_large());
+                }
+                body
+            }
+            None => response.bytes().map_err(timeout)?.to_vec(),
+        };
+
+        let response = ApiResponse {
+            url,
+            status,
+            headers,
+            body,
+        };
+        // a third-party logo or feed host being unavailable says nothing about the server
+        if server {
+            response.check_maintenance(self.now())
+        } else {
+            Ok(response)
         }
     }
 
-    pub(crate) fn get<U: IntoUrl>(&self, url: U) -> Result<Response, reqwest::Error> {
+    pub(crate) fn get<U: IntoUrl>(&self, url: U) -> Result<ApiResponse, Error> {
         let empty_slice: &[&String] = &[];
         self.get_with_query(url, empty_slice)
     }
@@ -45,15 +661,24 @@ impl PublicClient {
         &self,
         url: U,
         query_parameters: &[&T],
-    ) -> Result<Response, reqwest::Error> {
-        self.client
+    ) -> Result<ApiResponse, Error> {
+        let request = self
+            .client
             .get(url)
             .header(
                 reqwest::header::USER_AGENT,
                 &format!("{}/{}", PACKAGE_NAME, PACKAGE_VERSION),
             )
-            .query(query_parameters)
-            .send()
+            .query(query_parameters);
+        self.send(request)
+    }
+
+    pub(crate) fn head<U: IntoUrl>(&self, url: U) -> Result<ApiResponse, Error> {
+        let request = self.client.head(url).header(
+            reqwest::header::USER_AGENT,
+            &format!("{}/{}", PACKAGE_NAME, PACKAGE_VERSION),
+        );
+        self.send(request)
     }
 }
 
@@ -61,13 +686,184 @@ impl AuthenticatedClient {
     /// Create [AuthenticatedClient] locally
     pub fn new(username: &str, password: &str) -> AuthenticatedClient {
         AuthenticatedClient {
-            username: username.to_owned(),
-            password: password.to_owned(),
+            username: username.into(),
+            password: password.into(),
+            strictness: Strictness::default(),
+            authentication_mode: AuthenticationMode::default(),
+            credential_provider: None,
             public_client: PublicClient::new(),
         }
     }
 
-    pub(crate) fn get<U: IntoUrl>(&self, url: U) -> Result<Response, reqwest::Error> {
+    /// Create [AuthenticatedClient] locally, rejecting an empty username or password
+    ///
+    /// Returns [InvalidConfiguration](Error::InvalidConfiguration) instead of a client whose requests can only fail.
+    pub fn try_new(username: &str, password: &str) -> Result<AuthenticatedClient, Error> {
+        if username.trim().is_empty() {
+            return Err(invalid_configuration("username must not be empty"));
+        }
+        if password.is_empty() {
+            return Err(invalid_configuration("password must not be empty"));
+        }
+        Ok(AuthenticatedClient::new(username, password))
+    }
+
+    /// Use the given [Strictness] for validating uploads
+    pub fn with_strictness(mut self, strictness: Strictness) -> AuthenticatedClient {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Send credentials according to the given [AuthenticationMode]
+    pub fn with_authentication_mode(
+        mut self,
+        authentication_mode: AuthenticationMode,
+    ) -> AuthenticatedClient {
+        self.authentication_mode = authentication_mode;
+        self
+    }
+
+    /// Returns the configured [AuthenticationMode]
+    pub fn authentication_mode(&self) -> AuthenticationMode {
+        self.authentication_mode
+    }
+
+    /// Use the given version of the API instead of [DEFAULT_API_VERSION]
+    ///
+    /// Only endpoints below `/api/<version>/` are affected.
+    pub fn with_api_version(mut self, api_version: u8) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_api_version(api_version);
+        self
+    }
+
+    /// Insert the prefixes of the given [EndpointPaths] into the paths of all requests
+    pub fn with_endpoint_paths(mut self, endpoint_paths: EndpointPaths) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_endpoint_paths(endpoint_paths);
+        self
+    }
+
+    /// Returns the configured [EndpointPaths]
+    pub fn endpoint_paths(&self) -> &EndpointPaths {
+        self.public_client.endpoint_paths()
+    }
+
+    /// Talk to a server of the given [ServerProfile], also setting its [default strictness](ServerProfile::default_strictness)
+    pub fn with_server_profile(mut self, server_profile: ServerProfile) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_server_profile(server_profile);
+        self.strictness = server_profile.default_strictness();
+        self
+    }
+
+    /// Returns the configured [ServerProfile]
+    pub fn server_profile(&self) -> ServerProfile {
+        self.public_client.server_profile()
+    }
+
+    /// Send all requests to the server at `base_url`, see [PublicClient::with_base_url]
+    pub fn with_base_url(mut self, base_url: &str) -> Result<AuthenticatedClient, Error> {
+        self.public_client = self.public_client.with_base_url(base_url)?;
+        Ok(self)
+    }
+
+    /// Returns the configured [base URL](AuthenticatedClient::with_base_url)
+    pub fn base_url(&self) -> &str {
+        self.public_client.base_url()
+    }
+
+    /// Reject all requests except `GET` and `HEAD` with [ReadOnly](Error::ReadOnly), see [PublicClient::with_read_only]
+    pub fn with_read_only(mut self) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_read_only();
+        self
+    }
+
+    /// Returns `true` if the client was made [read-only](AuthenticatedClient::with_read_only)
+    pub fn is_read_only(&self) -> bool {
+        self.public_client.is_read_only()
+    }
+
+    /// Fail with [ResponseTooLarge](Error::ResponseTooLarge) instead of reading larger bodies, see [PublicClient::with_max_response_size]
+    pub fn with_max_response_size(mut self, max_response_size: u64) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_max_response_size(max_response_size);
+        self
+    }
+
+    /// Returns the configured [maximum response size](AuthenticatedClient::with_max_response_size)
+    pub fn max_response_size(&self) -> Option<u64> {
+        self.public_client.max_response_size()
+    }
+
+    /// Send the header `name` with every request to the server, see [PublicClient::with_default_header]
+    pub fn with_default_header(
+        mut self,
+        name: &str,
+        value: &str,
+    ) -> Result<AuthenticatedClient, Error> {
+        self.public_client = self.public_client.with_default_header(name, value)?;
+        Ok(self)
+    }
+
+    /// Append the query parameter `name` with `value` to the URL of every request to the server, see [PublicClient::with_default_query]
+    pub fn with_default_query(mut self, name: &str, value: &str) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_default_query(name, value);
+        self
+    }
+
+    /// Send requests with an HTTP client built from the given [ConnectionOptions]
+    pub fn with_connection_options(
+        self,
+        options: &ConnectionOptions,
+    ) -> Result<AuthenticatedClient, Error> {
+        Ok(self.with_http_client(options.build_http_client()?))
+    }
+
+    /// Send requests with the given preconfigured HTTP client, see [PublicClient::with_http_client]
+    pub fn with_http_client(mut self, client: Client) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_http_client(client);
+        self
+    }
+
+    /// Record requests and responses with the given [DebugRecorder]
+    pub fn with_debug_recorder(mut self, debug_recorder: DebugRecorder) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_debug_recorder(debug_recorder);
+        self
+    }
+
+    /// Retry failed requests according to the given [RetryPolicy]
+    pub fn with_retry_policy<P: RetryPolicy + 'static>(
+        mut self,
+        retry_policy: P,
+    ) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Read the current time from the given [Clock] instead of the [SystemClock]
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_clock(clock);
+        self
+    }
+
+    /// Returns the current UTC time of the attached [Clock], e.g. for timestamping episode actions
+    pub fn now(&self) -> NaiveDateTime {
+        self.public_client.now()
+    }
+
+    /// Returns the exchanges recorded by the attached [DebugRecorder], oldest first
+    ///
+    /// Empty if no [DebugRecorder] is attached.
+    pub fn debug_log(&self) -> Vec<RecordedExchange> {
+        self.public_client.debug_log()
+    }
+
+    pub(crate) fn api_url(&self, area: ApiArea, path: &str) -> String {
+        self.public_client.api_url(area, path)
+    }
+
+    pub(crate) fn url(&self, area: ApiArea, path: &str) -> String {
+        self.public_client.url(area, path)
+    }
+
+    pub(crate) fn get<U: IntoUrl>(&self, url: U) -> Result<ApiResponse, Error> {
         let empty_slice: &[&String] = &[];
         self.get_with_query(url, empty_slice)
     }
@@ -76,41 +872,41 @@ impl AuthenticatedClient {
         &self,
         url: U,
         query_parameters: &[&T],
-    ) -> Result<Response, reqwest::Error> {
-        self.public_client
+    ) -> Result<ApiResponse, Error> {
+        let request = self
+            .public_client
             .client
             .get(url)
-            .basic_auth(&self.username, Some(&self.password))
             .header(
                 reqwest::header::USER_AGENT,
                 &format!("{}/{}", PACKAGE_NAME, PACKAGE_VERSION),
             )
-            .query(query_parameters)
-            .send()
+            .query(query_parameters);
+        self.send_authenticated(request)
     }
 
     pub(crate) fn put<T: Serialize + ?Sized, U: IntoUrl>(
         &self,
         url: U,
         json: &T,
-    ) -> Result<Response, reqwest::Error> {
-        self.public_client
+    ) -> Result<ApiResponse, Error> {
+        let request = self
+            .public_client
             .client
             .put(url)
-            .basic_auth(&self.username, Some(&self.password))
             .header(
                 reqwest::header::USER_AGENT,
                 &format!("{}/{}", PACKAGE_NAME, PACKAGE_VERSION),
             )
-            .json(json)
-            .send()
+            .json(json);
+        self.send_authenticated(request)
     }
 
     pub(crate) fn post<T: Serialize + ?Sized, U: IntoUrl>(
         &self,
         url: U,
         json: &T,
-    ) -> Result<Response, reqwest::Error> {
+    ) -> Result<ApiResponse, Error> {
         let empty_slice: &[&String] = &[];
         self.post_with_query(url, json, empty_slice)
     }
@@ -120,31 +916,299 @@ impl AuthenticatedClient {
         url: U,
         json: &T,
         query_parameters: &[&V],
-    ) -> Result<Response, reqwest::Error> {
-        self.public_client
+    ) -> Result<ApiResponse, Error> {
+        let request = self
+            .public_client
             .client
             .post(url)
-            .basic_auth(&self.username, Some(&self.password))
             .header(
                 reqwest::header::USER_AGENT,
                 &format!("{}/{}", PACKAGE_NAME, PACKAGE_VERSION),
             )
             .query(query_parameters)
-            .json(json)
-            .send()
+            .json(json);
+        self.send_authenticated(request)
+    }
+
+    #[cfg(feature = "episodes")]
+    pub(crate) fn device_client(&self, device_id: &str) -> DeviceClient {
+        DeviceClient {
+            device_id: Arc::from(device_id),
+            authenticated_client: self.clone(),
+        }
+    }
+
+    fn send_authenticated(&self, request: RequestBuilder) -> Result<ApiResponse, Error> {
+        if let Some(credential_provider) = &self.credential_provider {
+            return self.send_with_credentials(credential_provider.as_ref(), request);
+        }
+
+        let retry = match self.authentication_mode {
+            AuthenticationMode::Preemptive => None,
+            // requests with streaming bodies can't be repeated after a challenge
+            AuthenticationMode::Challenge => request.try_clone(),
+        };
+        let retry = match retry {
+            Some(retry) => retry,
+            None => return self.public_client.send(self.basic_auth(request)),
+        };
+
+        let response = self.public_client.send(request)?;
+        if response.status != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let challenge = response
+            .headers
+            .get(WWW_AUTHENTICATE)
+            .and_then(|challenge| challenge.to_str().ok())
+            .unwrap_or_default();
+        let retry = self.answer_challenge(retry, challenge)?;
+        self.public_client.send(retry)
+    }
+
+    fn basic_auth(&self, request: RequestBuilder) -> RequestBuilder {
+        request.basic_auth(&self.username, Some(self.password.expose()))
+    }
+
+    #[cfg(not(feature = "digest-auth"))]
+    fn answer_challenge(
+        &self,
+        request: RequestBuilder,
+        _challenge: &str,
+    ) -> Result<RequestBuilder, Error> {
+        Ok(self.basic_auth(request))
+    }
+
+    #[cfg(feature = "digest-auth")]
+    fn answer_challenge(
+        &self,
+        request: RequestBuilder,
+        challenge: &str,
+    ) -> Result<RequestBuilder, Error> {
+        let is_digest = challenge
+            .get(..6)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("digest"));
+        if !is_digest {
+            return Ok(self.basic_auth(request));
+        }
+
+        let invalid_challenge =
+            |e: digest_auth::Error| io::Error::new(io::ErrorKind::InvalidData, e);
+        let built = match request.try_clone() {
+            Some(built) => built.build()?,
+            None => return Ok(self.basic_auth(request)),
+        };
+        let uri = match built.url().query() {
+            Some(query) => format!("{}?{}", built.url().path(), query),
+            None => built.url().path().to_owned(),
+        };
+        let context = digest_auth::AuthContext::new_with_method(
+            &*self.username,
+            self.password.expose(),
+            &uri,
+            built.body().and_then(reqwest::blocking::Body::as_bytes),
+            digest_auth::HttpMethod::from(built.method().as_str()),
+        );
+        let authorization = digest_auth::parse(challenge)
+            .and_then(|mut prompt| prompt.respond(&context))
+            .map_err(invalid_challenge)?;
+        Ok(request.header(AUTHORIZATION, authorization.to_header_string()))
     }
 }
 
 impl DeviceClient {
     /// Create [DeviceClient]
-    pub fn new(username: &str, password: &str, device_id: &str) -> DeviceClient {
+    pub fn new(username: &Username, password: &str, device_id: &DeviceId) -> DeviceClient {
         DeviceClient {
-            device_id: device_id.to_owned(),
-            authenticated_client: AuthenticatedClient::new(username, password),
+            device_id: device_id.as_str().into(),
+            authenticated_client: AuthenticatedClient::new(username.as_str(), password),
+        }
+    }
+
+    /// Create [DeviceClient], rejecting an empty username or password and device IDs not matching `[\w.-]+`
+    ///
+    /// Returns [InvalidConfiguration](Error::InvalidConfiguration) instead of a client whose requests can only fail.
+    pub fn try_new(
+        username: &Username,
+        password: &str,
+        device_id: &DeviceId,
+    ) -> Result<DeviceClient, Error> {
+        let authenticated_client = AuthenticatedClient::try_new(username.as_str(), password)?;
+        let device_id = device_id.as_str();
+        if device_id.is_empty()
+            || !device_id
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '-')
+        {
+            return Err(invalid_configuration(&format!(
+                "invalid device ID {:?}",
+                device_id
+            )));
         }
+        Ok(DeviceClient {
+            device_id: device_id.into(),
+            authenticated_client,
+        })
     }
 
-    pub(crate) fn get<U: IntoUrl>(&self, url: U) -> Result<Response, reqwest::Error> {
+    /// Use the given [Strictness] for validating uploads
+    pub fn with_strictness(mut self, strictness: Strictness) -> DeviceClient {
+        self.authenticated_client = self.authenticated_client.with_strictness(strictness);
+        self
+    }
+
+    /// Send credentials according to the given [AuthenticationMode]
+    pub fn with_authentication_mode(
+        mut self,
+        authentication_mode: AuthenticationMode,
+    ) -> DeviceClient {
+        self.authenticated_client = self
+            .authenticated_client
+            .with_authentication_mode(authentication_mode);
+        self
+    }
+
+    /// Returns the configured [AuthenticationMode]
+    pub fn authentication_mode(&self) -> AuthenticationMode {
+        self.authenticated_client.authentication_mode()
+    }
+
+    /// Use the given version of the API instead of [DEFAULT_API_VERSION]
+    ///
+    /// Only endpoints below `/api/<version>/` are affected.
+    pub fn with_api_version(mut self, api_version: u8) -> DeviceClient {
+        self.authenticated_client = self.authenticated_client.with_api_version(api_version);
+        self
+    }
+
+    /// Insert the prefixes of the given [EndpointPaths] into the paths of all requests
+    pub fn with_endpoint_paths(mut self, endpoint_paths: EndpointPaths) -> DeviceClient {
+        self.authenticated_client = self
+            .authenticated_client
+            .with_endpoint_paths(endpoint_paths);
+        self
+    }
+
+    /// Returns the configured [EndpointPaths]
+    pub fn endpoint_paths(&self) -> &EndpointPaths {
+        self.authenticated_client.endpoint_paths()
+    }
+
+    /// Talk to a server of the given [ServerProfile], also setting its [default strictness](ServerProfile::default_strictness)
+    pub fn with_server_profile(mut self, server_profile: ServerProfile) -> DeviceClient {
+        self.authenticated_client = self
+            .authenticated_client
+            .with_server_profile(server_profile);
+        self
+    }
+
+    /// Returns the configured [ServerProfile]
+    pub fn server_profile(&self) -> ServerProfile {
+        self.authenticated_client.server_profile()
+    }
+
+    /// Send all requests to the server at `base_url`, see [PublicClient::with_base_url]
+    pub fn with_base_url(mut self, base_url: &str) -> Result<DeviceClient, Error> {
+        self.authenticated_client = self.authenticated_client.with_base_url(base_url)?;
+        Ok(self)
+    }
+
+    /// Returns the configured [base URL](DeviceClient::with_base_url)
+    pub fn base_url(&self) -> &str {
+        self.authenticated_client.base_url()
+    }
+
+    /// Reject all requests except `GET` and `HEAD` with [ReadOnly](Error::ReadOnly), see [PublicClient::with_read_only]
+    pub fn with_read_only(mut self) -> DeviceClient {
+        self.authenticated_client = self.authenticated_client.with_read_only();
+        self
+    }
+
+    /// Returns `true` if the client was made [read-only](DeviceClient::with_read_only)
+    pub fn is_read_only(&self) -> bool {
+        self.authenticated_client.is_read_only()
+    }
+
+    /// Fail with [ResponseTooLarge](Error::ResponseTooLarge) instead of reading larger bodies, see [PublicClient::with_max_response_size]
+    pub fn with_max_response_size(mut self, max_response_size: u64) -> DeviceClient {
+        self.authenticated_client = self
+            .authenticated_client
+            .with_max_response_size(max_response_size);
+        self
+    }
+
+    /// Returns the configured [maximum response size](DeviceClient::with_max_response_size)
+    pub fn max_response_size(&self) -> Option<u64> {
+        self.authenticated_client.max_response_size()
+    }
+
+    /// Send the header `name` with every request to the server, see [PublicClient::with_default_header]
+    pub fn with_default_header(mut self, name: &str, value: &str) -> Result<DeviceClient, Error> {
+        self.authenticated_client = self.authenticated_client.with_default_header(name, value)?;
+        Ok(self)
+    }
+
+    /// Append the query parameter `name` with `value` to the URL of every request to the server, see [PublicClient::with_default_query]
+    pub fn with_default_query(mut self, name: &str, value: &str) -> DeviceClient {
+        self.authenticated_client = self.authenticated_client.with_default_query(name, value);
+        self
+    }
+
+    /// Send requests with an HTTP client built from the given [ConnectionOptions]
+    pub fn with_connection_options(
+        self,
+        options: &ConnectionOptions,
+    ) -> Result<DeviceClient, Error> {
+        Ok(self.with_http_client(options.build_http_client()?))
+    }
+
+    /// Send requests with the given preconfigured HTTP client, see [PublicClient::with_http_client]
+    pub fn with_http_client(mut self, client: Client) -> DeviceClient {
+        self.authenticated_client = self.authenticated_client.with_http_client(client);
+        self
+    }
+
+    /// Record requests and responses with the given [DebugRecorder]
+    pub fn with_debug_recorder(mut self, debug_recorder: DebugRecorder) -> DeviceClient {
+        self.authenticated_client = self
+            .authenticated_client
+            .with_debug_recorder(debug_recorder);
+        self
+    }
+
+    /// Retry failed requests according to the given [RetryPolicy]
+    pub fn with_retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> DeviceClient {
+        self.authenticated_client = self.authenticated_client.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Read the current time from the given [Clock] instead of the [SystemClock]
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> DeviceClient {
+        self.authenticated_client = self.authenticated_client.with_clock(clock);
+        self
+    }
+
+    /// Returns the current UTC time of the attached [Clock], e.g. for timestamping episode actions
+    pub fn now(&self) -> NaiveDateTime {
+        self.authenticated_client.now()
+    }
+
+    /// Returns the exchanges recorded by the attached [DebugRecorder], oldest first
+    ///
+    /// Empty if no [DebugRecorder] is attached.
+    pub fn debug_log(&self) -> Vec<RecordedExchange> {
+        self.authenticated_client.debug_log()
+    }
+
+    pub(crate) fn api_url(&self, area: ApiArea, path: &str) -> String {
+        self.authenticated_client.api_url(area, path)
+    }
+
+    pub(crate) fn url(&self, area: ApiArea, path: &str) -> String {
+        self.authenticated_client.url(area, path)
+    }
+
+    pub(crate) fn get<U: IntoUrl>(&self, url: U) -> Result<ApiResponse, Error> {
         self.authenticated_client.get(url)
     }
 
@@ -152,7 +1216,7 @@ impl DeviceClient {
         &self,
         url: U,
         query_parameters: &[&T],
-    ) -> Result<Response, reqwest::Error> {
+    ) -> Result<ApiResponse, Error> {
         self.authenticated_client
             .get_with_query(url, query_parameters)
     }
@@ -161,7 +1225,7 @@ impl DeviceClient {
         &self,
         url: U,
         json: &T,
-    ) -> Result<Response, reqwest::Error> {
+    ) -> Result<ApiResponse, Error> {
         self.authenticated_client.put(url, json)
     }
 
@@ -169,18 +1233,231 @@ impl DeviceClient {
         &self,
         url: U,
         json: &T,
-    ) -> Result<Response, reqwest::Error> {
+    ) -> Result<ApiResponse, Error> {
         self.authenticated_client.post(url, json)
     }
+}
 
-    pub(crate) fn post_with_query<T: Serialize + ?Sized, V: Serialize + ?Sized, U: IntoUrl>(
-        &self,
-        url: U,
-        json: &T,
-        query_parameters: &[&V],
-    ) -> Result<Response, reqwest::Error> {
-        self.authenticated_client
-            .post_with_query(url, json, query_parameters)
+impl ApiResponse {
+    /// Deserialize the body, failing with [UnexpectedStatus](Error::UnexpectedStatus) if the status isn't a success, e.g. for rejected credentials, or with [UnexpectedContentType](Error::UnexpectedContentType) if the server declared another content type or charset
+    ///
+    /// A missing `Content-Type` header is accepted, as some servers omit it.
+    pub(crate) fn json<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        self.check_status()?;
+        if let Some(content_type) = self.headers.get(CONTENT_TYPE) {
+            let content_type = String::from_utf8_lossy(content_type.as_bytes());
+            if !is_json_content_type(&content_type) {
+                return Err(Error::UnexpectedContentType {
+                    endpoint: self.url.path().to_owned(),
+                    content_type: content_type.into_owned(),
+                });
+            }
+        }
+
+        serde_json::from_slice(&self.body).map_err(|source| Error::InvalidResponse {
+            endpoint: self.url.path().to_owned(),
+            source,
+        })
+    }
+
+    /// Returns the body as text, failing with [UnexpectedStatus](Error::UnexpectedStatus) if the status isn't a success
+    pub(crate) fn text(&self) -> Result<String, Error> {
+        self.check_status()?;
+        Ok(String::from_utf8_lossy(&self.body).into_owned())
+    }
+
+    /// Error pages, e.g. of rejected credentials, must not be mistaken for data
+    fn check_status(&self) -> Result<(), Error> {
+        if self.status.is_success() {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedStatus {
+                url: redact_url(&self.url).to_string(),
+                status: self.status.as_u16(),
+            })
+        }
+    }
+
+    /// Fail with [Maintenance](Error::Maintenance) for `503 Service Unavailable` responses with a `Retry-After` header or an HTML page mentioning maintenance
+    ///
+    /// Other `503` responses are returned unchanged, as they may come from e.g. an overloaded proxy.
+    fn check_maintenance(self, now: NaiveDateTime) -> Result<ApiResponse, Error> {
+        if self.status != StatusCode::SERVICE_UNAVAILABLE {
+            return Ok(self);
+        }
+
+        let retry_after = self.headers.get(RETRY_AFTER);
+        let html = self.headers.get(CONTENT_TYPE).is_some_and(|content_type| {
+            String::from_utf8_lossy(content_type.as_bytes())
+                .to_ascii_lowercase()
+                .starts_with("text/html")
+        });
+        let maintenance_page = html
+            && String::from_utf8_lossy(&self.body)
+                .to_ascii_lowercase()
+                .contains("maintenance");
+        if retry_after.is_none() && !maintenance_page {
+            return Ok(self);
+        }
+
+        Err(Error::Maintenance {
+            endpoint: self.url.path().to_owned(),
+            retry_after: retry_after
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, now)),
+        })
+    }
+}
+
+/// Parses the value of a `Retry-After` header, which is either a number of seconds or an HTTP date
+///
+/// Dates in the past result in a zero duration.
+fn parse_retry_after(value: &str, now: NaiveDateTime) -> Option<Duration> {
+    let value = value.trim();
+    match value.parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => DateTime::parse_from_rfc2822(value)
+            .ok()
+            .map(|date| (date.naive_utc() - now).to_std().unwrap_or_default()),
+    }
+}
+
+/// Returns `true` for JSON media types like `application/json` and `application/ld+json` without a charset other than UTF-8
+fn is_json_content_type(content_type: &str) -> bool {
+    let mut parts = content_type.split(';').map(str::trim);
+    let media_type = parts.next().unwrap_or_default().to_ascii_lowercase();
+    let utf8 = parts
+        .filter_map(|parameter| {
+            let (name, value) = parameter.split_once('=')?;
+            if name.trim().eq_ignore_ascii_case("charset") {
+                Some(value.trim().trim_matches('"').to_ascii_lowercase())
+            } else {
+                None
+            }
+        })
+        .all(|charset| charset == "utf-8" || charset == "utf8");
+
+    (media_type == JSON_CONTENT_TYPE || media_type.ends_with("+json")) && utf8
+}
+
+impl ConnectionOptions {
+    /// Close connections which have been idle for longer than the given duration
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> ConnectionOptions {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Keep at most the given number of idle connections per host, 0 disables reuse
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> ConnectionOptions {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Talk HTTP/2 right away instead of negotiating the protocol
+    ///
+    /// Only use this if the server is known to support HTTP/2. Multiplexing all requests over a single connection saves connections when using a client from many threads.
+    pub fn with_http2_prior_knowledge(mut self) -> ConnectionOptions {
+        self.http2_prior_knowledge = true;
+        self
+    }
+
+    /// Follow at most the given number of redirects per request instead of 10
+    ///
+    /// Requests exceeding the limit fail with a [ReqwestError](Error::ReqwestError).
+    pub fn with_max_redirects(mut self, max: usize) -> ConnectionOptions {
+        self.max_redirects = Some(max);
+        self
+    }
+
+    /// Refuse redirects from `https` to `http` URLs
+    ///
+    /// Feed and logo URLs often redirect. Without this option, a redirect may silently downgrade the connection to unencrypted HTTP.
+    pub fn with_https_only_redirects(mut self) -> ConnectionOptions {
+        self.https_only_redirects = true;
+        self
+    }
+
+    /// Build an HTTP client with these options
+    pub fn build_http_client(&self) -> Result<Client, Error> {
+        let mut builder = Client::builder();
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        if self.max_redirects.is_some() || self.https_only_redirects {
+            let max_redirects = self.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS);
+            let https_only = self.https_only_redirects;
+            builder = builder.redirect(Policy::custom(move |attempt| {
+                let downgrade = attempt.url().scheme() == "http"
+                    && attempt
+                        .previous()
+                        .last()
+                        .is_some_and(|previous| previous.scheme() == "https");
+                if https_only && downgrade {
+                    attempt.error("redirect from https to http")
+                } else if attempt.previous().len() > max_redirects {
+                    attempt.error("too many redirects")
+                } else {
+                    attempt.follow()
+                }
+            }));
+        }
+        Ok(builder.build()?)
+    }
+}
+
+impl Default for PublicClient {
+    fn default() -> Self {
+        PublicClient::new()
+    }
+}
+
+/// Returns a clone of a process-wide [PublicClient], created on first use
+///
+/// All returned clients share one connection pool, so small tools can call this wherever they need a client instead of creating a new pool each time.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::directory::RetrieveTopTags;
+///
+/// let tags = mygpoclient::default_public_client().retrieve_top_tags(5)?;
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub fn default_public_client() -> PublicClient {
+    static DEFAULT_PUBLIC_CLIENT: OnceLock<PublicClient> = OnceLock::new();
+    DEFAULT_PUBLIC_CLIENT.get_or_init(PublicClient::new).clone()
+}
+
+/// see [AuthenticatedClient::try_new]
+impl TryFrom<(&str, &str)> for AuthenticatedClient {
+    type Error = Error;
+
+    fn try_from((username, password): (&str, &str)) -> Result<Self, Self::Error> {
+        AuthenticatedClient::try_new(username, password)
+    }
+}
+
+/// see [DeviceClient::try_new]
+impl TryFrom<(&Username, &str, &DeviceId)> for DeviceClient {
+    type Error = Error;
+
+    fn try_from(
+        (username, password, device_id): (&Username, &str, &DeviceId),
+    ) -> Result<Self, Self::Error> {
+        DeviceClient::try_new(username, password, device_id)
+    }
+}
+
+fn invalid_configuration(reason: &str) -> Error {
+    Error::InvalidConfiguration {
+        reason: reason.to_owned(),
     }
 }
 
@@ -195,3 +1472,446 @@ impl AsRef<AuthenticatedClient> for DeviceClient {
         &self.authenticated_client
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_json_content_type, parse_retry_after, ApiArea, ApiResponse, AuthenticatedClient,
+        AuthenticationMode, DeviceClient, EndpointPaths, PublicClient, ServerProfile, Strictness,
+    };
+    use crate::error::{Error, ErrorCode};
+    use crate::reference::{DeviceId, Username};
+    use chrono::NaiveDate;
+    use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, RETRY_AFTER};
+    use reqwest::StatusCode;
+    use std::convert::TryFrom;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+    use url::Url;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    fn device_client() -> DeviceClient {
+        DeviceClient::new(
+            &Username::from("username"),
+            "password",
+            &DeviceId::from("device"),
+        )
+    }
+
+    /// Serves `responses` to consecutive connections
+    ///
+    /// Returns the base URL of the server and a receiver of the heads of the served requests.
+    fn serve(responses: Vec<&'static str>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match stream.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buffer[..read]),
+                    }
+                }
+                let _ = sender.send(String::from_utf8_lossy(&request).into_owned());
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        (base_url, receiver)
+    }
+
+    /// Serves `response` to the first connection and returns the base URL of the server
+    fn serve_once(response: &'static str) -> String {
+        serve(vec![response]).0
+    }
+
+    #[test]
+    fn json_content_type() {
+        assert!(is_json_content_type("application/json"));
+        assert!(is_json_content_type("Application/JSON; charset=\"UTF-8\""));
+        assert!(is_json_content_type(
+            "application/vnd.api+json;charset=utf8"
+        ));
+        assert!(!is_json_content_type(
+            "application/json; charset=iso-8859-1"
+        ));
+        assert!(!is_json_content_type("text/html; charset=utf-8"));
+    }
+
+    #[test]
+    fn json_rejects_unsuccessful_status() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+        let response = ApiResponse {
+            url: Url::parse("https://gpodder.net/api/2/devices/username.json").unwrap(),
+            status: StatusCode::UNAUTHORIZED,
+            headers,
+            body: b"<html>Unauthorized</html>".to_vec(),
+        };
+
+        match response.json::<Vec<String>>() {
+            Err(error @ Error::UnexpectedStatus { status: 401, .. }) => {
+                assert_eq!(ErrorCode::Unauthorized, error.code())
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert!(response.text().is_err());
+    }
+
+    #[test]
+    fn json_rejects_unexpected_content_type() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+        let response = ApiResponse {
+            url: Url::parse("https://gpodder.net/api/2/devices/username.json").unwrap(),
+            status: StatusCode::OK,
+            headers,
+            body: b"<html></html>".to_vec(),
+        };
+
+        match response.json::<Vec<String>>() {
+            Err(Error::UnexpectedContentType {
+                endpoint,
+                content_type,
+            }) => {
+                assert_eq!("/api/2/devices/username.json", endpoint);
+                assert_eq!("text/html", content_type);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn read_only_client_rejects_mutating_requests() {
+        let client = device_client().with_read_only();
+        assert!(client.is_read_only());
+
+        let public_client = &client.authenticated_client.public_client;
+        let request = public_client
+            .client
+            .post("https://gpodder.net/api/2/subscriptions/username/device.json")
+            .build()
+            .unwrap();
+
+        match public_client.send_once(request) {
+            Err(Error::ReadOnly { method, endpoint }) => {
+                assert_eq!("POST", method);
+                assert_eq!("/api/2/subscriptions/username/device.json", endpoint);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn default_headers_and_query_are_added() {
+        let client = device_client()
+            .with_default_header("X-Api-Key", "secret-key")
+            .unwrap()
+            .with_default_query("tenant", "podcasts");
+        assert!(client
+            .clone()
+            .with_default_header("X-Api-Key", "line\nbreak")
+            .is_err());
+
+        let public_client = &client.authenticated_client.public_client;
+        let mut request = public_client
+            .client
+            .get("https://gpodder.net/api/2/devices/username.json?since=0")
+            .build()
+            .unwrap();
+        public_client.apply_defaults(&mut request);
+
+        assert_eq!(
+            "https://gpodder.net/api/2/devices/username.json?since=0&tenant=podcasts",
+            request.url().as_str()
+        );
+        let header = request.headers().get("x-api-key").unwrap();
+        assert_eq!("secret-key", header);
+        assert!(header.is_sensitive());
+
+        // e.g. a logo fetched by fetch_logo
+        let mut request = public_client
+            .client
+            .get("https://cdn.example.com/logo.png")
+            .build()
+            .unwrap();
+        public_client.apply_defaults(&mut request);
+
+        assert_eq!("https://cdn.example.com/logo.png", request.url().as_str());
+        assert!(request.headers().get("x-api-key").is_none());
+    }
+
+    #[test]
+    fn clients_are_send_and_sync() {
+        assert_send_sync::<PublicClient>();
+        assert_send_sync::<AuthenticatedClient>();
+        assert_send_sync::<DeviceClient>();
+    }
+
+    #[test]
+    fn api_url_uses_api_version() {
+        let client = device_client();
+        assert_eq!(
+            "https://gpodder.net/api/2/devices/username.json",
+            client.api_url(ApiArea::Devices, "devices/username.json")
+        );
+
+        let client = client.with_api_version(3);
+        assert_eq!(
+            "https://gpodder.net/api/3/devices/username.json",
+            client.api_url(ApiArea::Devices, "devices/username.json")
+        );
+
+        let client = client.with_base_url("http://localhost:8080/").unwrap();
+        assert_eq!(
+            "http://localhost:8080/api/3/devices/username.json",
+            client.api_url(ApiArea::Devices, "devices/username.json")
+        );
+    }
+
+    #[test]
+    fn reject_invalid_base_url() {
+        for base_url in &[
+            "gpodder.example",
+            "ftp://gpodder.example",
+            "https://gpodder.example/?key=value",
+            "https://gpodder.example/#top",
+        ] {
+            match PublicClient::new().with_base_url(base_url).err() {
+                Some(Error::InvalidConfiguration { .. }) => {}
+                error => panic!("unexpected error {:?} for {}", error, base_url),
+            }
+        }
+
+        let client = PublicClient::new()
+            .with_base_url("https://gpodder.example/mygpo/")
+            .unwrap();
+        assert_eq!("https://gpodder.example/mygpo", client.base_url());
+    }
+
+    #[test]
+    fn endpoint_paths_prefix_urls() {
+        let client = device_client().with_endpoint_paths(
+            EndpointPaths::new()
+                .with_prefix("gpodder/")
+                .with_area_prefix(ApiArea::Directory, ""),
+        );
+
+        assert_eq!(
+            "https://gpodder.net/gpodder/api/2/devices/username.json",
+            client.api_url(ApiArea::Devices, "devices/username.json")
+        );
+        assert_eq!(
+            "https://gpodder.net/gpodder/subscriptions/username.json",
+            client.url(ApiArea::Subscriptions, "subscriptions/username.json")
+        );
+        assert_eq!(
+            "https://gpodder.net/toplist/1.json",
+            client.url(ApiArea::Directory, "toplist/1.json")
+        );
+    }
+
+    #[test]
+    fn authentication_mode_is_preemptive_by_default() {
+        let client = device_client();
+        assert_eq!(AuthenticationMode::Preemptive, client.authentication_mode());
+
+        let client = client.with_authentication_mode(AuthenticationMode::Challenge);
+        assert_eq!(AuthenticationMode::Challenge, client.authentication_mode());
+        assert_eq!(
+            AuthenticationMode::Challenge,
+            client.clone().as_ref().authentication_mode()
+        );
+    }
+
+    #[test]
+    fn server_profile_sets_strictness() {
+        let client = AuthenticatedClient::new("username", "password")
+            .with_server_profile(ServerProfile::Nextcloud);
+
+        assert_eq!(ServerProfile::Nextcloud, client.server_profile());
+        assert_eq!(Strictness::Lenient, client.strictness);
+        assert!(client.server_profile().supports(ApiArea::Subscriptions));
+        assert!(!client.server_profile().supports(ApiArea::Directory));
+    }
+
+    #[test]
+    fn clone_shares_state() {
+        let client = device_client();
+        let clone = client.clone();
+
+        assert!(Arc::ptr_eq(&client.device_id, &clone.device_id));
+        assert!(Arc::ptr_eq(
+            &client.authenticated_client.username,
+            &clone.authenticated_client.username
+        ));
+        assert!(Arc::ptr_eq(
+            &client.authenticated_client.password.0,
+            &clone.authenticated_client.password.0
+        ));
+    }
+
+    #[test]
+    fn validating_constructors() {
+        assert!(AuthenticatedClient::try_from(("username", "password")).is_ok());
+        assert!(DeviceClient::try_from((
+            &Username::from("username"),
+            "password",
+            &DeviceId::from("phone-1.2_a")
+        ))
+        .is_ok());
+
+        let errors = vec![
+            AuthenticatedClient::try_new(" ", "password").err(),
+            DeviceClient::try_new(&Username::from("username"), "", &DeviceId::from("phone")).err(),
+            DeviceClient::try_new(&Username::from("username"), "password", &DeviceId::from(""))
+                .err(),
+            DeviceClient::try_new(
+                &Username::from("username"),
+                "password",
+                &DeviceId::from("my phone"),
+            )
+            .err(),
+        ];
+        for error in errors {
+            match error {
+                Some(Error::InvalidConfiguration { .. }) => {}
+                error => panic!("unexpected error {:?}", error),
+            }
+        }
+    }
+
+    #[test]
+    fn detect_maintenance() {
+        let now = NaiveDate::from_ymd(2015, 10, 21).and_hms(7, 0, 0);
+        let response = |headers| ApiResponse {
+            url: Url::parse("https://gpodder.net/api/2/devices/username.json").unwrap(),
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            headers,
+            body: b"<html><h1>Down for Maintenance</h1></html>".to_vec(),
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        match response(headers).check_maintenance(now) {
+            Err(Error::Maintenance {
+                endpoint,
+                retry_after,
+            }) => {
+                assert_eq!("/api/2/devices/username.json", endpoint);
+                assert_eq!(Some(Duration::from_secs(120)), retry_after);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+        assert!(matches!(
+            response(headers).check_maintenance(now),
+            Err(Error::Maintenance {
+                retry_after: None,
+                ..
+            })
+        ));
+
+        assert!(response(HeaderMap::new()).check_maintenance(now).is_ok());
+
+        assert_eq!(
+            Some(Duration::from_secs(28 * 60)),
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(0)),
+            parse_retry_after("Wed, 21 Oct 2015 06:28:00 GMT", now)
+        );
+        assert_eq!(None, parse_retry_after("soon", now));
+    }
+
+    #[test]
+    fn detect_maintenance_only_on_server() {
+        const MAINTENANCE: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 120\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+        let server = serve_once(MAINTENANCE);
+        let client = PublicClient::new().with_base_url(&server).unwrap();
+        match client.get(&format!("{}/api/2/devices/username.json", server)) {
+            Err(Error::Maintenance { retry_after, .. }) => {
+                assert_eq!(Some(Duration::from_secs(120)), retry_after)
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let logo_host = serve_once(MAINTENANCE);
+        let response = client.get(&format!("{}/logo.png", logo_host)).unwrap();
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status);
+    }
+
+    #[test]
+    fn reject_declared_length_above_max_response_size() {
+        let server = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 20\r\nConnection: close\r\n\r\n01234567890123456789");
+        let client = PublicClient::new().with_max_response_size(10);
+        match client.get(&format!("{}/toplist/10.json", server)) {
+            Err(Error::ResponseTooLarge { endpoint, limit }) => {
+                assert_eq!("/toplist/10.json", endpoint);
+                assert_eq!(10, limit);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn cut_off_undeclared_length_above_max_response_size() {
+        let server = serve(vec![
+            "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n01234567890123456789",
+            "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n0123456789",
+        ])
+        .0;
+        let client = PublicClient::new().with_max_response_size(10);
+        match client.get(&format!("{}/toplist/10.json", server)) {
+            Err(Error::ResponseTooLarge { limit, .. }) => assert_eq!(10, limit),
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let response = client.get(&format!("{}/toplist/10.json", server)).unwrap();
+        assert_eq!(b"0123456789".to_vec(), response.body);
+    }
+
+    #[cfg(feature = "digest-auth")]
+    #[test]
+    fn answer_digest_challenge() {
+        let (server, requests) = serve(vec![
+            "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Digest realm=\"gpodder\", qop=\"auth\", nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n[]",
+        ]);
+        let client = AuthenticatedClient::new("username", "password")
+            .with_base_url(&server)
+            .unwrap()
+            .with_authentication_mode(AuthenticationMode::Challenge);
+
+        let response = client
+            .get(&format!("{}/api/2/devices/username.json", server))
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status);
+
+        let challenged = requests.recv().unwrap().to_ascii_lowercase();
+        assert!(!challenged.contains("authorization:"));
+        let answered = requests.recv().unwrap();
+        let authorization = answered
+            .lines()
+            .find(|line| line.to_ascii_lowercase().starts_with("authorization:"))
+            .unwrap();
+        assert!(authorization.contains("Digest "));
+        assert!(authorization.contains("username=\"username\""));
+        assert!(authorization.contains("realm=\"gpodder\""));
+        assert!(authorization.contains("nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\""));
+        assert!(authorization.contains("uri=\"/api/2/devices/username.json\""));
+        assert!(!authorization.contains("password"));
+    }
+}
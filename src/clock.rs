@@ -0,0 +1,93 @@
+//! Source of the current time
+//!
+//! Clients read the current time from a [Clock], e.g. to timestamp episode actions with [now](crate::client::PublicClient::now). It defaults to the [SystemClock]; tests can attach a [ManualClock] with e.g. [with_clock](crate::client::AuthenticatedClient::with_clock) to control time deterministically. The `Scheduler` of the `sync` module reads due times from the clock of its client, and a `Journal` timestamps subscription changes with the clock attached to it.
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Source of the current UTC time
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the current UTC time
+    fn now(&self) -> NaiveDateTime;
+}
+
+/// [Clock] reading the system time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SystemClock;
+
+/// [Clock] which only advances when told to
+///
+/// Clones share the same time, so a test can keep a clone after attaching the clock to a client.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{Duration, NaiveDate};
+/// use mygpoclient::client::PublicClient;
+/// use mygpoclient::clock::ManualClock;
+///
+/// let clock = ManualClock::new(NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0));
+/// let client = PublicClient::default().with_clock(clock.clone());
+///
+/// clock.advance(Duration::minutes(5));
+/// assert_eq!(NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 5, 0), client.now());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock(Arc<Mutex<NaiveDateTime>>);
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Utc::now().naive_utc()
+    }
+}
+
+impl ManualClock {
+    /// Create [ManualClock] starting at the given UTC time
+    pub fn new(start: NaiveDateTime) -> ManualClock {
+        ManualClock(Arc::new(Mutex::new(start)))
+    }
+
+    /// Set the current time
+    pub fn set(&self, now: NaiveDateTime) {
+        *self.lock() = now;
+    }
+
+    /// Advance the current time by `duration`
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.lock();
+        *now += duration;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, NaiveDateTime> {
+        // a panic while holding the lock can't leave the time in an inconsistent state
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> NaiveDateTime {
+        *self.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clock, ManualClock};
+    use chrono::{Duration, NaiveDate};
+
+    #[test]
+    fn manual_clock_is_shared_by_clones() {
+        let start = NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0);
+        let clock = ManualClock::new(start);
+        let clone = clock.clone();
+
+        clone.advance(Duration::seconds(90));
+        assert_eq!(start + Duration::seconds(90), clock.now());
+
+        clock.set(start);
+        assert_eq!(start, clone.now());
+    }
+}
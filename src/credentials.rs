@@ -0,0 +1,151 @@
+//! Credentials supplied before each request, e.g. refreshed access tokens of servers experimenting with OAuth
+//!
+//! By default an [AuthenticatedClient] sends the username and password it was created with. Attach a [CredentialProvider] with [with_credential_provider](AuthenticatedClient::with_credential_provider) to supply the credentials of each request instead. The username the client was created with still determines the endpoints, e.g. `devices/{username}.json`.
+
+use crate::client::{ApiResponse, AuthenticatedClient, DeviceClient};
+use crate::error::Error;
+use crate::redaction::REDACTED;
+use reqwest::blocking::RequestBuilder;
+use reqwest::StatusCode;
+use std::fmt;
+use std::sync::Arc;
+
+/// Credentials of a single request
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Credentials {
+    /// HTTP Basic authentication, as used by gpodder.net
+    Basic {
+        /// username
+        username: String,
+        /// password
+        password: String,
+    },
+    /// bearer token, e.g. an OAuth access token
+    Bearer(String),
+}
+
+/// Hook supplying the [Credentials] of each request
+///
+/// Credentials are always sent with the request, regardless of the [AuthenticationMode](crate::client::AuthenticationMode) of the client.
+pub trait CredentialProvider: fmt::Debug + Send + Sync {
+    /// Returns the credentials for the next request, refreshing them first if necessary
+    ///
+    /// Errors are returned by the request without contacting the server.
+    fn credentials(&self) -> Result<Credentials, Error>;
+
+    /// Called after the server rejected the credentials with `401 Unauthorized`, e.g. to drop a cached token
+    ///
+    /// Returns `true` if the request should be sent once more with the next [credentials](CredentialProvider::credentials). Requests with streaming bodies are never repeated. The default implementation returns `false`.
+    fn invalidate(&self) -> bool {
+        false
+    }
+}
+
+/// Fixed credentials
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::AuthenticatedClient;
+/// use mygpoclient::credentials::Credentials;
+///
+/// let client = AuthenticatedClient::new("username", "")
+///     .with_credential_provider(Credentials::Bearer(String::from("access-token")));
+/// ```
+impl CredentialProvider for Credentials {
+    fn credentials(&self) -> Result<Credentials, Error> {
+        Ok(self.clone())
+    }
+}
+
+impl Credentials {
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Credentials::Basic { username, password } => {
+                request.basic_auth(username, Some(password))
+            }
+            Credentials::Bearer(token) => request.bearer_auth(token),
+        }
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credentials::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &REDACTED)
+                .finish(),
+            Credentials::Bearer(_) => f.debug_tuple("Bearer").field(&REDACTED).finish(),
+        }
+    }
+}
+
+impl AuthenticatedClient {
+    /// Consult the given [CredentialProvider] before each request instead of sending the password the client was created with
+    pub fn with_credential_provider<P: CredentialProvider + 'static>(
+        mut self,
+        credential_provider: P,
+    ) -> AuthenticatedClient {
+        self.credential_provider = Some(Arc::new(credential_provider));
+        self
+    }
+
+    pub(crate) fn send_with_credentials(
+        &self,
+        credential_provider: &dyn CredentialProvider,
+        request: RequestBuilder,
+    ) -> Result<ApiResponse, Error> {
+        let retry = request.try_clone();
+        let response = self
+            .public_client
+            .send(credential_provider.credentials()?.authorize(request))?;
+        match retry {
+            Some(retry)
+                if response.status == StatusCode::UNAUTHORIZED
+                    && credential_provider.invalidate() =>
+            {
+                self.public_client
+                    .send(credential_provider.credentials()?.authorize(retry))
+            }
+            _ => Ok(response),
+        }
+    }
+}
+
+impl DeviceClient {
+    /// Consult the given [CredentialProvider] before each request, see [AuthenticatedClient::with_credential_provider]
+    pub fn with_credential_provider<P: CredentialProvider + 'static>(
+        mut self,
+        credential_provider: P,
+    ) -> DeviceClient {
+        self.authenticated_client = self
+            .authenticated_client
+            .with_credential_provider(credential_provider);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CredentialProvider, Credentials};
+    use crate::client::AuthenticatedClient;
+
+    #[test]
+    fn debug_output_of_credentials_is_redacted() {
+        let basic = Credentials::Basic {
+            username: String::from("username"),
+            password: String::from("secret-password"),
+        };
+        let bearer = Credentials::Bearer(String::from("secret-token"));
+        let client = AuthenticatedClient::new("username", "password")
+            .with_credential_provider(bearer.clone());
+
+        assert!(format!("{:?}", basic).contains("username"));
+        assert!(!format!("{:?}", basic).contains("secret-password"));
+        assert!(!format!("{:?}", client).contains("secret-token"));
+        assert_eq!(bearer, bearer.credentials().unwrap());
+        assert!(!bearer.invalidate());
+    }
+}
@@ -0,0 +1,19 @@
+//! Lenient deserialization helpers for quirks of different server implementations
+
+use serde::de;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Timestamp {
+    Number(u64),
+    Text(String),
+}
+
+/// Deserialize a timestamp given either as number or as string containing a number
+pub(crate) fn timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match Timestamp::deserialize(deserializer)? {
+        Timestamp::Number(timestamp) => Ok(timestamp),
+        Timestamp::Text(timestamp) => timestamp.trim().parse().map_err(de::Error::custom),
+    }
+}
@@ -1,78 +1,61 @@
 //! [Device API](https://gpoddernet.readthedocs.io/en/latest/api/reference/devices.html)
 
-use crate::client::{AuthenticatedClient, DeviceClient};
-use crate::directory::Episode;
-use crate::episode::EpisodeActionType;
+use crate::client::{ApiArea, AuthenticatedClient, DeviceClient};
+use crate::episode::EpisodeAction;
 use crate::error::Error;
-use crate::subscription::Podcast;
+use crate::subscription::SubscriptionsOfDevice;
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt;
-use std::hash::{Hash, Hasher};
 use url::Url;
 
-/// Type of the [Device]
-#[serde(rename_all = "lowercase")]
-#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
-pub enum DeviceType {
-    /// desktop computer
-    Desktop,
-    /// portable computer
-    Laptop,
-    /// smartphone/tablet
-    Mobile,
-    /// server
-    Server,
-    /// any type of device, which doesn't fit another variant
-    Other,
-}
+pub use crate::protocol::devices::{Device, DeviceData, DeviceType, DeviceUpdates, EpisodeUpdate};
 
-/// Devices are used throughout the API to identify a device / a client application.
-#[derive(Deserialize, Serialize, Debug, Clone, Eq)]
-pub struct Device {
-    /// A device ID can be any string matching the regular expression `[\w.-]+`. The client application MUST generate a string to be used as its device ID, and SHOULD ensure that it is unique within the user account. A good approach is to combine the application name and the name of the host it is running on.
-    ///
-    /// If two applications share a device ID, this might cause subscriptions to be overwritten on the server side. While it is possible to retrieve a list of devices and their IDs from the server, this SHOULD NOT be used to let a user select an existing device ID.
-    pub id: String,
-    /// Human readable label for the device
-    pub caption: String,
-    /// Type of the device
-    #[serde(rename(serialize = "type", deserialize = "type"))]
-    pub device_type: DeviceType,
-    /// number of subscriptions for this device
-    pub subscriptions: u16,
+/// Position in the [DeviceUpdates] of a device, see [get_device_updates_page](GetDeviceUpdates::get_device_updates_page)
+///
+/// Serializable, so applications can persist it and resume later.
+///
+/// It only stores the `since` value of the first page and the number of changes already returned, not the changes themselves. Changes uploaded by other devices while paging can therefore shift the following pages, see [get_device_updates_page](GetDeviceUpdates::get_device_updates_page).
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct Continuation {
+    since: u64,
+    offset: usize,
 }
 
-#[derive(Serialize)]
-pub(crate) struct DeviceData {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub(crate) caption: Option<String>,
-    #[serde(rename(serialize = "type", deserialize = "type"))]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub(crate) device_type: Option<DeviceType>,
+/// Part of the [DeviceUpdates] of a device, as returned by [get_device_updates_page](GetDeviceUpdates::get_device_updates_page)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeviceUpdatesPage {
+    /// at most the requested number of changes
+    ///
+    /// Until the last page, the [timestamp](DeviceUpdates::timestamp) is the `since` value of the first page, so persisting it never skips changes.
+    pub updates: DeviceUpdates,
+    /// position of the next page; `None` for the last page
+    pub next: Option<Continuation>,
 }
 
-/// episode update information as used in [DeviceUpdates]
-#[derive(Serialize, Deserialize)]
-pub struct EpisodeUpdate {
-    /// episode
-    #[serde(flatten)]
-    pub episode: Episode,
-    /// latest episode action reported for this episode
-    pub status: Option<EpisodeActionType>,
+/// Outcome of [deregister](DeviceClient::deregister)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// None of the known servers deletes devices: gpodder.net only hides devices in its web interface, oPodSync (formerly micro-gpodder-server) only lists and updates devices, and Nextcloud gPodder Sync doesn't [support](crate::client::ServerProfile::supports) the device endpoints at all.
+#[non_exhaustive]
+pub enum Deregistration {
+    /// the server can't delete devices, so all subscriptions of the device were removed instead
+    ///
+    /// The device is still [listed](ListDevices::list_devices), and uploading to it again registers it anew.
+    SubscriptionsCleared,
 }
 
-/// updated information for a device as returned by [get_device_updates](GetDeviceUpdates::get_device_updates)
-#[derive(Serialize, Deserialize)]
-pub struct DeviceUpdates {
-    /// list of subscriptions to be added
-    pub add: Vec<Podcast>,
-    /// list of URLs to be unsubscribed
-    pub rem: Vec<Url>,
-    /// list of updated episodes
-    pub updates: Vec<EpisodeUpdate>,
-    /// current timestamp; for retrieving changes since the last query
-    pub timestamp: u64,
+/// Device whose [subscriptions](Device::subscriptions) count differs from its actual subscriptions, see [verify_device_consistency]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionCountMismatch {
+    /// device as listed by [list_devices](ListDevices::list_devices)
+    pub device: Device,
+    /// number of subscriptions according to the device list
+    pub reported: u32,
+    /// number of subscriptions returned by [get_subscriptions_of_device](SubscriptionsOfDevice::get_subscriptions_of_device)
+    pub actual: usize,
 }
 
 /// see [update_device_data](UpdateDeviceData::update_device_data)
@@ -90,9 +73,10 @@ pub trait UpdateDeviceData {
     /// use mygpoclient::client::DeviceClient;
     /// use mygpoclient::device::{DeviceType,UpdateDeviceData};
     ///
-    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
     /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
-    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
     /// #
     /// let client = DeviceClient::new(&username, &password, &deviceid);
     ///
@@ -149,9 +133,10 @@ pub trait GetDeviceUpdates {
     /// use mygpoclient::device::GetDeviceUpdates;
     /// # use std::time::{SystemTime, UNIX_EPOCH};
     ///
-    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
     /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
-    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
     /// # let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 86400;
     /// #
     /// let client = DeviceClient::new(&username, &password, &deviceid);
@@ -166,6 +151,78 @@ pub trait GetDeviceUpdates {
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/devices.html#get-device-updates)
     fn get_device_updates(&self, since: u64, include_actions: bool)
         -> Result<DeviceUpdates, Error>;
+
+    /// Poll Device Updates
+    ///
+    /// Same as [get_device_updates](GetDeviceUpdates::get_device_updates), but requests all updates if `since` is `None`. Pass the [timestamp](DeviceUpdates::timestamp) of the previous result as `since` on subsequent calls.
+    ///
+    /// Use [episode_actions](DeviceUpdates::episode_actions) to convert the updated episodes into [EpisodeAction]s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::DeviceClient;
+    /// use mygpoclient::device::GetDeviceUpdates;
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// let device_updates = client.poll_updates(None, true)?;
+    /// let next_device_updates = client.poll_updates(Some(device_updates.timestamp), true)?;
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn poll_updates(
+        &self,
+        since: Option<u64>,
+        include_actions: bool,
+    ) -> Result<DeviceUpdates, Error> {
+        self.get_device_updates(since.unwrap_or(0), include_actions)
+    }
+
+    /// Get Device Updates in pages of at most `max_changes` changes
+    ///
+    /// Paging is client-side only, e.g. to process and persist huge updates after a long time offline in steps. The API has neither a limit nor an upper bound for the time range of its response, so every page downloads all updates since the `since` value of the first page again and skips the changes of the previous pages. Neither the size of each response nor the total traffic is bounded: `n` pages download the full updates `n` times. Added podcasts come first, followed by removed podcasts and updated episodes. A `max_changes` of 0 is treated as 1.
+    ///
+    /// The pages aren't a snapshot: every page is cut from a new response, so changes made on the server between two pages, e.g. by other devices, shift the offsets of the following pages. Changes may then be returned twice or skipped, and skipped changes aren't returned again after saving the [timestamp](DeviceUpdates::timestamp) of the last page. Applications needing a consistent view should request all updates at once with [get_device_updates](GetDeviceUpdates::get_device_updates) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::DeviceClient;
+    /// use mygpoclient::device::{Continuation, GetDeviceUpdates};
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// let mut continuation = Continuation::since(0);
+    /// loop {
+    ///     let page = client.get_device_updates_page(continuation, true, 100)?;
+    ///     println!("{} podcasts added", page.updates.add.len());
+    ///     match page.next {
+    ///         Some(next) => continuation = next,
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn get_device_updates_page(
+        &self,
+        continuation: Continuation,
+        include_actions: bool,
+        max_changes: usize,
+    ) -> Result<DeviceUpdatesPage, Error> {
+        let updates = self.get_device_updates(continuation.since, include_actions)?;
+        Ok(continuation.page(updates, max_changes.max(1)))
+    }
 }
 
 impl UpdateDeviceData for DeviceClient {
@@ -179,9 +236,12 @@ impl UpdateDeviceData for DeviceClient {
             device_type: device_type.into(),
         };
         self.post(
-            &format!(
-                "https://gpodder.net/api/2/devices/{}/{}.json",
-                self.authenticated_client.username, self.device_id
+            &self.api_url(
+                ApiArea::Devices,
+                &format!(
+                    "devices/{}/{}.json",
+                    self.authenticated_client.username, self.device_id
+                ),
             ),
             &input,
         )?;
@@ -191,12 +251,8 @@ impl UpdateDeviceData for DeviceClient {
 
 impl ListDevices for AuthenticatedClient {
     fn list_devices(&self) -> Result<Vec<Device>, Error> {
-        Ok(self
-            .get(&format!(
-                "https://gpodder.net/api/2/devices/{}.json",
-                self.username
-            ))?
-            .json()?)
+        self.get(&self.api_url(ApiArea::Devices, &format!("devices/{}.json", self.username)))?
+            .json()
     }
 }
 
@@ -222,45 +278,237 @@ impl GetDeviceUpdates for DeviceClient {
         let query_parameter_include_actions = ("include_actions", include_actions_string.as_ref());
         query_parameters.push(&query_parameter_include_actions);
 
-        Ok(self
-            .get_with_query(
+        self.get_with_query(
+            &self.api_url(
+                ApiArea::Devices,
                 &format!(
-                    "https://gpodder.net/api/2/updates/{}/{}.json",
+                    "updates/{}/{}.json",
                     self.authenticated_client.username, self.device_id
                 ),
-                &query_parameters,
-            )?
-            .json()?)
+            ),
+            &query_parameters,
+        )?
+        .json()
     }
 }
 
-impl fmt::Display for DeviceType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+impl DeviceClient {
+    /// Deregister the device, e.g. when the user removes the account from the application
+    ///
+    /// No known server deletes devices through its API, so all subscriptions of the device are removed instead; episode actions and settings of the device remain. The returned [Deregistration] tells how the device was deregistered, so servers deleting devices can be supported later.
+    ///
+    /// Subscriptions of other devices are never changed, but the removed subscriptions show up as removals in the [subscription changes](crate::subscription::SubscriptionChanges) of synchronized devices.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use mygpoclient::client::DeviceClient;
+    /// use mygpoclient::device::Deregistration;
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// if client.deregister()? == Deregistration::SubscriptionsCleared {
+    ///     println!("device is still listed, but has no subscriptions");
+    /// }
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn deregister(&self) -> Result<Deregistration, Error> {
+        self.upload_subscriptions_of_device(&[])?;
+        Ok(Deregistration::SubscriptionsCleared)
+    }
+}
+
+/// Compare the [subscriptions](Device::subscriptions) count of each device of the user with its actual subscriptions
+///
+/// Returns the devices whose counts differ, which indicates drift on the server side. The [Display](fmt::Display) output of a [SubscriptionCountMismatch] is suitable for bug reports. Subscriptions are requested once per device, so this is a diagnostic tool rather than something to run on every synchronization.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::AuthenticatedClient;
+/// use mygpoclient::device::verify_device_consistency;
+///
+/// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+/// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+/// #
+/// let client = AuthenticatedClient::new(&username, &password);
+///
+/// for mismatch in verify_device_consistency(&client)? {
+///     eprintln!("{}", mismatch);
+/// }
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub fn verify_device_consistency(
+    client: &AuthenticatedClient,
+) -> Result<Vec<SubscriptionCountMismatch>, Error> {
+    find_mismatches(client.list_devices()?, |device| {
+        client
+            .device_client(&device.id)
+            .get_subscriptions_of_device()
+    })
+}
+
+fn find_mismatches<F>(
+    devices: Vec<Device>,
+    subscriptions: F,
+) -> Result<Vec<SubscriptionCountMismatch>, Error>
+where
+    F: Fn(&Device) -> Result<Vec<Url>, Error>,
+{
+    let mut mismatches = Vec::new();
+    for device in devices {
+        let actual = subscriptions(&device)?.len();
+        if usize::try_from(device.subscriptions) != Ok(actual) {
+            mismatches.push(SubscriptionCountMismatch {
+                reported: device.subscriptions,
+                device,
+                actual,
+            });
+        }
     }
+    Ok(mismatches)
+}
+
+impl DeviceUpdates {
+    /// Returns `true` if there are neither subscription changes nor updated episodes
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.rem.is_empty() && self.updates.is_empty()
+    }
+
+    /// Convert the updated episodes with a [status](EpisodeUpdate::status) into [EpisodeAction]s
+    pub fn episode_actions(&self) -> Vec<EpisodeAction> {
+        self.updates
+            .iter()
+            .filter_map(EpisodeUpdate::episode_action)
+            .collect()
+    }
+}
+
+impl Continuation {
+    /// Start at the updates since the given timestamp
+    pub fn since(since: u64) -> Continuation {
+        Continuation { since, offset: 0 }
+    }
+
+    fn page(self, updates: DeviceUpdates, max_changes: usize) -> DeviceUpdatesPage {
+        let start = self.offset;
+        let end = start.saturating_add(max_changes);
+        let total = updates.add.len() + updates.rem.len() + updates.updates.len();
+        let more = end < total;
+
+        let add_offset = 0;
+        let rem_offset = updates.add.len();
+        let updates_offset = rem_offset + updates.rem.len();
+        let page = DeviceUpdates {
+            add: slice(updates.add, add_offset, start, end),
+            rem: slice(updates.rem, rem_offset, start, end),
+            updates: slice(updates.updates, updates_offset, start, end),
+            timestamp: if more { self.since } else { updates.timestamp },
+        };
+
+        DeviceUpdatesPage {
+            updates: page,
+            next: if more {
+                Some(Continuation {
+                    since: self.since,
+                    offset: end,
+                })
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// Returns the items whose index in the concatenation of all changes is in `start..end`
+fn slice<T>(items: Vec<T>, offset: usize, start: usize, end: usize) -> Vec<T> {
+    items
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| (start..end).contains(&(offset + index)))
+        .map(|(_, item)| item)
+        .collect()
 }
 
-impl PartialEq for Device {
-    fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
+impl Device {
+    /// Compare devices by [last_seen](Device::last_seen), most recently seen first
+    ///
+    /// Devices without last-seen time are ordered last, so stale devices end up at the bottom of a list sorted with this function. Devices seen at the same time are ordered by ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::device::{Device, DeviceType};
+    /// use chrono::NaiveDate;
+    ///
+    /// let device = |id: &str, last_seen| Device {
+    ///     id: id.to_owned(),
+    ///     caption: id.to_owned(),
+    ///     device_type: DeviceType::Other,
+    ///     subscriptions: 0,
+    ///     last_seen,
+    ///     user_agent: None,
+    /// };
+    /// let mut devices = vec![
+    ///     device("unknown", None),
+    ///     device("old", Some(NaiveDate::from_ymd(2019, 1, 1).and_hms(0, 0, 0))),
+    ///     device("new", Some(NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0))),
+    /// ];
+    ///
+    /// devices.sort_by(Device::by_last_seen);
+    ///
+    /// let ids: Vec<&str> = devices.iter().map(|device| device.id.as_str()).collect();
+    /// assert_eq!(vec!["new", "old", "unknown"], ids);
+    /// ```
+    pub fn by_last_seen(a: &Device, b: &Device) -> Ordering {
+        match (a.last_seen, b.last_seen) {
+            (Some(a_last_seen), Some(b_last_seen)) => b_last_seen.cmp(&a_last_seen),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+        .then_with(|| a.id.cmp(&b.id))
+    }
+
+    /// Returns `true` if the device was last seen before `cutoff`
+    ///
+    /// Devices without last-seen time are never considered stale, as the server may just not provide it.
+    pub fn is_stale(&self, cutoff: NaiveDateTime) -> bool {
+        self.last_seen.is_some_and(|last_seen| last_seen < cutoff)
     }
 }
 
-impl Ord for Device {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.id.cmp(&other.id)
+impl EpisodeUpdate {
+    /// Convert into [EpisodeAction], if there is a [status](EpisodeUpdate::status)
+    pub fn episode_action(&self) -> Option<EpisodeAction> {
+        self.status.clone().map(|action| EpisodeAction {
+            podcast: self.episode.podcast_url.clone(),
+            episode: self.episode.url.clone(),
+            device: None,
+            action,
+            timestamp: None,
+        })
     }
 }
 
-impl PartialOrd for Device {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
     }
 }
 
-impl Hash for Device {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.id.hash(state);
+impl fmt::Display for SubscriptionCountMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "device {} reports {} subscriptions, but has {}",
+            self.device.id, self.reported, self.actual
+        )
     }
 }
 
@@ -272,10 +520,13 @@ impl fmt::Display for Device {
 
 #[cfg(test)]
 mod tests {
-    use super::{Device, DeviceType};
+    use super::{find_mismatches, Continuation, Device, DeviceType, DeviceUpdates};
+    use crate::episode::EpisodeActionType;
+    use chrono::NaiveDate;
     use std::cmp::Ordering;
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
+    use url::Url;
 
     #[test]
     fn equal_device_means_equal_hash() {
@@ -284,12 +535,16 @@ mod tests {
             caption: String::from("gPodder on my Lappy"),
             device_type: DeviceType::Laptop,
             subscriptions: 27,
+            last_seen: None,
+            user_agent: None,
         };
         let device2 = Device {
             id: String::from("abcdef"),
             caption: String::from("unnamed"),
             device_type: DeviceType::Other,
             subscriptions: 1,
+            last_seen: None,
+            user_agent: None,
         };
 
         assert_eq!(device1, device2);
@@ -311,12 +566,16 @@ mod tests {
             caption: String::from("gPodder on my Lappy"),
             device_type: DeviceType::Laptop,
             subscriptions: 27,
+            last_seen: None,
+            user_agent: None,
         };
         let device2 = Device {
             id: String::from("phone-au90f923023.203f9j23f"),
             caption: String::from("My Phone"),
             device_type: DeviceType::Mobile,
             subscriptions: 5,
+            last_seen: None,
+            user_agent: None,
         };
 
         assert_ne!(device1, device2);
@@ -331,6 +590,78 @@ mod tests {
         assert_ne!(hasher1.finish(), hasher2.finish());
     }
 
+    #[test]
+    fn device_optional_metadata() {
+        let devices: Vec<Device> = serde_json::from_str(
+            r#"[
+                {"id": "abcdef", "caption": "Laptop", "type": "laptop", "subscriptions": 27},
+                {"id": "phone", "caption": "Phone", "type": "mobile", "subscriptions": 5,
+                 "last_action": "2020-01-01T09:00:00", "user_agent": "gPodder/3.10.16"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(None, devices[0].last_seen);
+        assert_eq!(None, devices[0].user_agent);
+        assert_eq!(
+            Some(NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0)),
+            devices[1].last_seen
+        );
+        assert_eq!(Some("gPodder/3.10.16"), devices[1].user_agent.as_deref());
+        assert!(devices[1].is_stale(NaiveDate::from_ymd(2020, 6, 1).and_hms(0, 0, 0)));
+        assert!(!devices[0].is_stale(NaiveDate::from_ymd(2020, 6, 1).and_hms(0, 0, 0)));
+
+        let mut sorted = devices.clone();
+        sorted.sort_by(Device::by_last_seen);
+        assert_eq!("phone", sorted[0].id);
+    }
+
+    #[test]
+    fn deserialize_unparsable_last_seen_as_none() {
+        let devices: Vec<Device> = serde_json::from_str(
+            r#"[
+                {"id": "a", "caption": "A", "type": "laptop", "subscriptions": 1, "last_seen": "yesterday"},
+                {"id": "b", "caption": "B", "type": "laptop", "subscriptions": 1, "last_seen": 1577869200},
+                {"id": "c", "caption": "C", "type": "laptop", "subscriptions": 1, "last_seen": null},
+                {"id": "d", "caption": "D", "type": "laptop", "subscriptions": 1, "last_seen": "2020-01-01T10:00:00+01:00"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(None, devices[0].last_seen);
+        assert_eq!(None, devices[1].last_seen);
+        assert_eq!(None, devices[2].last_seen);
+        assert_eq!(
+            Some(NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0)),
+            devices[3].last_seen
+        );
+    }
+
+    #[test]
+    fn find_subscription_count_mismatches() {
+        let device = |id: &str, subscriptions| Device {
+            id: id.to_owned(),
+            caption: id.to_owned(),
+            device_type: DeviceType::Other,
+            subscriptions,
+            last_seen: None,
+            user_agent: None,
+        };
+        let devices = vec![device("laptop", 1), device("phone", 2)];
+
+        let mismatches = find_mismatches(devices, |_| {
+            Ok(vec![Url::parse("http://example.com/feed.rss").unwrap()])
+        })
+        .unwrap();
+
+        assert_eq!(1, mismatches.len());
+        assert_eq!("phone", mismatches[0].device.id);
+        assert_eq!(
+            "device phone reports 2 subscriptions, but has 1",
+            mismatches[0].to_string()
+        );
+    }
+
     #[test]
     fn display() {
         let device = Device {
@@ -338,6 +669,8 @@ mod tests {
             caption: String::from("gPodder on my Lappy"),
             device_type: DeviceType::Laptop,
             subscriptions: 27,
+            last_seen: None,
+            user_agent: None,
         };
 
         assert_eq!(
@@ -345,4 +678,98 @@ mod tests {
             format!("{}", device)
         );
     }
+
+    #[test]
+    fn device_updates_episode_actions() {
+        let device_updates: DeviceUpdates = serde_json::from_str(
+            r#"{
+                "add": [],
+                "rem": ["http://example.com/feed.rss"],
+                "updates": [
+                    {
+                        "title": "Episode 1",
+                        "url": "http://example.org/episode1.mp3",
+                        "podcast_title": "Podcast",
+                        "podcast_url": "http://example.org/feed.rss",
+                        "description": "",
+                        "website": null,
+                        "mygpo_link": "http://gpodder.net/episode/1",
+                        "released": "2020-01-15T17:00:00",
+                        "status": {"action": "play", "position": 120}
+                    },
+                    {
+                        "title": "Episode 2",
+                        "url": "http://example.org/episode2.mp3",
+                        "podcast_title": "Podcast",
+                        "podcast_url": "http://example.org/feed.rss",
+                        "description": "",
+                        "website": null,
+                        "mygpo_link": "http://gpodder.net/episode/2",
+                        "released": "2020-01-16T17:00:00",
+                        "status": null
+                    }
+                ],
+                "timestamp": 12347
+            }"#,
+        )
+        .unwrap();
+
+        assert!(!device_updates.is_empty());
+
+        let episode_actions = device_updates.episode_actions();
+        assert_eq!(1, episode_actions.len());
+        assert_eq!(
+            "http://example.org/episode1.mp3",
+            episode_actions[0].episode.as_str()
+        );
+        assert_eq!(
+            EpisodeActionType::Play {
+                position: 120,
+                started: None,
+                total: None
+            },
+            episode_actions[0].action
+        );
+    }
+
+    #[test]
+    fn deserialize_subscriptions_beyond_u16() {
+        let device: Device = serde_json::from_str(
+            r#"{"id":"abcdef","caption":"gPodder on my Lappy","type":"laptop","subscriptions":65536}"#,
+        )
+        .unwrap();
+        assert_eq!(65536, device.subscriptions);
+
+        let device: Device = serde_json::from_str(
+            r#"{"id":"abcdef","caption":"gPodder on my Lappy","type":"laptop","subscriptions":4294967295}"#,
+        )
+        .unwrap();
+        assert_eq!(u32::MAX, device.subscriptions);
+    }
+
+    #[test]
+    fn device_updates_are_split_into_pages() {
+        let device_updates: DeviceUpdates = serde_json::from_str(
+            r#"{
+                "add": [],
+                "rem": ["http://example.com/1.rss", "http://example.com/2.rss", "http://example.com/3.rss"],
+                "updates": [],
+                "timestamp": 1337
+            }"#,
+        )
+        .unwrap();
+
+        let first = Continuation::since(1000).page(device_updates.clone(), 2);
+        assert_eq!(2, first.updates.rem.len());
+        assert_eq!(1000, first.updates.timestamp);
+        let next = first.next.unwrap();
+
+        let last = next.page(device_updates, 2);
+        assert_eq!(
+            vec![Url::parse("http://example.com/3.rss").unwrap()],
+            last.updates.rem
+        );
+        assert_eq!(1337, last.updates.timestamp);
+        assert_eq!(None, last.next);
+    }
 }
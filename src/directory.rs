@@ -1,46 +1,76 @@
 //! [Directory API](https://gpoddernet.readthedocs.io/en/latest/api/reference/directory.html)
 
-use crate::client::{AuthenticatedClient, DeviceClient, PublicClient};
+use crate::batch::{BlockingBatch, DEFAULT_THREADS};
+use crate::client::{ApiArea, ApiResponse, AuthenticatedClient, DeviceClient, PublicClient};
 use crate::error::Error;
-use crate::subscription::Podcast;
-use chrono::NaiveDateTime;
-use serde::{Deserialize, Serialize};
+use crate::format::Format;
+use crate::redaction::redact_url;
+use crate::reference::{self, EpisodeRef};
+use crate::subscription;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::StatusCode;
+use serde::Serialize;
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
 use std::fmt;
-use std::hash::{Hash, Hasher};
+use std::hash::Hash;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use url::form_urlencoded::byte_serialize;
 use url::Url;
 
-/// Podcast tag
-#[derive(Deserialize, Serialize, Debug, Clone, Eq)]
-pub struct Tag {
-    /// more reader-friendly representation of tag
-    pub title: String,
-    /// actual tag, unique identifier
-    pub tag: String,
-    /// number of podcasts using this tag
-    pub usage: u16,
-}
-
-/// Podcast episode
-#[derive(Deserialize, Serialize, Debug, Clone, Eq)]
-pub struct Episode {
-    /// title
-    pub title: String,
-    /// media url
+pub use crate::protocol::directory::{Episode, Podcast, Tag, Toplist, ToplistIntoIter};
+
+/// Number of items requested by the first batch of [AllTags] and [PodcastsForTag] and by each page of [ToplistPages]
+pub const BATCH_SIZE: u8 = 50;
+
+/// Change of rank of a toplist [Podcast] compared to the week before
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum Movement {
+    /// moved up by the contained number of ranks
+    Up(u32),
+    /// moved down by the contained number of ranks
+    Down(u32),
+    /// same rank as the week before
+    Unchanged,
+    /// not in the toplist the week before
+    New,
+}
+
+/// Image downloaded by [fetch_logo](FetchLogo::fetch_logo)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Logo {
+    /// URL the image was downloaded from, after following redirects
     pub url: Url,
-    /// podcast title
-    pub podcast_title: String,
-    /// podcast feed url
-    pub podcast_url: Url,
-    /// description
-    pub description: String,
-    /// website
-    pub website: Option<Url>,
-    /// gpodder internal link
-    pub mygpo_link: Url,
-    /// release date
-    pub released: NaiveDateTime,
+    /// value of the `Content-Type` header, if any
+    pub content_type: Option<String>,
+    /// image data
+    pub data: Vec<u8>,
+}
+
+/// Logo of a podcast downloaded by [prefetch_logos]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum LogoSize {
+    /// [logo_url](subscription::Podcast::logo_url), falling back to the scaled logo
+    Original,
+    /// [scaled_logo_url](subscription::Podcast::scaled_logo_url) in the `scale_logo` size requested with the podcasts, falling back to the original logo
+    Scaled,
+}
+
+/// Health of a feed as determined by [check_feed](CheckFeed::check_feed)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum FeedStatus {
+    /// feed is known to gpodder.net or responded successfully
+    Alive,
+    /// feed has moved to the contained URL
+    Redirected(Url),
+    /// feed doesn't exist anymore or its server is unreachable
+    Dead,
+    /// feed is unknown to gpodder.net and wasn't requested
+    Unknown,
 }
 
 /// see [retrieve_top_tags](RetrieveTopTags::retrieve_top_tags)
@@ -83,9 +113,10 @@ pub trait RetrievePodcastsForTag {
     /// ```
     /// use mygpoclient::client::PublicClient;
     /// use mygpoclient::directory::RetrievePodcastsForTag;
+    /// use mygpoclient::reference::TagName;
     ///
     /// let max_results = 3;
-    /// let podcasts = PublicClient::default().retrieve_podcasts_for_tag("new", max_results)?;
+    /// let podcasts = PublicClient::default().retrieve_podcasts_for_tag(TagName::from("new"), max_results)?;
     /// assert!(podcasts.len() <= max_results as usize);
     ///
     /// # Ok::<(), mygpoclient::error::Error>(())
@@ -94,13 +125,19 @@ pub trait RetrievePodcastsForTag {
     /// # See also
     ///
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/directory.html#retrieve-podcasts-for-tag)
-    fn retrieve_podcasts_for_tag(&self, tag: &str, count: u8) -> Result<Vec<Podcast>, Error>;
+    fn retrieve_podcasts_for_tag(
+        &self,
+        tag: reference::TagName<'_>,
+        count: u8,
+    ) -> Result<Vec<subscription::Podcast>, Error>;
 }
 
 /// see [retrieve_podcast_data](RetrievePodcastData::retrieve_podcast_data)
 pub trait RetrievePodcastData {
     /// Returns information for the podcast with the given URL or Error if there is no podcast with this URL.
     ///
+    /// If the server redirects to the data of the podcast under its canonical feed URL, e.g. with `302 Found` or `303 See Other`, the redirect is followed and the [url](subscription::Podcast::url) of the result is the canonical URL. See [canonical_feed_url](RetrievePodcastData::canonical_feed_url) for correcting stored subscriptions.
+    ///
     /// # Parameters
     ///
     /// - `url`: podcast feed url
@@ -121,28 +158,81 @@ pub trait RetrievePodcastData {
     /// # See also
     ///
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/directory.html#retrieve-podcast-data)
-    fn retrieve_podcast_data(&self, url: Url) -> Result<Podcast, Error>;
+    fn retrieve_podcast_data(&self, url: Url) -> Result<subscription::Podcast, Error>;
+
+    /// Returns the canonical feed URL of the podcast, if it differs from `url`
+    ///
+    /// Clients can replace `url` in their stored subscriptions with the result, so later requests skip the redirect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::directory::RetrievePodcastData;
+    /// use url::Url;
+    ///
+    /// let url = Url::parse("http://feeds.feedburner.com/coverville").unwrap();
+    /// if let Some(canonical) = PublicClient::default().canonical_feed_url(url)? {
+    ///     println!("subscribe to {} instead", canonical);
+    /// }
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn canonical_feed_url(&self, url: Url) -> Result<Option<Url>, Error> {
+        let podcast = self.retrieve_podcast_data(url.clone())?;
+        Ok(Some(podcast.url).filter(|canonical| *canonical != url))
+    }
+
+    /// Same as [retrieve_podcast_data](RetrievePodcastData::retrieve_podcast_data) for many URLs, returning the results in input order
+    ///
+    /// At most [DEFAULT_THREADS] requests are sent at the same time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::directory::RetrievePodcastData;
+    /// use url::Url;
+    ///
+    /// let urls = vec![
+    ///     Url::parse("http://feeds.feedburner.com/coverville").unwrap(),
+    ///     Url::parse("http://goinglinux.com/mp3podcast.xml").unwrap(),
+    /// ];
+    /// for podcast in PublicClient::default().retrieve_podcast_data_bulk(&urls) {
+    ///     println!("{}", podcast?);
+    /// }
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn retrieve_podcast_data_bulk(&self, urls: &[Url]) -> Vec<Result<subscription::Podcast, Error>>
+    where
+        Self: Sync,
+    {
+        let mut batch = BlockingBatch::new(DEFAULT_THREADS);
+        for url in urls {
+            batch.push(move || self.retrieve_podcast_data(url.clone()));
+        }
+        batch.run()
+    }
 }
 
 /// see [retrieve_episode_data](RetrieveEpisodeData::retrieve_episode_data)
 pub trait RetrieveEpisodeData {
-    /// Returns information for the episode with the given url that belongs to the given podcast
-    ///
-    /// # Parameters
+    /// Returns information for the given episode
     ///
-    /// - `url`: media url of episode
-    /// - `podcast`: podcast feed url
+    /// Redirects to the canonical URLs of the episode or its podcast are followed like in [retrieve_podcast_data](RetrievePodcastData::retrieve_podcast_data); [url](Episode::url) and [podcast_url](Episode::podcast_url) of the result are the canonical URLs.
     ///
     /// # Examples
     ///
     /// ```
     /// use mygpoclient::client::PublicClient;
     /// use mygpoclient::directory::RetrieveEpisodeData;
+    /// use mygpoclient::reference::EpisodeRef;
     /// use url::Url;
     ///
     /// let url = Url::parse("https://www.podtrac.com/pts/redirect.mp3/audio.wnyc.org/otm/otm011520_podextra.mp3").unwrap();
     /// let podcast = Url::parse("http://feeds.wnyc.org/onthemedia?format=xml").unwrap();
-    /// let episode = PublicClient::default().retrieve_episode_data(url, podcast)?;
+    /// let episode = PublicClient::default().retrieve_episode_data(EpisodeRef::new(podcast, url))?;
     ///
     /// # Ok::<(), mygpoclient::error::Error>(())
     /// ```
@@ -150,7 +240,7 @@ pub trait RetrieveEpisodeData {
     /// # See also
     ///
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/directory.html#retrieve-episode-data)
-    fn retrieve_episode_data(&self, podcast: Url, url: Url) -> Result<Episode, Error>;
+    fn retrieve_episode_data(&self, episode: EpisodeRef) -> Result<Episode, Error>;
 }
 
 /// see [podcast_toplist](PodcastToplist::podcast_toplist)
@@ -178,7 +268,31 @@ pub trait PodcastToplist {
     /// # See also
     ///
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/directory.html#podcast-toplist)
-    fn podcast_toplist(&self, number: u8, scale_logo: Option<u16>) -> Result<Vec<Podcast>, Error>;
+    fn podcast_toplist(&self, number: u8, scale_logo: Option<u16>) -> Result<Toplist, Error> {
+        self.podcast_toplist_page(number, 0, scale_logo)
+    }
+
+    /// Returns list of top podcasts, skipping the first `offset` podcasts
+    ///
+    /// The `offset` parameter is only supported by some deployments. If the server evidently ignored it, i.e. the first returned podcast has a [position](Podcast::position) of at most `offset`, the skipped podcasts are removed from the response, so fewer than `number` podcasts are returned. Servers which send no positions can't be checked this way, so on pages with an `offset` their podcasts keep a [position](Podcast::position) of `None` instead of a possibly wrong rank. Only the first page derives missing positions, as it starts at rank 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::directory::PodcastToplist;
+    ///
+    /// let podcasts = PublicClient::default().podcast_toplist_page(10, 10, None)?;
+    /// assert!(podcasts.len() <= 10);
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn podcast_toplist_page(
+        &self,
+        number: u8,
+        offset: u32,
+        scale_logo: Option<u16>,
+    ) -> Result<Toplist, Error>;
 }
 
 /// see [podcast_search](PodcastSearch::podcast_search)
@@ -205,17 +319,282 @@ pub trait PodcastSearch {
     /// # See also
     ///
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/directory.html#podcast-search)
-    fn podcast_search(&self, q: &str, scale_logo: Option<u16>) -> Result<Vec<Podcast>, Error>;
+    fn podcast_search(
+        &self,
+        q: &str,
+        scale_logo: Option<u16>,
+    ) -> Result<Vec<subscription::Podcast>, Error>;
+
+    /// Same as [podcast_search](PodcastSearch::podcast_search), but returns the unparsed response body in the given [Format]
+    ///
+    /// Supported formats are [Json](Format::Json), [Jsonp](Format::Jsonp), [Opml](Format::Opml), [Txt](Format::Txt) and [Xml](Format::Xml).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::directory::PodcastSearch;
+    /// use mygpoclient::format::Format;
+    ///
+    /// let opml = PublicClient::default().podcast_search_raw("raumzeit", None, &Format::Opml)?;
+    /// assert!(opml.contains("<opml"));
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    ///
+    /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/directory.html#podcast-search)
+    fn podcast_search_raw(
+        &self,
+        q: &str,
+        scale_logo: Option<u16>,
+        format: &Format,
+    ) -> Result<String, Error>;
+
+    /// Same as [podcast_search](PodcastSearch::podcast_search), but with a typed [SearchQuery]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::directory::{PodcastSearch, SearchQuery};
+    ///
+    /// let query = SearchQuery::new().title("raumzeit");
+    /// let podcasts = PublicClient::default().podcast_search_query(&query, None)?;
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn podcast_search_query(
+        &self,
+        query: &SearchQuery,
+        scale_logo: Option<u16>,
+    ) -> Result<Vec<subscription::Podcast>, Error> {
+        self.podcast_search(&query.to_string(), scale_logo)
+    }
+}
+
+/// Search query for [podcast_search_query](PodcastSearch::podcast_search_query)
+///
+/// Serializes to the `q` parameter. Plain text is searched as is, while the `title:` and `author:` operators restrict terms to these fields where the server supports it. Values containing whitespace, quotes, colons or backslashes are quoted and escaped, so user input can't inject operators.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::directory::SearchQuery;
+///
+/// let query = SearchQuery::new()
+///     .text("linux news")
+///     .author("Tim Pritlove")
+///     .title("say \"hi\"");
+/// assert_eq!(
+///     r#"linux news author:"Tim Pritlove" title:"say \"hi\"""#,
+///     query.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SearchQuery {
+    terms: Vec<String>,
+}
+
+impl SearchQuery {
+    /// Create empty [SearchQuery]
+    pub fn new() -> SearchQuery {
+        Default::default()
+    }
+
+    /// Search for each whitespace-separated word of `text` in all fields
+    pub fn text(mut self, text: &str) -> SearchQuery {
+        self.terms
+            .extend(text.split_whitespace().map(quote_search_term));
+        self
+    }
+
+    /// Search for `title` in podcast titles
+    pub fn title(self, title: &str) -> SearchQuery {
+        self.operator("title", title)
+    }
+
+    /// Search for `author` in podcast authors
+    pub fn author(self, author: &str) -> SearchQuery {
+        self.operator("author", author)
+    }
+
+    /// Returns `true` if the query has no terms
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    fn operator(mut self, operator: &str, value: &str) -> SearchQuery {
+        let value = value.trim();
+        if !value.is_empty() {
+            self.terms
+                .push(format!("{}:{}", operator, quote_search_term(value)));
+        }
+        self
+    }
+}
+
+impl fmt::Display for SearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.terms.join(" "))
+    }
+}
+
+fn quote_search_term(term: &str) -> String {
+    if term
+        .chars()
+        .any(|c| c.is_whitespace() || c == '"' || c == ':' || c == '\\')
+    {
+        format!("\"{}\"", term.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        term.to_owned()
+    }
+}
+
+/// see [fetch_logo](FetchLogo::fetch_logo)
+pub trait FetchLogo {
+    /// Download the logo of a podcast, e.g. from [logo_url](subscription::Podcast::logo_url)
+    ///
+    /// Redirects are followed according to the [redirect options](crate::client::ConnectionOptions::with_max_redirects) of the client. The final URL is returned, so it can be stored instead of the redirecting one.
+    ///
+    /// Returns [UnexpectedStatus](Error::UnexpectedStatus) if the server doesn't respond with a success status.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::directory::{FetchLogo, RetrievePodcastData};
+    /// use url::Url;
+    ///
+    /// let client = PublicClient::default();
+    /// let podcast = client.retrieve_podcast_data(Url::parse("http://feeds.feedburner.com/linuxoutlaws").unwrap())?;
+    /// if let Some(logo_url) = podcast.logo_url {
+    ///     let logo = client.fetch_logo(logo_url)?;
+    ///     println!("{} bytes from {}", logo.data.len(), logo.url);
+    /// }
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn fetch_logo(&self, url: Url) -> Result<Logo, Error>;
+}
+
+/// Download the logos of `podcasts` in parallel, e.g. to populate a UI on the first run
+///
+/// At most `concurrency` logos are downloaded at the same time; a `concurrency` of 0 is treated as 1. Returns the logo or the error of each podcast with a logo URL, keyed by feed URL. Podcasts without logo are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::PublicClient;
+/// use mygpoclient::directory::{prefetch_logos, LogoSize, PodcastToplist};
+///
+/// let client = PublicClient::default();
+/// let podcasts: Vec<_> = client
+///     .podcast_toplist(10, Some(128))?
+///     .into_iter()
+///     .map(|entry| entry.podcast)
+///     .collect();
+///
+/// let logos = prefetch_logos(&client, &podcasts, LogoSize::Scaled, 4);
+/// for (feed, logo) in &logos {
+///     if let Ok(logo) = logo {
+///         println!("{}: {} bytes", feed, logo.data.len());
+///     }
+/// }
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub fn prefetch_logos<C: FetchLogo + Sync>(
+    client: &C,
+    podcasts: &[subscription::Podcast],
+    size: LogoSize,
+    concurrency: usize,
+) -> HashMap<Url, Result<Logo, Error>> {
+    let logos = Mutex::new(HashMap::new());
+    prefetch_logos_with(client, podcasts, size, concurrency, |podcast, logo| {
+        // a panicking sink is propagated by the batch, the map itself stays consistent
+        logos
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(podcast.url.clone(), logo);
+    });
+    logos
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Same as [prefetch_logos], but hands each logo to `sink` as soon as it's downloaded, e.g. to show it or to write it to a cache
+///
+/// `sink` is called from the download threads.
+pub fn prefetch_logos_with<C, F>(
+    client: &C,
+    podcasts: &[subscription::Podcast],
+    size: LogoSize,
+    concurrency: usize,
+    sink: F,
+) where
+    C: FetchLogo + Sync,
+    F: Fn(&subscription::Podcast, Result<Logo, Error>) + Sync,
+{
+    let sink = &sink;
+    let mut batch = BlockingBatch::new(concurrency);
+    for podcast in podcasts {
+        if let Some(url) = size.logo_url(podcast) {
+            batch.push(move || {
+                sink(podcast, client.fetch_logo(url.clone()));
+                Ok(())
+            });
+        }
+    }
+    batch.run();
+}
+
+impl LogoSize {
+    fn logo_url(self, podcast: &subscription::Podcast) -> Option<&Url> {
+        match self {
+            LogoSize::Original => podcast
+                .logo_url
+                .as_ref()
+                .or(podcast.scaled_logo_url.as_ref()),
+            LogoSize::Scaled => podcast
+                .scaled_logo_url
+                .as_ref()
+                .or(podcast.logo_url.as_ref()),
+        }
+    }
+}
+
+/// see [check_feed](CheckFeed::check_feed)
+pub trait CheckFeed {
+    /// Classify a feed as alive, redirected or dead
+    ///
+    /// gpodder.net is asked for the [podcast data](RetrievePodcastData::retrieve_podcast_data) of the feed first. A different feed URL in its answer means the feed has been redirected. If `probe_feed` is `true`, a `HEAD` request is additionally sent to the feed itself: redirects are followed, `404 Not Found` and `410 Gone` mean the feed is dead, as does an unreachable server. Other responses, e.g. `405 Method Not Allowed`, leave the verdict to gpodder.net.
+    ///
+    /// Errors while contacting gpodder.net are returned, so a missing network connection doesn't mark feeds as dead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::directory::{CheckFeed, FeedStatus};
+    /// use url::Url;
+    ///
+    /// let url = Url::parse("http://feeds.feedburner.com/linuxoutlaws").unwrap();
+    /// match PublicClient::default().check_feed(url, true)? {
+    ///     FeedStatus::Redirected(new_url) => println!("moved to {}", new_url),
+    ///     FeedStatus::Dead => println!("dead"),
+    ///     _ => {}
+    /// }
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn check_feed(&self, url: Url, probe_feed: bool) -> Result<FeedStatus, Error>;
 }
 
 impl RetrieveTopTags for PublicClient {
     fn retrieve_top_tags(&self, count: u8) -> Result<Vec<Tag>, Error> {
-        Ok(self
-            .get(&format!(
-                "https://gpodder.net/api/2/tags/{}.json",
-                count.to_string()
-            ))?
-            .json()?)
+        self.top_tags(u32::from(count))
     }
 }
 
@@ -232,107 +611,610 @@ impl RetrieveTopTags for DeviceClient {
 }
 
 impl RetrievePodcastsForTag for PublicClient {
-    fn retrieve_podcasts_for_tag(&self, tag: &str, count: u8) -> Result<Vec<Podcast>, Error> {
-        let tag_urlencoded: String = byte_serialize(tag.as_bytes()).collect();
-        Ok(self
-            .get(&format!(
-                "https://gpodder.net/api/2/tag/{}/{}.json",
-                tag_urlencoded,
-                count.to_string()
-            ))?
-            .json()?)
+    fn retrieve_podcasts_for_tag(
+        &self,
+        tag: reference::TagName<'_>,
+        count: u8,
+    ) -> Result<Vec<subscription::Podcast>, Error> {
+        self.podcasts_for_tag(tag, u32::from(count))
     }
 }
 
 impl RetrievePodcastsForTag for AuthenticatedClient {
-    fn retrieve_podcasts_for_tag(&self, tag: &str, count: u8) -> Result<Vec<Podcast>, Error> {
+    fn retrieve_podcasts_for_tag(
+        &self,
+        tag: reference::TagName<'_>,
+        count: u8,
+    ) -> Result<Vec<subscription::Podcast>, Error> {
         self.public_client.retrieve_podcasts_for_tag(tag, count)
     }
 }
 
 impl RetrievePodcastsForTag for DeviceClient {
-    fn retrieve_podcasts_for_tag(&self, tag: &str, count: u8) -> Result<Vec<Podcast>, Error> {
+    fn retrieve_podcasts_for_tag(
+        &self,
+        tag: reference::TagName<'_>,
+        count: u8,
+    ) -> Result<Vec<subscription::Podcast>, Error> {
         self.authenticated_client
             .retrieve_podcasts_for_tag(tag, count)
     }
 }
 
+impl PublicClient {
+    fn top_tags(&self, count: u32) -> Result<Vec<Tag>, Error> {
+        self.get(&self.api_url(ApiArea::Directory, &format!("tags/{}.json", count)))?
+            .json()
+    }
+
+    fn podcasts_for_tag(
+        &self,
+        tag: reference::TagName<'_>,
+        count: u32,
+    ) -> Result<Vec<subscription::Podcast>, Error> {
+        let tag_urlencoded: String = byte_serialize(tag.as_str().as_bytes()).collect();
+        let podcasts: Vec<subscription::Podcast> = self
+            .get(&self.api_url(
+                ApiArea::Directory,
+                &format!("tag/{}/{}.json", tag_urlencoded, count),
+            ))?
+            .json()?;
+        Ok(podcasts
+            .into_iter()
+            .map(|podcast| self.rewrite_podcast(podcast))
+            .collect())
+    }
+
+    /// Lazily iterate over all top tags
+    ///
+    /// The endpoint doesn't support offsets, so tags are requested in batches starting with [BATCH_SIZE] tags and doubling in size, each containing the tags of the previous batches again. Iteration ends at the real end of the server's results, i.e. after the first batch with fewer tags than requested or without new tags. Each tag is yielded once. Iteration stops after the first error.
+    ///
+    /// The server may cap the number of returned tags: gpodder.net returns at most 100, so iteration ends there even if the server knows more tags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    ///
+    /// let client = PublicClient::default();
+    /// let tags = client.iter_all_tags().take(60).collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(60, tags.len());
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn iter_all_tags(&self) -> AllTags<'_> {
+        AllTags(Batches::new(Box::new(move |count| self.top_tags(count))))
+    }
+
+    /// Lazily iterate over all podcasts for the given tag
+    ///
+    /// Works like [iter_all_tags](PublicClient::iter_all_tags), including the cap of the server: gpodder.net returns at most 100 podcasts for a tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::reference::TagName;
+    ///
+    /// let client = PublicClient::default();
+    /// for podcast in client.iter_podcasts_for_tag(TagName::from("new")).take(3) {
+    ///     println!("{}", podcast?);
+    /// }
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn iter_podcasts_for_tag<'a>(&'a self, tag: reference::TagName<'a>) -> PodcastsForTag<'a> {
+        PodcastsForTag(Batches::new(Box::new(move |count| {
+            self.podcasts_for_tag(tag, count)
+        })))
+    }
+
+    /// Lazily iterate over the whole toplist
+    ///
+    /// Pages of [BATCH_SIZE] podcasts are requested with [podcast_toplist_page](PodcastToplist::podcast_toplist_page) until the server returns a short page or no new podcasts, so iteration also ends if the server doesn't support offsets. Each podcast is yielded once. Iteration stops after the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    ///
+    /// let client = PublicClient::default();
+    /// for podcast in client.toplist_iter(None).take(3) {
+    ///     println!("{}", podcast?);
+    /// }
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn toplist_iter(&self, scale_logo: Option<u16>) -> ToplistPages<'_> {
+        ToplistPages::new(Box::new(move |offset| {
+            self.podcast_toplist_page(BATCH_SIZE, offset, scale_logo)
+        }))
+    }
+}
+
+/// Iterator over the whole toplist, see [toplist_iter](PublicClient::toplist_iter)
+pub struct ToplistPages<'a> {
+    fetch: Box<dyn Fn(u32) -> Result<Toplist, Error> + 'a>,
+    offset: u32,
+    finished: bool,
+    seen: HashSet<Url>,
+    pending: VecDeque<Podcast>,
+}
+
+impl<'a> ToplistPages<'a> {
+    fn new(fetch: Box<dyn Fn(u32) -> Result<Toplist, Error> + 'a>) -> Self {
+        ToplistPages {
+            fetch,
+            offset: 0,
+            finished: false,
+            seen: HashSet::new(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for ToplistPages<'_> {
+    type Item = Result<Podcast, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(podcast) = self.pending.pop_front() {
+                return Some(Ok(podcast));
+            }
+            if self.finished {
+                return None;
+            }
+
+            match (self.fetch)(self.offset) {
+                Ok(page) => {
+                    let len = page.len();
+                    self.offset = self
+                        .offset
+                        .saturating_add(u32::try_from(len).unwrap_or(u32::MAX));
+                    for podcast in page {
+                        if self.seen.insert(podcast.podcast.url.clone()) {
+                            self.pending.push_back(podcast);
+                        }
+                    }
+                    // a short page or a repeated page means there are no more podcasts
+                    self.finished = len < usize::from(BATCH_SIZE) || self.pending.is_empty();
+                }
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ToplistPages<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToplistPages")
+            .field("offset", &self.offset)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+/// Iterator over all top tags, see [iter_all_tags](PublicClient::iter_all_tags)
+pub struct AllTags<'a>(Batches<'a, Tag>);
+
+/// Iterator over all podcasts for a tag, see [iter_podcasts_for_tag](PublicClient::iter_podcasts_for_tag)
+pub struct PodcastsForTag<'a>(Batches<'a, subscription::Podcast>);
+
+type Fetch<'a, T> = Box<dyn Fn(u32) -> Result<Vec<T>, Error> + 'a>;
+
+struct Batches<'a, T> {
+    fetch: Fetch<'a, T>,
+    count: u32,
+    finished: bool,
+    seen: HashSet<T>,
+    pending: VecDeque<T>,
+}
+
+impl<'a, T: Clone + Eq + Hash> Batches<'a, T> {
+    fn new(fetch: Fetch<'a, T>) -> Self {
+        Batches {
+            fetch,
+            count: u32::from(BATCH_SIZE),
+            finished: false,
+            seen: HashSet::new(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Iterator for Batches<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(Ok(item));
+            }
+            if self.finished {
+                return None;
+            }
+
+            let count = self.count;
+            match (self.fetch)(count) {
+                Ok(items) => {
+                    let short = u32::try_from(items.len()).is_ok_and(|len| len < count);
+                    self.count = count.saturating_mul(2);
+                    for item in items {
+                        if self.seen.insert(item.clone()) {
+                            self.pending.push_back(item);
+                        }
+                    }
+                    // a short batch or one without new items means there are no more
+                    self.finished = short || self.pending.is_empty() || count == u32::MAX;
+                }
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for AllTags<'_> {
+    type Item = Result<Tag, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl Iterator for PodcastsForTag<'_> {
+    type Item = Result<subscription::Podcast, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl fmt::Debug for AllTags<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AllTags")
+            .field("pending", &self.0.pending)
+            .finish()
+    }
+}
+
+impl fmt::Debug for PodcastsForTag<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PodcastsForTag")
+            .field("pending", &self.0.pending)
+            .finish()
+    }
+}
+
+/// Search-as-you-type helper built on [podcast_search](PodcastSearch::podcast_search)
+///
+/// Queries are submitted to a background thread. A query is only sent after no newer query has been submitted for the debounce duration, so superseded queries never reach the server. Results of recent queries are cached and outgoing searches are rate-limited.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::PublicClient;
+/// use mygpoclient::directory::SearchSession;
+///
+/// let (handle, results) = SearchSession::new(PublicClient::default()).start();
+///
+/// handle.search("lin");
+/// handle.search("linux");
+///
+/// let result = results.recv().unwrap();
+/// assert_eq!("linux", result.query);
+/// assert!(result.podcasts.is_ok());
+///
+/// handle.stop();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SearchSession {
+    client: PublicClient,
+    debounce: Duration,
+    min_interval: Duration,
+    cache_capacity: usize,
+}
+
+/// Handle to a running [SearchSession]
+#[derive(Debug)]
+pub struct SearchHandle {
+    queries: Sender<String>,
+    thread: JoinHandle<()>,
+}
+
+/// Result of a query submitted to a [SearchSession]
+#[derive(Debug)]
+pub struct SearchResult {
+    /// submitted query
+    pub query: String,
+    /// found podcasts
+    pub podcasts: Result<Vec<subscription::Podcast>, Error>,
+}
+
+impl SearchSession {
+    /// Create [SearchSession] with a debounce duration of 300 ms, at most one search per second and 32 cached queries
+    pub fn new(client: PublicClient) -> SearchSession {
+        SearchSession {
+            client,
+            debounce: Duration::from_millis(300),
+            min_interval: Duration::from_secs(1),
+            cache_capacity: 32,
+        }
+    }
+
+    /// Only send a query after no newer query has been submitted for `debounce`
+    pub fn with_debounce(mut self, debounce: Duration) -> SearchSession {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Wait at least `min_interval` between two searches sent to the server
+    pub fn with_min_interval(mut self, min_interval: Duration) -> SearchSession {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Cache the results of the given number of most recent queries
+    pub fn with_cache_capacity(mut self, cache_capacity: usize) -> SearchSession {
+        self.cache_capacity = cache_capacity;
+        self
+    }
+
+    /// Start processing queries on a background thread
+    ///
+    /// The thread stops when [stop](SearchHandle::stop) is called or the returned [Receiver] is dropped.
+    pub fn start(self) -> (SearchHandle, Receiver<SearchResult>) {
+        let (query_sender, query_receiver) = mpsc::channel();
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        let thread = thread::spawn(move || self.run(&query_receiver, &result_sender));
+
+        (
+            SearchHandle {
+                queries: query_sender,
+                thread,
+            },
+            result_receiver,
+        )
+    }
+
+    fn run(self, queries: &Receiver<String>, results: &Sender<SearchResult>) {
+        let mut cache: VecDeque<(String, Vec<subscription::Podcast>)> = VecDeque::new();
+        let mut last_search: Option<Instant> = None;
+
+        while let Ok(mut query) = queries.recv() {
+            loop {
+                match queries.recv_timeout(self.debounce) {
+                    Ok(newer_query) => query = newer_query,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            let key = query.trim().to_lowercase();
+            let cached = cache
+                .iter()
+                .position(|(cached, _)| *cached == key)
+                .and_then(|index| cache.remove(index));
+            let podcasts = match cached {
+                Some(entry) => {
+                    let podcasts = entry.1.clone();
+                    cache.push_back(entry);
+                    Ok(podcasts)
+                }
+                None => {
+                    if let Some(last_search) = last_search {
+                        thread::sleep(self.min_interval.saturating_sub(last_search.elapsed()));
+                    }
+                    last_search = Some(Instant::now());
+
+                    let podcasts = self.client.podcast_search(&key, None);
+                    if let Ok(podcasts) = &podcasts {
+                        if self.cache_capacity > 0 {
+                            if cache.len() >= self.cache_capacity {
+                                cache.pop_front();
+                            }
+                            cache.push_back((key, podcasts.clone()));
+                        }
+                    }
+                    podcasts
+                }
+            };
+
+            if results.send(SearchResult { query, podcasts }).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl SearchHandle {
+    /// Submit a query, superseding all queries which haven't been sent yet
+    pub fn search(&self, query: &str) {
+        let _ = self.queries.send(query.to_owned());
+    }
+
+    /// Stop processing queries and wait for a running search to finish
+    pub fn stop(self) {
+        drop(self.queries);
+        let _ = self.thread.join();
+    }
+}
+
 impl RetrievePodcastData for PublicClient {
-    fn retrieve_podcast_data(&self, url: Url) -> Result<Podcast, Error> {
-        Ok(self
-            .get_with_query(
-                "https://gpodder.net/api/2/data/podcast.json",
-                &[&("url", url.as_str())],
-            )?
-            .json()?)
+    fn retrieve_podcast_data(&self, url: Url) -> Result<subscription::Podcast, Error> {
+        if let Some(podcast) = self
+            .metadata_cache
+            .as_ref()
+            .and_then(|cache| cache.podcast(&url))
+        {
+            return Ok(self.rewrite_podcast(podcast));
+        }
+
+        let response = self.get_with_query(
+            &self.api_url(ApiArea::Directory, "data/podcast.json"),
+            &[&("url", url.as_str())],
+        )?;
+        let mut podcast: subscription::Podcast = response.json()?;
+        if podcast.url == url {
+            if let Some(canonical) = redirected_parameter(&response, "url", &url) {
+                podcast.url = canonical;
+            }
+        }
+        if let Some(cache) = &self.metadata_cache {
+            cache.insert_podcast(url, podcast.clone());
+        }
+        Ok(self.rewrite_podcast(podcast))
     }
 }
 
 impl RetrievePodcastData for AuthenticatedClient {
-    fn retrieve_podcast_data(&self, url: Url) -> Result<Podcast, Error> {
+    fn retrieve_podcast_data(&self, url: Url) -> Result<subscription::Podcast, Error> {
         self.public_client.retrieve_podcast_data(url)
     }
 }
 
 impl RetrievePodcastData for DeviceClient {
-    fn retrieve_podcast_data(&self, url: Url) -> Result<Podcast, Error> {
+    fn retrieve_podcast_data(&self, url: Url) -> Result<subscription::Podcast, Error> {
         self.authenticated_client.retrieve_podcast_data(url)
     }
 }
 
 impl RetrieveEpisodeData for PublicClient {
-    fn retrieve_episode_data(&self, url: Url, podcast: Url) -> Result<Episode, Error> {
-        Ok(self
-            .get_with_query(
-                "https://gpodder.net/api/2/data/episode.json",
-                &[&("url", url.as_str()), &("podcast", podcast.as_str())],
-            )?
-            .json()?)
+    fn retrieve_episode_data(&self, episode: EpisodeRef) -> Result<Episode, Error> {
+        if let Some(cached) = self
+            .metadata_cache
+            .as_ref()
+            .and_then(|cache| cache.episode(&episode))
+        {
+            return Ok(self.rewrite_episode(cached));
+        }
+
+        let response = self.get_with_query(
+            &self.api_url(ApiArea::Directory, "data/episode.json"),
+            &[
+                &("url", episode.episode.as_str()),
+                &("podcast", episode.podcast.as_str()),
+            ],
+        )?;
+        let mut data: Episode = response.json()?;
+        if data.url == episode.episode {
+            if let Some(canonical) = redirected_parameter(&response, "url", &episode.episode) {
+                data.url = canonical;
+            }
+        }
+        if data.podcast_url == episode.podcast {
+            if let Some(canonical) = redirected_parameter(&response, "podcast", &episode.podcast) {
+                data.podcast_url = canonical;
+            }
+        }
+        if let Some(cache) = &self.metadata_cache {
+            cache.insert_episode(episode, data.clone());
+        }
+        Ok(self.rewrite_episode(data))
     }
 }
 
 impl RetrieveEpisodeData for AuthenticatedClient {
-    fn retrieve_episode_data(&self, url: Url, podcast: Url) -> Result<Episode, Error> {
-        self.public_client.retrieve_episode_data(url, podcast)
+    fn retrieve_episode_data(&self, episode: EpisodeRef) -> Result<Episode, Error> {
+        self.public_client.retrieve_episode_data(episode)
     }
 }
 
 impl RetrieveEpisodeData for DeviceClient {
-    fn retrieve_episode_data(&self, url: Url, podcast: Url) -> Result<Episode, Error> {
-        self.authenticated_client
-            .retrieve_episode_data(url, podcast)
+    fn retrieve_episode_data(&self, episode: EpisodeRef) -> Result<Episode, Error> {
+        self.authenticated_client.retrieve_episode_data(episode)
     }
 }
 
 impl PodcastToplist for PublicClient {
-    fn podcast_toplist(&self, number: u8, scale_logo: Option<u16>) -> Result<Vec<Podcast>, Error> {
-        let url = &format!("https://gpodder.net/toplist/{}.json", number);
-
+    fn podcast_toplist_page(
+        &self,
+        number: u8,
+        offset: u32,
+        scale_logo: Option<u16>,
+    ) -> Result<Toplist, Error> {
+        let url = &self.url(ApiArea::Directory, &format!("toplist/{}.json", number));
+
+        let mut query_parameters = Vec::new();
+        if offset > 0 {
+            query_parameters.push(("offset", offset.to_string()));
+        }
         if let Some(size) = scale_logo {
-            Ok(self
-                .get_with_query(url, &[&("scale_logo", size.to_string())])?
-                .json()?)
-        } else {
-            Ok(self.get(url)?.json()?)
+            query_parameters.push(("scale_logo", size.to_string()));
+        }
+        let query_parameters: Vec<&(&str, String)> = query_parameters.iter().collect();
+
+        let podcasts: Vec<Podcast> = self.get_with_query(url, &query_parameters)?.json()?;
+
+        Ok(toplist_page(podcasts, offset)
+            .into_iter()
+            .map(|mut podcast| {
+                podcast.podcast = self.rewrite_podcast(podcast.podcast);
+                podcast
+            })
+            .collect())
+    }
+}
+
+/// Removes podcasts before `offset` if the server ignored it and derives missing positions of the first page
+fn toplist_page(mut podcasts: Vec<Podcast>, offset: u32) -> Vec<Podcast> {
+    let offset_ignored = podcasts
+        .first()
+        .and_then(|podcast| podcast.position)
+        .is_some_and(|position| offset > 0 && position <= offset);
+    if offset_ignored {
+        let skipped = podcasts
+            .iter()
+            .take_while(|podcast| podcast.position.is_some_and(|position| position <= offset))
+            .count();
+        podcasts.drain(..skipped);
+    }
+
+    // without positions, a server ignoring the offset is indistinguishable from one honoring it
+    if offset == 0 {
+        for (podcast, rank) in podcasts.iter_mut().zip(1..) {
+            podcast.position.get_or_insert(rank);
         }
     }
+    podcasts
 }
 
 impl PodcastToplist for AuthenticatedClient {
-    fn podcast_toplist(&self, number: u8, scale_logo: Option<u16>) -> Result<Vec<Podcast>, Error> {
-        self.public_client.podcast_toplist(number, scale_logo)
+    fn podcast_toplist_page(
+        &self,
+        number: u8,
+        offset: u32,
+        scale_logo: Option<u16>,
+    ) -> Result<Toplist, Error> {
+        self.public_client
+            .podcast_toplist_page(number, offset, scale_logo)
     }
 }
 
 impl PodcastToplist for DeviceClient {
-    fn podcast_toplist(&self, number: u8, scale_logo: Option<u16>) -> Result<Vec<Podcast>, Error> {
+    fn podcast_toplist_page(
+        &self,
+        number: u8,
+        offset: u32,
+        scale_logo: Option<u16>,
+    ) -> Result<Toplist, Error> {
         self.authenticated_client
-            .podcast_toplist(number, scale_logo)
+            .podcast_toplist_page(number, offset, scale_logo)
     }
 }
 
 impl PodcastSearch for PublicClient {
-    fn podcast_search(&self, q: &str, scale_logo: Option<u16>) -> Result<Vec<Podcast>, Error> {
+    fn podcast_search(
+        &self,
+        q: &str,
+        scale_logo: Option<u16>,
+    ) -> Result<Vec<subscription::Podcast>, Error> {
         let mut query_parameters: Vec<&(&str, &str)> = Vec::new();
 
         let query_parameter_since = ("q", q);
@@ -348,75 +1230,234 @@ impl PodcastSearch for PublicClient {
             query_parameters.push(&query_parameter_scale_logo);
         }
 
-        Ok(self
-            .get_with_query("https://gpodder.net/search.json", &query_parameters)?
-            .json()?)
+        let podcasts: Vec<subscription::Podcast> = self
+            .get_with_query(
+                &self.url(ApiArea::Directory, "search.json"),
+                &query_parameters,
+            )?
+            .json()?;
+        Ok(podcasts
+            .into_iter()
+            .map(|podcast| self.rewrite_podcast(podcast))
+            .collect())
+    }
+
+    fn podcast_search_raw(
+        &self,
+        q: &str,
+        scale_logo: Option<u16>,
+        format: &Format,
+    ) -> Result<String, Error> {
+        let mut query_parameters: Vec<(&str, &str)> = vec![("q", q)];
+
+        let scale_logo_string = match scale_logo {
+            Some(size) => size.to_string(),
+            None => String::new(),
+        };
+        if !scale_logo_string.is_empty() {
+            query_parameters.push(("scale_logo", scale_logo_string.as_ref()));
+        }
+
+        query_parameters.extend(format.query_parameters());
+
+        self.get_with_query(
+            &self.url(
+                ApiArea::Directory,
+                &format!("search.{}", format.extension()),
+            ),
+            &query_parameters.iter().collect::<Vec<_>>(),
+        )?
+        .text()
     }
 }
 
 impl PodcastSearch for AuthenticatedClient {
-    fn podcast_search(&self, q: &str, scale_logo: Option<u16>) -> Result<Vec<Podcast>, Error> {
+    fn podcast_search(
+        &self,
+        q: &str,
+        scale_logo: Option<u16>,
+    ) -> Result<Vec<subscription::Podcast>, Error> {
         self.public_client.podcast_search(q, scale_logo)
     }
+
+    fn podcast_search_raw(
+        &self,
+        q: &str,
+        scale_logo: Option<u16>,
+        format: &Format,
+    ) -> Result<String, Error> {
+        self.public_client.podcast_search_raw(q, scale_logo, format)
+    }
 }
 
 impl PodcastSearch for DeviceClient {
-    fn podcast_search(&self, q: &str, scale_logo: Option<u16>) -> Result<Vec<Podcast>, Error> {
+    fn podcast_search(
+        &self,
+        q: &str,
+        scale_logo: Option<u16>,
+    ) -> Result<Vec<subscription::Podcast>, Error> {
         self.authenticated_client.podcast_search(q, scale_logo)
     }
+
+    fn podcast_search_raw(
+        &self,
+        q: &str,
+        scale_logo: Option<u16>,
+        format: &Format,
+    ) -> Result<String, Error> {
+        self.authenticated_client
+            .podcast_search_raw(q, scale_logo, format)
+    }
+}
+
+impl FetchLogo for PublicClient {
+    fn fetch_logo(&self, url: Url) -> Result<Logo, Error> {
+        let response = self.get(url.clone())?;
+        if !response.status.is_success() {
+            return Err(Error::UnexpectedStatus {
+                url: redact_url(&url).to_string(),
+                status: response.status.as_u16(),
+            });
+        }
+        Ok(Logo {
+            content_type: response
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned),
+            url: response.url,
+            data: response.body,
+        })
+    }
 }
 
-impl PartialEq for Tag {
-    fn eq(&self, other: &Self) -> bool {
-        self.tag == other.tag
+impl FetchLogo for AuthenticatedClient {
+    fn fetch_logo(&self, url: Url) -> Result<Logo, Error> {
+        self.public_client.fetch_logo(url)
     }
 }
 
-impl Ord for Tag {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.tag.cmp(&other.tag)
+impl FetchLogo for DeviceClient {
+    fn fetch_logo(&self, url: Url) -> Result<Logo, Error> {
+        self.authenticated_client.fetch_logo(url)
     }
 }
 
-impl PartialOrd for Tag {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl CheckFeed for PublicClient {
+    fn check_feed(&self, url: Url, probe_feed: bool) -> Result<FeedStatus, Error> {
+        let response = self.get_with_query(
+            &self.api_url(ApiArea::Directory, "data/podcast.json"),
+            &[&("url", url.as_str())],
+        )?;
+        let known_url = if response.status == StatusCode::NOT_FOUND {
+            None
+        } else {
+            Some(response.json::<subscription::Podcast>()?.url)
+        };
+        let probe = if probe_feed {
+            Some(self.head(url.clone()))
+        } else {
+            None
+        };
+        feed_status(&url, known_url, probe)
     }
 }
 
-impl Hash for Tag {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.tag.hash(state);
+impl CheckFeed for AuthenticatedClient {
+    fn check_feed(&self, url: Url, probe_feed: bool) -> Result<FeedStatus, Error> {
+        self.public_client.check_feed(url, probe_feed)
     }
 }
 
-impl fmt::Display for Tag {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {}", self.tag, self.title)
+impl CheckFeed for DeviceClient {
+    fn check_feed(&self, url: Url, probe_feed: bool) -> Result<FeedStatus, Error> {
+        self.authenticated_client.check_feed(url, probe_feed)
+    }
+}
+
+/// Returns the URL in the query parameter `name` of the final request, if a redirect changed it from `requested`
+fn redirected_parameter(response: &ApiResponse, name: &str, requested: &Url) -> Option<Url> {
+    response
+        .url
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .and_then(|(_, value)| Url::parse(&value).ok())
+        .filter(|url| url != requested)
+}
+
+fn feed_status(
+    url: &Url,
+    known_url: Option<Url>,
+    probe: Option<Result<ApiResponse, Error>>,
+) -> Result<FeedStatus, Error> {
+    let moved = |new_url: Url| {
+        if new_url == *url {
+            FeedStatus::Alive
+        } else {
+            FeedStatus::Redirected(new_url)
+        }
+    };
+
+    match probe {
+        Some(Ok(response)) if response.status.is_success() => return Ok(moved(response.url)),
+        Some(Ok(response))
+            if response.status == StatusCode::NOT_FOUND || response.status == StatusCode::GONE =>
+        {
+            return Ok(FeedStatus::Dead)
+        }
+        Some(Err(Error::ReqwestError(_))) | Some(Err(Error::Timeout { .. })) => {
+            return Ok(FeedStatus::Dead)
+        }
+        Some(Err(error)) => return Err(error),
+        Some(Ok(_)) | None => {}
     }
+
+    Ok(known_url.map_or(FeedStatus::Unknown, moved))
 }
 
-impl PartialEq for Episode {
-    fn eq(&self, other: &Self) -> bool {
-        self.url == other.url
+impl Podcast {
+    /// Change of rank compared to the week before, `None` if the current rank is unknown
+    pub fn movement(&self) -> Option<Movement> {
+        let position = self.position?;
+        Some(match self.position_last_week {
+            None => Movement::New,
+            Some(last_week) if last_week > position => Movement::Up(last_week - position),
+            Some(last_week) if last_week < position => Movement::Down(position - last_week),
+            Some(_) => Movement::Unchanged,
+        })
+    }
+
+    /// Compare by [position](Podcast::position), e.g. for use with `sort_by`
+    ///
+    /// Podcasts without position are ordered last. Unlike [Ord], which compares feed URLs consistent with [Eq], this ordering considers podcasts with equal rank as equal.
+    pub fn cmp_by_rank(&self, other: &Self) -> Ordering {
+        match (self.position, other.position) {
+            (Some(position), Some(other_position)) => position.cmp(&other_position),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
     }
 }
 
-impl Ord for Episode {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.url.cmp(&other.url)
+impl From<Podcast> for subscription::Podcast {
+    fn from(podcast: Podcast) -> Self {
+        podcast.podcast
     }
 }
 
-impl PartialOrd for Episode {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl fmt::Display for Podcast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "{}. {}", position, self.podcast),
+            None => self.podcast.fmt(f),
+        }
     }
 }
 
-impl Hash for Episode {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.url.hash(state);
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.tag, self.title)
     }
 }
 
@@ -428,14 +1469,117 @@ impl fmt::Display for Episode {
 
 #[cfg(test)]
 mod tests {
+    use super::feed_status;
+    use super::prefetch_logos;
+    use super::redirected_parameter;
+    use super::toplist_page;
+    use super::Batches;
     use super::Episode;
+    use super::FeedStatus;
+    use super::FetchLogo;
+    use super::Logo;
+    use super::LogoSize;
+    use super::Movement;
+    use super::Podcast;
+    use super::RetrievePodcastData;
+    use super::SearchQuery;
     use super::Tag;
+    use super::Toplist;
+    use super::ToplistPages;
+    use super::BATCH_SIZE;
+    use crate::client::ApiResponse;
+    use crate::error::Error;
+    use crate::subscription;
     use chrono::NaiveDate;
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
     use std::cmp::Ordering;
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
+    use std::sync::Mutex;
     use url::Url;
 
+    #[test]
+    fn deserialize_toplist_podcast() {
+        let podcast: Podcast = serde_json::from_str(r#"{"website":"http://goinglinux.com","mygpo_link":"http://gpodder.net/podcast/11171","description":"Going Linux","subscribers":571,"title":"Going Linux","url":"http://goinglinux.com/mp3podcast.xml","subscribers_last_week":571,"logo_url":null,"scaled_logo_url":null,"author":null,"position":3,"position_last_week":5}"#).unwrap();
+
+        assert_eq!(
+            Url::parse("http://goinglinux.com/mp3podcast.xml").unwrap(),
+            podcast.podcast.url
+        );
+        assert_eq!(Some(3), podcast.position);
+        assert_eq!(Some(5), podcast.position_last_week);
+        assert_eq!(
+            "3. Going Linux: Going Linux <http://goinglinux.com/mp3podcast.xml>".to_owned(),
+            format!("{}", podcast)
+        );
+    }
+
+    #[test]
+    fn deserialize_toplist_podcast_without_position() {
+        let podcast: Podcast = serde_json::from_str(r#"{"website":"http://goinglinux.com","mygpo_link":"http://gpodder.net/podcast/11171","description":"Going Linux","subscribers":571,"title":"Going Linux","url":"http://goinglinux.com/mp3podcast.xml","subscribers_last_week":571,"logo_url":null,"scaled_logo_url":null,"author":null}"#).unwrap();
+
+        assert_eq!(None, podcast.position);
+        assert_eq!(None, podcast.position_last_week);
+    }
+
+    fn toplist_podcast(
+        url: &str,
+        position: Option<u32>,
+        position_last_week: Option<u32>,
+    ) -> Podcast {
+        Podcast {
+            podcast: serde_json::from_str(&format!(r#"{{"website":null,"mygpo_link":"http://gpodder.net/podcast/11171","description":"","subscribers":0,"title":"","url":"{}","subscribers_last_week":0,"logo_url":null,"scaled_logo_url":null,"author":null}}"#, url)).unwrap(),
+            position,
+            position_last_week,
+        }
+    }
+
+    #[test]
+    fn toplist_movement() {
+        let url = "http://example.com/feed.rss";
+
+        assert_eq!(
+            Some(Movement::Up(2)),
+            toplist_podcast(url, Some(3), Some(5)).movement()
+        );
+        assert_eq!(
+            Some(Movement::Down(1)),
+            toplist_podcast(url, Some(3), Some(2)).movement()
+        );
+        assert_eq!(
+            Some(Movement::Unchanged),
+            toplist_podcast(url, Some(3), Some(3)).movement()
+        );
+        assert_eq!(
+            Some(Movement::New),
+            toplist_podcast(url, Some(3), None).movement()
+        );
+        assert_eq!(None, toplist_podcast(url, None, Some(3)).movement());
+    }
+
+    #[test]
+    fn toplist_sort_by_rank() {
+        let mut podcasts = [
+            toplist_podcast("http://example.com/a.rss", None, None),
+            toplist_podcast("http://example.com/b.rss", Some(2), None),
+            toplist_podcast("http://example.com/c.rss", Some(1), None),
+        ];
+        podcasts.sort_by(Podcast::cmp_by_rank);
+
+        assert_eq!(
+            vec![Some(1), Some(2), None],
+            podcasts.iter().map(|p| p.position).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn deserialize_toplist_podcast_with_old_position() {
+        let podcast: Podcast = serde_json::from_str(r#"{"website":"http://goinglinux.com","mygpo_link":"http://gpodder.net/podcast/11171","description":"Going Linux","subscribers":571,"title":"Going Linux","url":"http://goinglinux.com/mp3podcast.xml","subscribers_last_week":571,"logo_url":null,"scaled_logo_url":null,"author":null,"position":3,"old_position":1}"#).unwrap();
+
+        assert_eq!(Some(1), podcast.position_last_week);
+    }
+
     #[test]
     fn equal_tag_means_equal_hash() {
         let tag1 = Tag {
@@ -506,8 +1650,8 @@ mod tests {
             podcast_url: Url::parse("http://leo.am/podcasts/twit").unwrap(),
             description: String::from("[...]"),
             website: Some(Url::parse("http://www.podtrac.com/pts/redirect.mp3/aolradio.podcast.aol.com/twit/twit0245.mp3").unwrap()),
-            mygpo_link: Url::parse("http://gpodder.net/episode/1046492").unwrap(),
-            released: NaiveDate::from_ymd(2010, 12, 25).and_hms(0, 30, 0),
+            mygpo_link: Some(Url::parse("http://gpodder.net/episode/1046492").unwrap()),
+            released: Some(NaiveDate::from_ymd(2010, 12, 25).and_hms(0, 30, 0)),
         };
         let episode2 = Episode {
             title: String::from("Climate Change, News Corp, and the Australian Fires"),
@@ -516,8 +1660,8 @@ mod tests {
             podcast_url: Url::parse("http://feeds.wnyc.org/onthemedia?format=xml").unwrap(),
             description: String::from("[...]"),
             website: Some(Url::parse("http://www.wnycstudios.org/story/climate-change-news-corp-and-australian-fires/").unwrap()),
-            mygpo_link: Url::parse("http://gpodder.net/podcast/on-the-media-1/climate-change-news-corp-and-the-australian-fires").unwrap(),
-            released: NaiveDate::from_ymd(2020, 1, 15).and_hms(17, 0, 0),
+            mygpo_link: Some(Url::parse("http://gpodder.net/podcast/on-the-media-1/climate-change-news-corp-and-the-australian-fires").unwrap()),
+            released: Some(NaiveDate::from_ymd(2020, 1, 15).and_hms(17, 0, 0)),
         };
 
         assert_eq!(episode1, episode2);
@@ -541,8 +1685,8 @@ mod tests {
             podcast_url: Url::parse("http://leo.am/podcasts/twit").unwrap(),
             description: String::from("[...]"),
             website: Some(Url::parse("http://www.podtrac.com/pts/redirect.mp3/aolradio.podcast.aol.com/twit/twit0245.mp3").unwrap()),
-            mygpo_link: Url::parse("http://gpodder.net/episode/1046492").unwrap(),
-            released: NaiveDate::from_ymd(2010, 12, 25).and_hms(0, 30, 0),
+            mygpo_link: Some(Url::parse("http://gpodder.net/episode/1046492").unwrap()),
+            released: Some(NaiveDate::from_ymd(2010, 12, 25).and_hms(0, 30, 0)),
         };
         let episode2 = Episode {
             title: String::from("Climate Change, News Corp, and the Australian Fires"),
@@ -551,8 +1695,8 @@ mod tests {
             podcast_url: Url::parse("http://feeds.wnyc.org/onthemedia?format=xml").unwrap(),
             description: String::from("[...]"),
             website: Some(Url::parse("http://www.wnycstudios.org/story/climate-change-news-corp-and-australian-fires/").unwrap()),
-            mygpo_link: Url::parse("http://gpodder.net/podcast/on-the-media-1/climate-change-news-corp-and-the-australian-fires").unwrap(),
-            released: NaiveDate::from_ymd(2020, 1, 15).and_hms(17, 0, 0),
+            mygpo_link: Some(Url::parse("http://gpodder.net/podcast/on-the-media-1/climate-change-news-corp-and-the-australian-fires").unwrap()),
+            released: Some(NaiveDate::from_ymd(2020, 1, 15).and_hms(17, 0, 0)),
         };
 
         assert_ne!(episode1, episode2);
@@ -567,6 +1711,61 @@ mod tests {
         assert_ne!(hasher1.finish(), hasher2.finish());
     }
 
+    #[test]
+    fn deserialize_empty_urls_as_none() {
+        let episode = |website: &str, mygpo_link: &str| {
+            serde_json::from_str::<Episode>(&format!(
+                r#"{{"title":"TWiT 245: No Hitler For You","url":"http://www.podtrac.com/pts/redirect.mp3/aolradio.podcast.aol.com/twit/twit0245.mp3","podcast_title":"this WEEK in TECH - MP3 Edition","podcast_url":"http://leo.am/podcasts/twit","description":"[...]","website":{},"mygpo_link":{}}}"#,
+                website, mygpo_link
+            ))
+        };
+
+        let empty = episode(r#""""#, r#"" ""#).unwrap();
+        assert_eq!(None, empty.website);
+        assert_eq!(None, empty.mygpo_link);
+
+        let partial = episode("null", r#""http://gpodder.net/episode/1046492""#).unwrap();
+        assert_eq!(None, partial.website);
+        assert_eq!(
+            Some(Url::parse("http://gpodder.net/episode/1046492").unwrap()),
+            partial.mygpo_link
+        );
+
+        assert!(episode(r#""no url""#, "null").is_err());
+    }
+
+    #[test]
+    fn deserialize_released() {
+        let episode_json = |released: &str| {
+            format!(
+                r#"{{"title":"TWiT 245: No Hitler For You","url":"http://www.podtrac.com/pts/redirect.mp3/aolradio.podcast.aol.com/twit/twit0245.mp3","podcast_title":"this WEEK in TECH - MP3 Edition","podcast_url":"http://leo.am/podcasts/twit","description":"[...]","website":null,"mygpo_link":"http://gpodder.net/episode/1046492"{}}}"#,
+                released
+            )
+        };
+        let released = |json: String| serde_json::from_str::<Episode>(&json).unwrap().released;
+        let expected = Some(NaiveDate::from_ymd(2010, 12, 25).and_hms(0, 30, 0));
+
+        assert_eq!(
+            expected,
+            released(episode_json(r#","released":"2010-12-25T00:30:00""#))
+        );
+        assert_eq!(
+            expected,
+            released(episode_json(r#","released":"2010-12-25T01:30:00+01:00""#))
+        );
+        assert_eq!(
+            expected,
+            released(episode_json(r#","released":"2010-12-25T00:30:00Z""#))
+        );
+        assert_eq!(
+            expected,
+            released(episode_json(r#","released":"2010-12-25 00:30:00""#))
+        );
+        assert_eq!(None, released(episode_json(r#","released":null"#)));
+        assert_eq!(None, released(episode_json(r#","released":"unknown""#)));
+        assert_eq!(None, released(episode_json("")));
+    }
+
     #[test]
     fn display_episode() {
         let episode = Episode {
@@ -576,10 +1775,326 @@ mod tests {
             podcast_url: Url::parse("http://leo.am/podcasts/twit").unwrap(),
             description: String::from("[...]"),
             website: Some(Url::parse("http://www.podtrac.com/pts/redirect.mp3/aolradio.podcast.aol.com/twit/twit0245.mp3").unwrap()),
-            mygpo_link: Url::parse("http://gpodder.net/episode/1046492").unwrap(),
-            released: NaiveDate::from_ymd(2010, 12, 25).and_hms(0, 30, 0),
+            mygpo_link: Some(Url::parse("http://gpodder.net/episode/1046492").unwrap()),
+            released: Some(NaiveDate::from_ymd(2010, 12, 25).and_hms(0, 30, 0)),
         };
 
         assert_eq!("TWiT 245: No Hitler For You: http://www.podtrac.com/pts/redirect.mp3/aolradio.podcast.aol.com/twit/twit0245.mp3".to_owned(), format!("{}", episode));
     }
+
+    #[test]
+    fn batches_grow_until_exhausted() {
+        let requested_counts = std::cell::RefCell::new(Vec::new());
+        let batches = Batches::new(Box::new(|count| {
+            requested_counts.borrow_mut().push(count);
+            // the server knows 300 items
+            Ok((0..count.min(300)).collect())
+        }));
+
+        let items: Vec<u32> = batches.map(Result::unwrap).collect();
+
+        assert_eq!((0..300).collect::<Vec<_>>(), items);
+        assert_eq!(vec![50, 100, 200, 400], *requested_counts.borrow());
+    }
+
+    #[test]
+    fn batches_stop_at_server_cap() {
+        let requested_counts = std::cell::RefCell::new(Vec::new());
+        let batches = Batches::new(Box::new(|count| {
+            requested_counts.borrow_mut().push(count);
+            // the server returns at most 100 items
+            Ok((0..count.min(100)).collect())
+        }));
+
+        assert_eq!(100, batches.map(Result::unwrap).count());
+        assert_eq!(vec![50, 100, 200], *requested_counts.borrow());
+    }
+
+    #[test]
+    fn batches_stop_after_error() {
+        let mut batches = Batches::<u32>::new(Box::new(|_| {
+            Err(Error::DeviceNotFound(String::from("phone")))
+        }));
+
+        assert!(batches.next().unwrap().is_err());
+        assert!(batches.next().is_none());
+    }
+
+    #[test]
+    fn deserialize_tag_usage_beyond_u16() {
+        let tag: Tag =
+            serde_json::from_str(r#"{"title":"Technology","tag":"technology","usage":65536}"#)
+                .unwrap();
+
+        assert_eq!(65536, tag.usage);
+    }
+
+    #[test]
+    fn classify_feeds() {
+        let url = Url::parse("http://example.com/feed.rss").unwrap();
+        let moved = Url::parse("https://example.com/feed.rss").unwrap();
+        let probe = |status, url: &Url| {
+            Some(Ok(ApiResponse {
+                url: url.clone(),
+                status,
+                headers: HeaderMap::new(),
+                body: Vec::new(),
+            }))
+        };
+
+        assert_eq!(
+            FeedStatus::Alive,
+            feed_status(&url, Some(url.clone()), None).unwrap()
+        );
+        assert_eq!(
+            FeedStatus::Redirected(moved.clone()),
+            feed_status(&url, Some(moved.clone()), None).unwrap()
+        );
+        assert_eq!(FeedStatus::Unknown, feed_status(&url, None, None).unwrap());
+        assert_eq!(
+            FeedStatus::Redirected(moved.clone()),
+            feed_status(&url, Some(url.clone()), probe(StatusCode::OK, &moved)).unwrap()
+        );
+        assert_eq!(
+            FeedStatus::Dead,
+            feed_status(&url, Some(url.clone()), probe(StatusCode::GONE, &url)).unwrap()
+        );
+        assert_eq!(
+            FeedStatus::Alive,
+            feed_status(
+                &url,
+                Some(url.clone()),
+                probe(StatusCode::METHOD_NOT_ALLOWED, &url)
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn search_query_quotes_operators_in_text() {
+        let query = SearchQuery::new()
+            .text("  title:evil  c:\\dir ")
+            .title("   ");
+
+        assert_eq!(r#""title:evil" "c:\\dir""#, query.to_string());
+        assert!(SearchQuery::new().author("").is_empty());
+    }
+
+    struct FakePodcastData;
+
+    impl RetrievePodcastData for FakePodcastData {
+        fn retrieve_podcast_data(&self, url: Url) -> Result<subscription::Podcast, Error> {
+            if url.path() == "/missing.rss" {
+                return Err(Error::UnexpectedStatus {
+                    url: url.to_string(),
+                    status: 404,
+                });
+            }
+            Ok(subscription::Podcast {
+                url: url.clone(),
+                title: url.path().to_owned(),
+                author: None,
+                description: String::new(),
+                subscribers: 1,
+                subscribers_last_week: 1,
+                logo_url: None,
+                scaled_logo_url: None,
+                website: None,
+                mygpo_link: Url::parse("http://gpodder.net/podcast/1").unwrap(),
+            })
+        }
+    }
+
+    #[test]
+    fn retrieve_podcast_data_bulk_keeps_input_order() {
+        let urls: Vec<Url> = [
+            "/1.rss",
+            "/missing.rss",
+            "/3.rss",
+            "/4.rss",
+            "/5.rss",
+            "/6.rss",
+        ]
+        .iter()
+        .map(|path| {
+            Url::parse("http://example.com")
+                .unwrap()
+                .join(path)
+                .unwrap()
+        })
+        .collect();
+
+        let results = FakePodcastData.retrieve_podcast_data_bulk(&urls);
+
+        assert_eq!(urls.len(), results.len());
+        for (url, result) in urls.iter().zip(results) {
+            match result {
+                Ok(podcast) => assert_eq!(url, &podcast.url),
+                Err(Error::UnexpectedStatus { status, .. }) => {
+                    assert_eq!("/missing.rss", url.path());
+                    assert_eq!(404, status);
+                }
+                Err(e) => panic!("unexpected error {}", e),
+            }
+        }
+    }
+
+    fn ranked_podcast(rank: u32) -> Podcast {
+        Podcast {
+            podcast: FakePodcastData
+                .retrieve_podcast_data(
+                    Url::parse(&format!("http://example.com/{}.rss", rank)).unwrap(),
+                )
+                .unwrap(),
+            position: None,
+            position_last_week: None,
+        }
+    }
+
+    #[test]
+    fn toplist_pages_until_short_page() {
+        let total = u32::from(BATCH_SIZE) * 2 + 5;
+        let pages = ToplistPages::new(Box::new(|offset| {
+            Ok((offset + 1..=total)
+                .take(usize::from(BATCH_SIZE))
+                .map(ranked_podcast)
+                .collect::<Toplist>())
+        }));
+
+        let podcasts: Vec<Podcast> = pages.collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(total as usize, podcasts.len());
+    }
+
+    #[test]
+    fn toplist_pages_stop_if_offset_is_ignored() {
+        let requests = std::cell::Cell::new(0);
+        let pages = ToplistPages::new(Box::new(|_| {
+            requests.set(requests.get() + 1);
+            Ok((1..=u32::from(BATCH_SIZE))
+                .map(ranked_podcast)
+                .collect::<Toplist>())
+        }));
+
+        assert_eq!(usize::from(BATCH_SIZE), pages.count());
+        assert_eq!(2, requests.get());
+    }
+
+    #[test]
+    fn toplist_page_keeps_unknown_positions() {
+        let ranks = |page: Vec<Podcast>| {
+            page.iter()
+                .map(|podcast| podcast.position)
+                .collect::<Vec<_>>()
+        };
+
+        let first_page = toplist_page((1..=3).map(ranked_podcast).collect(), 0);
+        assert_eq!(vec![Some(1), Some(2), Some(3)], ranks(first_page));
+
+        // the server ignored the offset and returned the first page without positions
+        let ignored_offset = toplist_page((1..=3).map(ranked_podcast).collect(), 2);
+        assert_eq!(vec![None, None, None], ranks(ignored_offset));
+
+        let with_positions = (1..=3)
+            .map(|rank| Podcast {
+                position: Some(rank),
+                ..ranked_podcast(rank)
+            })
+            .collect();
+        assert_eq!(vec![Some(3)], ranks(toplist_page(with_positions, 2)));
+    }
+
+    #[derive(Default)]
+    struct FakeLogos {
+        requests: Mutex<Vec<Url>>,
+    }
+
+    impl FetchLogo for FakeLogos {
+        fn fetch_logo(&self, url: Url) -> Result<Logo, Error> {
+            self.requests.lock().unwrap().push(url.clone());
+            if url.path() == "/missing.png" {
+                return Err(Error::UnexpectedStatus {
+                    url: url.to_string(),
+                    status: 404,
+                });
+            }
+            Ok(Logo {
+                data: url.path().as_bytes().to_vec(),
+                url,
+                content_type: Some(String::from("image/png")),
+            })
+        }
+    }
+
+    #[test]
+    fn prefetch_logos_of_podcasts_with_logo() {
+        let podcast = |feed: &str, logo: Option<&str>, scaled_logo: Option<&str>| {
+            let mut podcast = FakePodcastData
+                .retrieve_podcast_data(Url::parse(feed).unwrap())
+                .unwrap();
+            podcast.logo_url = logo.map(|logo| Url::parse(logo).unwrap());
+            podcast.scaled_logo_url = scaled_logo.map(|logo| Url::parse(logo).unwrap());
+            podcast
+        };
+        let podcasts = vec![
+            podcast(
+                "http://example.com/1.rss",
+                Some("http://example.com/1.png"),
+                Some("http://example.com/1-64.png"),
+            ),
+            podcast(
+                "http://example.com/2.rss",
+                Some("http://example.com/missing.png"),
+                None,
+            ),
+            podcast("http://example.com/3.rss", None, None),
+        ];
+        let client = FakeLogos::default();
+
+        let logos = prefetch_logos(&client, &podcasts, LogoSize::Scaled, 0);
+
+        assert_eq!(2, logos.len());
+        assert_eq!(2, client.requests.lock().unwrap().len());
+        assert_eq!(
+            b"/1-64.png".to_vec(),
+            logos[&podcasts[0].url].as_ref().unwrap().data
+        );
+        assert!(logos[&podcasts[1].url].is_err());
+    }
+
+    #[test]
+    fn redirected_parameter_of_final_url() {
+        let requested = Url::parse("http://feeds.feedburner.com/coverville").unwrap();
+        let response = |url: &str| ApiResponse {
+            url: Url::parse(url).unwrap(),
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Vec::new(),
+        };
+
+        assert_eq!(
+            Some(Url::parse("https://coverville.com/feed/").unwrap()),
+            redirected_parameter(
+                &response("https://gpodder.net/api/2/data/podcast.json?url=https%3A%2F%2Fcoverville.com%2Ffeed%2F"),
+                "url",
+                &requested
+            )
+        );
+        assert_eq!(
+            None,
+            redirected_parameter(
+                &response("https://gpodder.net/api/2/data/podcast.json?url=http%3A%2F%2Ffeeds.feedburner.com%2Fcoverville"),
+                "url",
+                &requested
+            )
+        );
+        assert_eq!(
+            None,
+            redirected_parameter(
+                &response("https://gpodder.net/api/2/data/podcast.json"),
+                "url",
+                &requested
+            )
+        );
+    }
 }
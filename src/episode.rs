@@ -7,81 +7,56 @@
 //! - Clients can send play events with position information so that other clients know where to start playback.
 //! - Clients can send new states to reset previous events. This state needs to be interpreted by receiving clients and does not delete any information on the webservice.
 
-use crate::client::AuthenticatedClient;
-use crate::error::Error;
+use crate::bulk::BulkResult;
+use crate::client::{ApiArea, AuthenticatedClient, Strictness};
+use crate::error::{Error, ValidationError};
+use crate::subscription::FeedUrl;
 use chrono::naive::NaiveDateTime;
-use serde::Deserialize;
-use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
 use url::Url;
 
-/// Type of an [EpisodeAction]
-///
-/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/events.html#episode-action-types
-#[serde(rename_all = "lowercase", tag = "action")]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
-pub enum EpisodeActionType {
-    /// download event, so that other clients know where a file has already been downloaded
-    Download,
-    /// delete event, so that other clients know where a file has already been deleted
-    Delete,
-    /// play event, so that other clients know where to start playback
-    Play {
-        /// the position (in seconds) at which the client stopped playback
-        position: u32,
-        /// the position (in seconds) at which the client started playback. Requires total to be set.
-        #[serde(skip_serializing_if = "Option::is_none")]
-        started: Option<u32>,
-        /// the total length of the file in seconds. Requires started to be set.
-        #[serde(skip_serializing_if = "Option::is_none")]
-        total: Option<u32>,
-    },
-    /// Resets previous events. This state needs to be interpreted by receiving clients and does not delete any information on the webservice.
-    New,
-    /// (undocumented)
-    Flattr,
-}
+pub use crate::protocol::episodes::{
+    ActionList, ActionListIntoIter, EpisodeAction, EpisodeActionType, ExtraFields,
+    GetEpisodeActionsResponse, UploadEpisodeActionsResponse,
+};
 
-/// Episode-related event
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
-pub struct EpisodeAction {
-    /// feed URL to the podcast feed the episode belongs to
-    pub podcast: Url,
-    /// media URL of the episode
-    pub episode: Url,
-    /// device ID on which the action has taken place
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub device: Option<String>,
-    /// see [EpisodeActionType](./enum.EpisodeActionType.html)
-    #[serde(flatten)]
-    pub action: EpisodeActionType,
-    /// UTC timestamp when the action took place
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub timestamp: Option<NaiveDateTime>,
-}
+pub mod antennapod;
 
-// TODO see UploadSubscriptionChangesResponse
-/// Response to [upload_episode_actions](UploadEpisodeActions::upload_episode_actions)
-///
-/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/events.html#upload-episode-actions
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
-pub struct UploadEpisodeActionsResponse {
-    /// the current timestamp; for retrieving changes since the last query
-    pub timestamp: u64,
-    /// list of URLs that have been rewritten (sanitized, see bug:747 and bug:862) as a list of tuples. The client SHOULD parse this list and update the local subscription and episode list accordingly (the server only sanitizes the URL, so the semantic “content” should stay the same and therefore the client can simply update the URL value locally and use it for future updates.
-    ///
-    /// URLs that are not allowed (currently all URLs that contain non-ASCII characters or don’t start with either http or https) are rewritten to the empty string and are ignored by the Webservice.
-    pub update_urls: Vec<(Url, Url)>,
+const CSV_HEADER: [&str; 8] = [
+    "podcast",
+    "episode",
+    "device",
+    "action",
+    "timestamp",
+    "started",
+    "position",
+    "total",
+];
+
+const CSV_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// File format used by [export_actions] and [import_actions]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ExportFormat {
+    /// one JSON object per line, each formatted like in the API
+    JsonLines,
+    /// comma-separated values with a header row and the columns `podcast,episode,device,action,timestamp,started,position,total`
+    ///
+    /// Actions of [unknown types](EpisodeActionType::Unknown) can't be written, since their fields have no columns.
+    Csv,
 }
 
-/// Response to [get_episode_actions](GetEpisodeActions::get_episode_actions)
-///
-/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/events.html#get-episode-actions
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
-pub struct GetEpisodeActionsResponse {
-    /// see [EpisodeAction](./struct.EpisodeAction.html)
+/// Result of [compact_history]
+#[derive(Debug)]
+pub struct CompactionReport {
+    /// number of actions in the downloaded history
+    pub original_len: usize,
+    /// minimal set of actions describing the same state, oldest first
     pub actions: Vec<EpisodeAction>,
-    /// new timestamp that the client SHOULD save and use for subsequent requests
-    pub timestamp: u64,
+    /// result of re-uploading [actions](CompactionReport::actions), `None` for a dry run
+    pub upload: Option<BulkResult<UploadEpisodeActionsResponse>>,
 }
 
 /// see [upload_episode_actions](UploadEpisodeActions::upload_episode_actions)
@@ -121,6 +96,48 @@ pub trait UploadEpisodeActions {
         &self,
         actions: &[EpisodeAction],
     ) -> Result<UploadEpisodeActionsResponse, Error>;
+
+    /// Upload changed episode actions in chunks of at most `chunk_size` actions
+    ///
+    /// All chunks are uploaded, even if some of them fail. The returned [BulkResult] contains the indices of the actions of each chunk, so failed chunks can be retried later. A `chunk_size` of 0 is treated as 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::episode::EpisodeAction;
+    /// use mygpoclient::episode::UploadEpisodeActions;
+    /// use url::Url;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    ///
+    /// let download = EpisodeAction::new_download(Url::parse("http://example.com/feed1.rss").unwrap(), Url::parse("http://example.com/files/s01e20.mp3").unwrap(), None);
+    /// let delete = EpisodeAction::new_delete(Url::parse("http://example.com/feed3.rss").unwrap(), Url::parse("http://example.com/files/s03e20.mp3").unwrap(), None);
+    ///
+    /// let result = client.upload_episode_actions_in_chunks(&[download, delete], 1);
+    /// let retry_later = result.retriable_items();
+    /// ```
+    fn upload_episode_actions_in_chunks(
+        &self,
+        actions: &[EpisodeAction],
+        chunk_size: usize,
+    ) -> BulkResult<UploadEpisodeActionsResponse> {
+        let mut result = BulkResult::default();
+        let chunk_size = chunk_size.max(1);
+
+        for (index, chunk) in actions.chunks(chunk_size).enumerate() {
+            let start = index * chunk_size;
+            result.push(
+                start..start + chunk.len(),
+                self.upload_episode_actions(chunk),
+            );
+        }
+
+        result
+    }
 }
 
 // TODO use Date(time?) instead of timestamps as integers
@@ -161,6 +178,46 @@ pub trait GetEpisodeActions {
         since: Option<u64>,
         aggregated: bool,
     ) -> Result<GetEpisodeActionsResponse, Error>;
+
+    /// Get episode actions which took place between `start` (inclusive) and `end` (exclusive)
+    ///
+    /// The API only filters by upload time, so all actions uploaded since `start` are requested and filtered by their own [timestamp](EpisodeAction::timestamp). An action can't be uploaded before it took place, so no action of the time window is missed. Actions without timestamp are skipped. Both bounds are UTC, like the timestamps of actions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::episode::GetEpisodeActions;
+    /// use chrono::NaiveDate;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    ///
+    /// let january = client.get_episode_actions_between(
+    ///     NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0),
+    ///     NaiveDate::from_ymd(2020, 2, 1).and_hms(0, 0, 0),
+    /// )?;
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn get_episode_actions_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<EpisodeAction>, Error> {
+        let since = u64::try_from(start.timestamp()).unwrap_or(0);
+        let actions = self.get_episode_actions(None, Some(since), false)?.actions;
+        Ok(actions
+            .into_iter()
+            .filter(|action| {
+                action
+                    .timestamp
+                    .is_some_and(|timestamp| start <= timestamp && timestamp < end)
+            })
+            .collect())
+    }
 }
 
 impl EpisodeAction {
@@ -202,6 +259,15 @@ impl EpisodeAction {
         Self::new(podcast, episode, timestamp, EpisodeActionType::New)
     }
 
+    /// Create new [Flattr](EpisodeActionType::Flattr) event, issued when the user flattrs an episode.
+    pub fn new_flattr(
+        podcast: Url,
+        episode: Url,
+        timestamp: Option<NaiveDateTime>,
+    ) -> EpisodeAction {
+        Self::new(podcast, episode, timestamp, EpisodeActionType::Flattr)
+    }
+
     /// Create new [Play](EpisodeActionType::Play) event with [`position`](EpisodeActionType::Play::position) information (in seconds) so that other clients know where to start playback.
     pub fn new_play_stop(
         podcast: Url,
@@ -222,6 +288,24 @@ impl EpisodeAction {
         }
     }
 
+    /// Check whether this action may be uploaded with the given [Strictness]
+    ///
+    /// [upload_episode_actions](UploadEpisodeActions::upload_episode_actions) validates all actions with the [Strictness] of the client. [Strict](Strictness::Strict) validation also requires the podcast URL to be a valid [FeedUrl].
+    pub fn validate(&self, strictness: Strictness) -> Result<(), ValidationError> {
+        if strictness == Strictness::Strict {
+            FeedUrl::try_from(self.podcast.clone())?;
+        }
+
+        match (strictness, &self.action) {
+            (Strictness::Strict, EpisodeActionType::Play { started, total, .. })
+                if started.is_some() != total.is_some() =>
+            {
+                Err(ValidationError::IncompletePlayAction(self.episode.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Create new [Play](EpisodeActionType::Play) event with [position](EpisodeActionType::Play::position), [started](EpisodeActionType::Play::started) and [total](EpisodeActionType::Play::total) information (in seconds) so that other clients know where to start playback.
     pub fn new_play(
         podcast: Url,
@@ -245,17 +329,366 @@ impl EpisodeAction {
     }
 }
 
+impl EpisodeActionType {
+    /// Returns the played share of the episode between 0 and 1, e.g. for progress bars
+    ///
+    /// `None` for other actions than [Play](EpisodeActionType::Play) and play actions without a positive `total`. Positions beyond `total` count as 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::episode::EpisodeActionType;
+    ///
+    /// let play = EpisodeActionType::Play { position: 30, started: Some(0), total: Some(120) };
+    /// assert_eq!(Some(0.25), play.progress_fraction());
+    /// assert!(!play.is_finished(0.95));
+    /// ```
+    pub fn progress_fraction(&self) -> Option<f64> {
+        match *self {
+            EpisodeActionType::Play {
+                position, total, ..
+            } => progress_fraction(position, total),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if at least the fraction `threshold` of the episode has been played, e.g. `0.95` to mark episodes as played at 95 %
+    ///
+    /// Always `false` if the [progress_fraction](EpisodeActionType::progress_fraction) is unknown.
+    pub fn is_finished(&self, threshold: f64) -> bool {
+        self.progress_fraction()
+            .is_some_and(|fraction| fraction >= threshold)
+    }
+
+    /// Returns the value of the `action` field, e.g. `"play"`
+    pub fn name(&self) -> &str {
+        match self {
+            EpisodeActionType::Download => "download",
+            EpisodeActionType::Delete => "delete",
+            EpisodeActionType::Play { .. } => "play",
+            EpisodeActionType::New => "new",
+            EpisodeActionType::Flattr => "flattr",
+            EpisodeActionType::Unknown { action, .. } => action,
+        }
+    }
+}
+
+pub(crate) fn progress_fraction(position: u32, total: Option<u32>) -> Option<f64> {
+    match total {
+        Some(total) if total > 0 => Some((f64::from(position) / f64::from(total)).min(1.0)),
+        _ => None,
+    }
+}
+
+/// Write episode actions to `writer`, e.g. for backups or analysis in other tools
+///
+/// The CSV column order is stable, so exported files can be read by [import_actions] of later versions. Actions of [unknown types](EpisodeActionType::Unknown) are only kept by [JsonLines](ExportFormat::JsonLines); writing them as CSV fails without writing anything.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::episode::{export_actions, EpisodeAction, ExportFormat};
+/// use url::Url;
+///
+/// let download = EpisodeAction::new_download(Url::parse("http://example.com/feed1.rss").unwrap(), Url::parse("http://example.com/files/s01e20.mp3").unwrap(), None);
+///
+/// let mut csv = Vec::new();
+/// export_actions(&[download], ExportFormat::Csv, &mut csv)?;
+///
+/// assert_eq!("podcast,episode,device,action,timestamp,started,position,total\nhttp://example.com/feed1.rss,http://example.com/files/s01e20.mp3,,download,,,,\n", String::from_utf8(csv).unwrap());
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub fn export_actions<W: Write>(
+    actions: &[EpisodeAction],
+    format: ExportFormat,
+    mut writer: W,
+) -> Result<(), Error> {
+    match format {
+        ExportFormat::JsonLines => {
+            for action in actions {
+                serde_json::to_writer(&mut writer, action)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        ExportFormat::Csv => {
+            if let Some((index, name)) =
+                actions
+                    .iter()
+                    .enumerate()
+                    .find_map(|(index, action)| match &action.action {
+                        EpisodeActionType::Unknown { action, .. } => Some((index, action)),
+                        _ => None,
+                    })
+            {
+                return Err(csv_error(index + 2, &format!("unknown action {}", name)));
+            }
+            writeln!(writer, "{}", CSV_HEADER.join(","))?;
+            for action in actions {
+                writeln!(writer, "{}", to_csv_record(action).join(","))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read episode actions previously written by [export_actions]
+///
+/// Empty lines are skipped.
+pub fn import_actions<R: BufRead>(
+    reader: R,
+    format: ExportFormat,
+) -> Result<Vec<EpisodeAction>, Error> {
+    let mut actions = Vec::new();
+    let mut lines = reader.lines().enumerate();
+
+    if format == ExportFormat::Csv {
+        match lines.next() {
+            Some((_, header)) => {
+                if !split_csv_record(&header?)
+                    .iter()
+                    .map(String::as_str)
+                    .eq(CSV_HEADER.iter().copied())
+                {
+                    return Err(csv_error(1, "unexpected header"));
+                }
+            }
+            None => return Ok(actions),
+        }
+    }
+
+    for (index, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        actions.push(match format {
+            ExportFormat::JsonLines => serde_json::from_str(&line)?,
+            ExportFormat::Csv => from_csv_record(index + 1, &line)?,
+        });
+    }
+
+    Ok(actions)
+}
+
+const COMPACTION_CHUNK_SIZE: usize = 1000;
+
+/// Compute a minimal set of episode actions describing the same state as the full history of the user
+///
+/// For every episode, only the latest action of each [type](EpisodeActionType) is kept, e.g. the last playback position and whether the file was last downloaded or deleted. Actions which took place before the latest [New](EpisodeActionType::New) action of an episode are dropped, as it resets them. Actions without timestamp are considered older than all others.
+///
+/// The API can't delete actions, so the history on the server keeps its size. Unless `dry_run` is set, the compacted actions are uploaded again though. Clients synchronizing from scratch can then request only the actions since the returned [timestamp](UploadEpisodeActionsResponse::timestamp) instead of the full history.
+///
+/// # Examples
+///
+/// ```no_run
+/// use mygpoclient::client::AuthenticatedClient;
+/// use mygpoclient::episode::compact_history;
+///
+/// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+/// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+/// #
+/// let client = AuthenticatedClient::new(&username, &password);
+///
+/// let report = compact_history(&client, true)?;
+/// println!("{} of {} actions needed", report.actions.len(), report.original_len);
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub fn compact_history(
+    client: &AuthenticatedClient,
+    dry_run: bool,
+) -> Result<CompactionReport, Error> {
+    let history = client
+        .get_episode_actions(None, None, false)?
+        .actions
+        .into_vec();
+    let original_len = history.len();
+    let actions = compact(history);
+    let upload = if dry_run {
+        None
+    } else {
+        Some(client.upload_episode_actions_in_chunks(&actions, COMPACTION_CHUNK_SIZE))
+    };
+
+    Ok(CompactionReport {
+        original_len,
+        actions,
+        upload,
+    })
+}
+
+fn compact(mut actions: Vec<EpisodeAction>) -> Vec<EpisodeAction> {
+    // stable, so actions with equal timestamps stay in upload order
+    actions.sort_by_key(|action| action.timestamp);
+
+    let mut latest = HashMap::new();
+    let mut resets = HashMap::new();
+    for (index, action) in actions.iter().enumerate() {
+        let episode = (&action.podcast, &action.episode);
+        if action.action == EpisodeActionType::New {
+            resets.insert(episode, index);
+        }
+        latest.insert((episode, action.action.name()), index);
+    }
+
+    let kept: HashSet<usize> = latest
+        .into_iter()
+        .filter(|((episode, _), index)| resets.get(episode).is_none_or(|reset| index >= reset))
+        .map(|(_, index)| index)
+        .collect();
+    actions
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| kept.contains(index))
+        .map(|(_, action)| action)
+        .collect()
+}
+
+fn to_csv_record(action: &EpisodeAction) -> Vec<String> {
+    let (started, position, total) = match action.action {
+        EpisodeActionType::Play {
+            position,
+            started,
+            total,
+        } => (started, Some(position), total),
+        _ => (None, None, None),
+    };
+    let optional_number = |number: Option<u32>| number.map(|n| n.to_string()).unwrap_or_default();
+
+    [
+        action.podcast.to_string(),
+        action.episode.to_string(),
+        action.device.clone().unwrap_or_default(),
+        action.action.name().to_owned(),
+        action
+            .timestamp
+            .map(|timestamp| timestamp.format(CSV_TIMESTAMP_FORMAT).to_string())
+            .unwrap_or_default(),
+        optional_number(started),
+        optional_number(position),
+        optional_number(total),
+    ]
+    .iter()
+    .map(|field| escape_csv_field(field))
+    .collect()
+}
+
+fn from_csv_record(line: usize, record: &str) -> Result<EpisodeAction, Error> {
+    let fields = split_csv_record(record);
+    if fields.len() != CSV_HEADER.len() {
+        return Err(csv_error(
+            line,
+            &format!(
+                "expected {} fields, found {}",
+                CSV_HEADER.len(),
+                fields.len()
+            ),
+        ));
+    }
+
+    let parse_url = |field: &str| Url::parse(field).map_err(|e| csv_error(line, &e.to_string()));
+    let parse_number = |field: &str| -> Result<Option<u32>, Error> {
+        if field.is_empty() {
+            Ok(None)
+        } else {
+            field
+                .parse()
+                .map(Some)
+                .map_err(|e: std::num::ParseIntError| csv_error(line, &e.to_string()))
+        }
+    };
+
+    let action = match fields[3].as_str() {
+        "download" => EpisodeActionType::Download,
+        "delete" => EpisodeActionType::Delete,
+        "new" => EpisodeActionType::New,
+        "flattr" => EpisodeActionType::Flattr,
+        "play" => EpisodeActionType::Play {
+            position: parse_number(fields[6].as_str())?
+                .ok_or_else(|| csv_error(line, "play action without position"))?,
+            started: parse_number(fields[5].as_str())?,
+            total: parse_number(fields[7].as_str())?,
+        },
+        other => return Err(csv_error(line, &format!("unknown action {}", other))),
+    };
+
+    let timestamp = if fields[4].is_empty() {
+        None
+    } else {
+        Some(
+            NaiveDateTime::parse_from_str(fields[4].as_str(), CSV_TIMESTAMP_FORMAT)
+                .map_err(|e| csv_error(line, &e.to_string()))?,
+        )
+    };
+
+    Ok(EpisodeAction {
+        podcast: parse_url(fields[0].as_str())?,
+        episode: parse_url(fields[1].as_str())?,
+        device: Some(fields[2].clone()).filter(|device| !device.is_empty()),
+        action,
+        timestamp,
+    })
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn split_csv_record(record: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = record.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+fn csv_error(line: usize, reason: &str) -> Error {
+    Error::CsvError {
+        line,
+        reason: reason.to_owned(),
+    }
+}
+
 impl UploadEpisodeActions for AuthenticatedClient {
     fn upload_episode_actions(
         &self,
         actions: &[EpisodeAction],
     ) -> Result<UploadEpisodeActionsResponse, Error> {
-        Ok(self
-            .post(
-                &format!("https://gpodder.net/api/2/episodes/{}.json", self.username),
-                actions,
-            )?
-            .json()?)
+        for action in actions {
+            action.validate(self.strictness)?;
+        }
+
+        self.post(
+            &self.api_url(
+                ApiArea::EpisodeActions,
+                &format!("episodes/{}.json", self.username),
+            ),
+            actions,
+        )?
+        .json()
     }
 }
 
@@ -292,11 +725,335 @@ impl GetEpisodeActions for AuthenticatedClient {
             query_parameters.push(&query_parameter_podcast);
         }
 
-        Ok(self
-            .get_with_query(
-                &format!("https://gpodder.net/api/2/episodes/{}.json", self.username),
-                &query_parameters,
-            )?
-            .json()?)
+        self.get_with_query(
+            &self.api_url(
+                ApiArea::EpisodeActions,
+                &format!("episodes/{}.json", self.username),
+            ),
+            &query_parameters,
+        )?
+        .json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compact, export_actions, import_actions, EpisodeAction, EpisodeActionType, ExportFormat,
+        GetEpisodeActions, GetEpisodeActionsResponse, UploadEpisodeActionsResponse,
+    };
+    use crate::client::Strictness;
+    use crate::error::{Error, ValidationError};
+    use chrono::NaiveDate;
+    use std::cell::Cell;
+    use url::Url;
+
+    fn example_actions() -> Vec<EpisodeAction> {
+        let mut play = EpisodeAction::new_play(
+            Url::parse("http://example.org/podcast2.php?a=1,2").unwrap(),
+            Url::parse("http://ftp.example.org/foo2.ogg").unwrap(),
+            Some(NaiveDate::from_ymd(2009, 12, 12).and_hms(9, 0, 0)),
+            120,
+            15,
+            500,
+        );
+        play.device = Some(String::from("phone-au90f923023.203f9j23f"));
+
+        vec![
+            EpisodeAction::new_download(
+                Url::parse("http://example.com/feed1.rss").unwrap(),
+                Url::parse("http://example.com/files/s01e20.mp3").unwrap(),
+                None,
+            ),
+            play,
+            EpisodeAction::new_play_stop(
+                Url::parse("http://example.org/podcast5.php").unwrap(),
+                Url::parse("http://ftp.example.org/foo5.ogg").unwrap(),
+                None,
+                120,
+            ),
+        ]
+    }
+
+    #[test]
+    fn validate_play_with_total_only() {
+        let episode = Url::parse("http://ftp.example.org/foo2.ogg").unwrap();
+        let play = EpisodeAction {
+            podcast: Url::parse("http://example.org/podcast2.php").unwrap(),
+            episode: episode.clone(),
+            device: None,
+            action: EpisodeActionType::Play {
+                position: 120,
+                started: None,
+                total: Some(500),
+            },
+            timestamp: None,
+        };
+
+        assert_eq!(
+            Err(ValidationError::IncompletePlayAction(episode)),
+            play.validate(Strictness::Strict)
+        );
+        assert_eq!(Ok(()), play.validate(Strictness::Lenient));
+    }
+
+    #[test]
+    fn validate_complete_play() {
+        let play = EpisodeAction::new_play(
+            Url::parse("http://example.org/podcast2.php").unwrap(),
+            Url::parse("http://ftp.example.org/foo2.ogg").unwrap(),
+            None,
+            120,
+            15,
+            500,
+        );
+
+        assert_eq!(Ok(()), play.validate(Strictness::Strict));
+    }
+
+    #[test]
+    fn validate_podcast_feed_url() {
+        let download = EpisodeAction::new_download(
+            Url::parse("ftp://example.org/podcast2.php").unwrap(),
+            Url::parse("ftp://ftp.example.org/foo2.ogg").unwrap(),
+            None,
+        );
+
+        assert_eq!(
+            Err(ValidationError::InvalidFeedUrl(String::from(
+                "ftp://example.org/podcast2.php"
+            ))),
+            download.validate(Strictness::Strict)
+        );
+        assert_eq!(Ok(()), download.validate(Strictness::Lenient));
+    }
+
+    #[test]
+    fn csv_roundtrip() {
+        let actions = example_actions();
+        let mut csv = Vec::new();
+        export_actions(&actions, ExportFormat::Csv, &mut csv).unwrap();
+
+        assert_eq!(
+            actions,
+            import_actions(csv.as_slice(), ExportFormat::Csv).unwrap()
+        );
+    }
+
+    #[test]
+    fn json_lines_roundtrip() {
+        let actions = example_actions();
+        let mut json_lines = Vec::new();
+        export_actions(&actions, ExportFormat::JsonLines, &mut json_lines).unwrap();
+
+        assert_eq!(
+            actions.len(),
+            String::from_utf8(json_lines.clone())
+                .unwrap()
+                .lines()
+                .count()
+        );
+        assert_eq!(
+            actions,
+            import_actions(json_lines.as_slice(), ExportFormat::JsonLines).unwrap()
+        );
+    }
+
+    #[test]
+    fn csv_import_rejects_unknown_action() {
+        let csv = "podcast,episode,device,action,timestamp,started,position,total\nhttp://example.com/feed1.rss,http://example.com/files/s01e20.mp3,,listen,,,,\n";
+
+        assert!(import_actions(csv.as_bytes(), ExportFormat::Csv).is_err());
+    }
+
+    #[test]
+    fn csv_export_rejects_unknown_action() {
+        let action: EpisodeAction = serde_json::from_str(
+            r#"{"podcast": "http://example.com/feed1.rss", "episode": "http://example.com/files/s01e20.mp3", "action": "listen", "rating": 5}"#,
+        )
+        .unwrap();
+
+        let actions = vec![action];
+
+        let mut csv = Vec::new();
+        assert!(export_actions(&actions, ExportFormat::Csv, &mut csv).is_err());
+        assert!(csv.is_empty());
+
+        let mut json_lines = Vec::new();
+        export_actions(&actions, ExportFormat::JsonLines, &mut json_lines).unwrap();
+        assert_eq!(
+            actions,
+            import_actions(json_lines.as_slice(), ExportFormat::JsonLines).unwrap()
+        );
+    }
+
+    #[test]
+    fn serialize_flattr() {
+        let flattr = EpisodeAction::new_flattr(
+            Url::parse("http://example.com/feed1.rss").unwrap(),
+            Url::parse("http://example.com/files/s01e20.mp3").unwrap(),
+            None,
+        );
+
+        assert_eq!(
+            r#"{"podcast":"http://example.com/feed1.rss","episode":"http://example.com/files/s01e20.mp3","action":"flattr"}"#,
+            serde_json::to_string(&flattr).unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_flattr() {
+        let flattr: EpisodeAction = serde_json::from_str(
+            r#"{"podcast":"http://example.com/feed1.rss","episode":"http://example.com/files/s01e20.mp3","action":"flattr"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(EpisodeActionType::Flattr, flattr.action);
+    }
+
+    #[test]
+    fn unknown_action_roundtrip() {
+        let json = r#"{"podcast":"http://example.com/feed1.rss","episode":"http://example.com/files/s01e20.mp3","action":"chapter","chapter":3,"title":"Intro"}"#;
+
+        let action: EpisodeAction = serde_json::from_str(json).unwrap();
+
+        match &action.action {
+            EpisodeActionType::Unknown { action, fields } => {
+                assert_eq!("chapter", action);
+                assert_eq!(Some(&serde_json::json!(3)), fields.0.get("chapter"));
+                assert_eq!(None, fields.0.get("podcast"));
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+        assert_eq!("chapter", action.action.name());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(json).unwrap(),
+            serde_json::to_value(&action).unwrap()
+        );
+    }
+
+    struct FakeEpisodeActions {
+        actions: Vec<EpisodeAction>,
+        since: Cell<Option<u64>>,
+    }
+
+    impl GetEpisodeActions for FakeEpisodeActions {
+        fn get_episode_actions(
+            &self,
+            _podcast: Option<Url>,
+            since: Option<u64>,
+            _aggregated: bool,
+        ) -> Result<GetEpisodeActionsResponse, Error> {
+            self.since.set(since);
+            Ok(GetEpisodeActionsResponse {
+                actions: self.actions.clone().into(),
+                timestamp: 0,
+            })
+        }
+    }
+
+    #[test]
+    fn get_actions_between_filters_by_action_timestamp() {
+        let day = |day| NaiveDate::from_ymd(2020, 1, day).and_hms(0, 0, 0);
+        let action = |timestamp| {
+            EpisodeAction::new_download(
+                Url::parse("http://example.com/feed.rss").unwrap(),
+                Url::parse("http://example.com/episode.mp3").unwrap(),
+                timestamp,
+            )
+        };
+        let client = FakeEpisodeActions {
+            actions: vec![
+                action(Some(day(1))),
+                action(Some(day(2))),
+                action(Some(day(3))),
+                action(None),
+            ],
+            since: Cell::new(None),
+        };
+
+        let actions = client.get_episode_actions_between(day(2), day(3)).unwrap();
+
+        assert_eq!(vec![action(Some(day(2)))], actions);
+        assert_eq!(Some(day(2).timestamp() as u64), client.since.get());
+    }
+
+    #[test]
+    fn compact_keeps_latest_action_of_each_type_after_reset() {
+        let podcast = Url::parse("http://example.com/feed.rss").unwrap();
+        let episode = Url::parse("http://example.com/s01e01.mp3").unwrap();
+        let other = Url::parse("http://example.com/s01e02.mp3").unwrap();
+        let at = |hour| Some(NaiveDate::from_ymd(2020, 1, 1).and_hms(hour, 0, 0));
+
+        let actions = vec![
+            EpisodeAction::new_play_stop(podcast.clone(), episode.clone(), at(5), 300),
+            EpisodeAction::new_download(podcast.clone(), episode.clone(), at(1)),
+            EpisodeAction::new_play_stop(podcast.clone(), episode.clone(), at(2), 100),
+            EpisodeAction::new_new(podcast.clone(), episode.clone(), at(3)),
+            EpisodeAction::new_delete(podcast.clone(), episode.clone(), at(4)),
+            EpisodeAction::new_download(podcast.clone(), other.clone(), None),
+            EpisodeAction::new_download(podcast.clone(), other.clone(), at(6)),
+        ];
+
+        assert_eq!(
+            vec![
+                EpisodeAction::new_new(podcast.clone(), episode.clone(), at(3)),
+                EpisodeAction::new_delete(podcast.clone(), episode.clone(), at(4)),
+                EpisodeAction::new_play_stop(podcast.clone(), episode, at(5), 300),
+                EpisodeAction::new_download(podcast, other, at(6)),
+            ],
+            compact(actions)
+        );
+    }
+
+    #[test]
+    fn action_list_is_transparent() {
+        let response: GetEpisodeActionsResponse = serde_json::from_str(
+            r#"{"actions":[{"podcast":"http://example.com/feed.rss","episode":"http://example.com/s01e01.mp3","action":"download"}],"timestamp":12345}"#,
+        )
+        .unwrap();
+
+        assert_eq!(1, response.actions.len());
+        assert_eq!(
+            vec![EpisodeAction::new_download(
+                Url::parse("http://example.com/feed.rss").unwrap(),
+                Url::parse("http://example.com/s01e01.mp3").unwrap(),
+                None,
+            )],
+            response.actions.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn progress_fraction_of_play_actions() {
+        let play = |position: u32, total: Option<u32>| EpisodeActionType::Play {
+            position,
+            started: total.map(|_| 0),
+            total,
+        };
+
+        assert!(play(600, Some(500)).is_finished(1.0));
+        assert!(play(475, Some(500)).is_finished(0.95));
+        assert!(!play(474, Some(500)).is_finished(0.95));
+        assert_eq!(None, play(100, Some(0)).progress_fraction());
+        assert_eq!(None, play(100, None).progress_fraction());
+        assert_eq!(None, EpisodeActionType::Download.progress_fraction());
+    }
+
+    #[test]
+    fn deserialize_rejected_update_url() {
+        let json = r#"{"timestamp":1337,"update_urls":[["http://example.com/feed.rss?a=1","http://example.com/feed.rss"],["ftp://example.org/podcast.php",""]]}"#;
+
+        let response: UploadEpisodeActionsResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            Some(&Url::parse("http://example.com/feed.rss").unwrap()),
+            response.update_urls[0].resolved()
+        );
+        assert!(response.update_urls[1].is_rejected());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(json).unwrap(),
+            serde_json::to_value(&response).unwrap()
+        );
     }
 }
@@ -0,0 +1,181 @@
+//! Conversion between [EpisodeAction]s and the episode action export of [AntennaPod](https://antennapod.org/)
+//!
+//! AntennaPod exports episode actions as a JSON array in almost the same schema as the API. The differences are handled here:
+//! - action names may be upper case, e.g. `"PLAY"`
+//! - episodes carry an additional `guid`, which is dropped on import
+//! - unknown positions are written as `-1`
+//! - there are no devices and no flattr actions, so both are dropped on export
+
+use crate::episode::{EpisodeAction, EpisodeActionType};
+use crate::error::Error;
+use chrono::NaiveDateTime;
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+use url::Url;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+const TIMESTAMP_PARSE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const UNKNOWN_POSITION: i64 = -1;
+
+#[derive(Serialize, Deserialize)]
+struct AntennaPodAction {
+    podcast: Url,
+    episode: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    guid: Option<String>,
+    action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    started: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+}
+
+/// Read episode actions from an AntennaPod export, e.g. to upload them with [upload_episode_actions](crate::episode::UploadEpisodeActions::upload_episode_actions)
+///
+/// Play actions with only one of `started` and `total` are imported without both, so they pass [validation](EpisodeAction::validate).
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::episode::antennapod;
+/// use mygpoclient::episode::EpisodeActionType;
+///
+/// let export = r#"[{"podcast":"http://example.com/feed.rss","episode":"http://example.com/s01e01.mp3","guid":"s01e01","action":"PLAY","timestamp":"2020-01-01T09:00:00","started":-1,"position":120,"total":-1}]"#;
+///
+/// let actions = antennapod::import_actions(export.as_bytes())?;
+/// assert_eq!(EpisodeActionType::Play { position: 120, started: None, total: None }, actions[0].action);
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub fn import_actions<R: Read>(reader: R) -> Result<Vec<EpisodeAction>, Error> {
+    let actions: Vec<AntennaPodAction> = serde_json::from_reader(reader)?;
+    actions.into_iter().map(from_antennapod).collect()
+}
+
+/// Write episode actions in the format of an AntennaPod export, e.g. after downloading them with [get_episode_actions](crate::episode::GetEpisodeActions::get_episode_actions)
+///
+/// Flattr actions and [unknown](EpisodeActionType::Unknown) actions are skipped, as AntennaPod doesn't know them.
+pub fn export_actions<W: Write>(actions: &[EpisodeAction], writer: W) -> Result<(), Error> {
+    let actions: Vec<AntennaPodAction> = actions.iter().filter_map(to_antennapod).collect();
+    serde_json::to_writer(writer, &actions)?;
+    Ok(())
+}
+
+fn from_antennapod(action: AntennaPodAction) -> Result<EpisodeAction, Error> {
+    let position = |value: Option<i64>| value.and_then(|value| u32::try_from(value).ok());
+
+    let action_type = match action.action.to_lowercase().as_str() {
+        "download" => EpisodeActionType::Download,
+        "delete" => EpisodeActionType::Delete,
+        "new" => EpisodeActionType::New,
+        "play" => {
+            let (started, total) = match (position(action.started), position(action.total)) {
+                (Some(started), Some(total)) => (Some(started), Some(total)),
+                _ => (None, None),
+            };
+            EpisodeActionType::Play {
+                position: position(action.position).ok_or_else(|| {
+                    serde_json::Error::custom(format!(
+                        "play action for {} without position",
+                        action.episode
+                    ))
+                })?,
+                started,
+                total,
+            }
+        }
+        other => {
+            return Err(serde_json::Error::custom(format!("unknown action {}", other)).into());
+        }
+    };
+
+    let timestamp = match action.timestamp {
+        Some(timestamp) => Some(
+            NaiveDateTime::parse_from_str(&timestamp, TIMESTAMP_PARSE_FORMAT)
+                .map_err(|e| serde_json::Error::custom(e.to_string()))?,
+        ),
+        None => None,
+    };
+
+    Ok(EpisodeAction {
+        podcast: action.podcast,
+        episode: action.episode,
+        device: None,
+        action: action_type,
+        timestamp,
+    })
+}
+
+fn to_antennapod(action: &EpisodeAction) -> Option<AntennaPodAction> {
+    let position = |value: Option<u32>| Some(value.map_or(UNKNOWN_POSITION, i64::from));
+
+    let (name, started, position, total) = match action.action {
+        EpisodeActionType::Download => ("download", None, None, None),
+        EpisodeActionType::Delete => ("delete", None, None, None),
+        EpisodeActionType::New => ("new", None, None, None),
+        EpisodeActionType::Play {
+            position: played,
+            started,
+            total,
+        } => (
+            "play",
+            position(started),
+            position(Some(played)),
+            position(total),
+        ),
+        EpisodeActionType::Flattr | EpisodeActionType::Unknown { .. } => return None,
+    };
+
+    Some(AntennaPodAction {
+        podcast: action.podcast.clone(),
+        episode: action.episode.clone(),
+        guid: None,
+        action: name.to_owned(),
+        timestamp: action
+            .timestamp
+            .map(|timestamp| timestamp.format(TIMESTAMP_FORMAT).to_string()),
+        started,
+        position,
+        total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_actions, import_actions};
+    use crate::episode::EpisodeAction;
+    use chrono::NaiveDate;
+    use url::Url;
+
+    #[test]
+    fn roundtrip_without_flattr() {
+        let podcast = Url::parse("http://example.com/feed.rss").unwrap();
+        let episode = Url::parse("http://example.com/s01e01.mp3").unwrap();
+        let timestamp = Some(NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0));
+        let actions = vec![
+            EpisodeAction::new_download(podcast.clone(), episode.clone(), timestamp),
+            EpisodeAction::new_play(podcast.clone(), episode.clone(), timestamp, 15, 120, 500),
+            EpisodeAction::new_play_stop(podcast.clone(), episode.clone(), None, 120),
+        ];
+        let mut with_flattr = actions.clone();
+        with_flattr.push(EpisodeAction::new_flattr(podcast, episode, None));
+
+        let mut export = Vec::new();
+        export_actions(&with_flattr, &mut export).unwrap();
+
+        assert_eq!(actions, import_actions(export.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn import_rejects_unknown_action() {
+        let export = r#"[{"podcast":"http://example.com/feed.rss","episode":"http://example.com/s01e01.mp3","action":"RATE"}]"#;
+
+        assert!(import_actions(export.as_bytes()).is_err());
+    }
+}
@@ -1,26 +1,590 @@
 //! Error handling
+//!
+//! [Error] converts into [std::io::Error], so it can be propagated with `?` in functions returning [io::Result]. Messages of errors caused by a request name the endpoint, e.g. `/api/2/devices/username.json`, so they remain meaningful when wrapped with additional context, e.g. by `anyhow::Context`.
 
+#[cfg(feature = "subscriptions")]
+use crate::subscription::GetSubscriptionChangesResponse;
 use std::fmt;
+use std::io;
+use std::time::Duration;
+use url::Url;
+
+/// [Result](std::result::Result) with this crate's [Error]
+pub type Result<T> = std::result::Result<T, Error>;
 
 /// Error resulting from an API request
 #[derive(Debug)]
 pub enum Error {
     /// Error originating from reqwest crate
+    #[cfg(feature = "client")]
     ReqwestError(reqwest::Error),
+    /// Error while reading or writing data
+    IoError(io::Error),
+    /// Error originating from serde_json crate
+    JsonError(serde_json::Error),
+    /// Server responded with an unexpected status code
+    UnexpectedStatus {
+        /// requested URL
+        url: String,
+        /// HTTP status code of the response
+        status: u16,
+    },
+    /// Error originating from url crate
+    UrlError(url::ParseError),
+    /// Response of an endpoint couldn't be deserialized
+    InvalidResponse {
+        /// path of the requested URL
+        endpoint: String,
+        /// cause of the failure
+        source: serde_json::Error,
+    },
+    /// Data rejected by client-side validation before uploading
+    ValidationError(ValidationError),
+    /// Malformed CSV record
+    CsvError {
+        /// line number, starting at 1
+        line: usize,
+        /// description of the problem
+        reason: String,
+    },
+    /// Data on the server has been changed by another client since the caller's snapshot, see [upload_subscriptions_of_device_if_unmodified](crate::subscription::SubscriptionsOfDevice::upload_subscriptions_of_device_if_unmodified)
+    #[cfg(feature = "subscriptions")]
+    ConcurrentModification(GetSubscriptionChangesResponse),
+    /// Device with the contained ID doesn't exist on the server
+    DeviceNotFound(String),
+    /// User with the contained name doesn't share their subscriptions publicly
+    SubscriptionsNotPublic(String),
+    /// Client was created with invalid credentials or device ID
+    InvalidConfiguration {
+        /// description of the problem
+        reason: String,
+    },
+    /// Malformed OPML document
+    OpmlError {
+        /// description of the problem
+        reason: String,
+    },
+    /// Server declared a content type other than JSON for a JSON endpoint, e.g. the HTML page of a proxy
+    UnexpectedContentType {
+        /// path of the requested URL
+        endpoint: String,
+        /// declared content type
+        content_type: String,
+    },
+    /// Request would alter data on the server, but the client is [read-only](crate::client::PublicClient::with_read_only)
+    ReadOnly {
+        /// HTTP method of the rejected request
+        method: String,
+        /// path of the requested URL
+        endpoint: String,
+    },
+    /// Server didn't respond within the timeout of the client
+    Timeout {
+        /// path of the requested URL
+        endpoint: String,
+        /// time passed between sending the request and giving up
+        elapsed: Duration,
+    },
+    /// Response body exceeded the [maximum response size](crate::client::PublicClient::with_max_response_size)
+    ResponseTooLarge {
+        /// path of the requested URL
+        endpoint: String,
+        /// maximum size in bytes
+        limit: u64,
+    },
+    /// Server is down for maintenance, as announced by a `503 Service Unavailable` response with a `Retry-After` header or a maintenance page
+    Maintenance {
+        /// path of the requested URL
+        endpoint: String,
+        /// time until the server is expected to be available again, if announced
+        retry_after: Option<Duration>,
+    },
+}
+
+/// Stable identifier of the category of an [Error], see [code](Error::code)
+///
+/// Applications can map codes to localized messages instead of parsing [Display](fmt::Display) output, which may change between releases. [user_message](ErrorCode::user_message) provides an English fallback.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::error::{Error, ErrorCode};
+///
+/// let error = Error::DeviceNotFound(String::from("phone"));
+/// assert_eq!(ErrorCode::DeviceNotFound, error.code());
+/// assert_eq!("device_not_found", error.code().as_str());
+/// println!("{}", error.user_message());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ErrorCode {
+    /// server couldn't be reached
+    Network,
+    /// server didn't respond in time
+    Timeout,
+    /// credentials were rejected
+    Unauthorized,
+    /// requested resource doesn't exist
+    NotFound,
+    /// server rejected the request because of too many requests
+    RateLimited,
+    /// server failed to process the request
+    ServerError,
+    /// server is down for maintenance
+    Maintenance,
+    /// response couldn't be understood
+    InvalidResponse,
+    /// data was rejected before sending it
+    InvalidInput,
+    /// client was created with invalid credentials or settings
+    InvalidConfiguration,
+    /// data was changed by another client in the meantime
+    ConcurrentModification,
+    /// device doesn't exist on the server
+    DeviceNotFound,
+    /// subscriptions of the user aren't public
+    SubscriptionsNotPublic,
+    /// request was rejected by a read-only client
+    ReadOnly,
+    /// local data couldn't be read or written
+    Io,
 }
 
+/// Reason for rejecting data before uploading it
+///
+/// see [Strictness](crate::client::Strictness)
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ValidationError {
+    /// play action for the contained episode URL has either `started` or `total` set, but not both
+    IncompletePlayAction(Url),
+    /// the contained URL is both added and removed in the same upload
+    ConflictingChange(Url),
+    /// the contained string is not a valid [FeedUrl](crate::subscription::FeedUrl)
+    InvalidFeedUrl(String),
+}
+
+#[cfg(feature = "client")]
 impl From<reqwest::Error> for Error {
     fn from(error: reqwest::Error) -> Self {
         Error::ReqwestError(error)
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::IoError(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::JsonError(error)
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(error: url::ParseError) -> Self {
+        Error::UrlError(error)
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(error: ValidationError) -> Self {
+        Error::ValidationError(error)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
+            #[cfg(feature = "client")]
             Error::ReqwestError(reqwest_error) => reqwest_error.fmt(f),
+            Error::IoError(io_error) => io_error.fmt(f),
+            Error::JsonError(json_error) => json_error.fmt(f),
+            Error::UnexpectedStatus { url, status } => {
+                write!(f, "unexpected status {} from {}", status, url)
+            }
+            Error::UrlError(url_error) => url_error.fmt(f),
+            Error::InvalidResponse { endpoint, .. } => {
+                write!(f, "invalid response from {}", endpoint)
+            }
+            Error::ValidationError(validation_error) => validation_error.fmt(f),
+            Error::CsvError { line, reason } => write!(f, "line {}: {}", line, reason),
+            #[cfg(feature = "subscriptions")]
+            Error::ConcurrentModification(changes) => {
+                write!(f, "modified concurrently: {}", changes)
+            }
+            Error::DeviceNotFound(device_id) => write!(f, "device {} not found", device_id),
+            Error::SubscriptionsNotPublic(username) => {
+                write!(f, "subscriptions of {} are not public", username)
+            }
+            Error::InvalidConfiguration { reason } => {
+                write!(f, "invalid configuration: {}", reason)
+            }
+            Error::OpmlError { reason } => write!(f, "invalid OPML: {}", reason),
+            Error::UnexpectedContentType {
+                endpoint,
+                content_type,
+            } => write!(
+                f,
+                "unexpected content type {} from {}",
+                content_type, endpoint
+            ),
+            Error::ReadOnly { method, endpoint } => {
+                write!(f, "{} {} rejected by read-only client", method, endpoint)
+            }
+            Error::Timeout { endpoint, elapsed } => {
+                write!(f, "{} timed out after {} ms", endpoint, elapsed.as_millis())
+            }
+            Error::ResponseTooLarge { endpoint, limit } => {
+                write!(f, "response of {} exceeds {} bytes", endpoint, limit)
+            }
+            Error::Maintenance {
+                endpoint,
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "{} unavailable due to maintenance for {} s",
+                endpoint,
+                retry_after.as_secs()
+            ),
+            Error::Maintenance {
+                endpoint,
+                retry_after: None,
+            } => write!(f, "{} unavailable due to maintenance", endpoint),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self {
+            #[cfg(feature = "client")]
+            Error::ReqwestError(reqwest_error) => Some(reqwest_error),
+            Error::IoError(io_error) => Some(io_error),
+            Error::JsonError(json_error) => Some(json_error),
+            Error::InvalidResponse { source, .. } => Some(source),
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for Error {}
+/// Maps the category of the error to an [io::ErrorKind]
+///
+/// An [IoError](Error::IoError) is unwrapped, all other errors are kept as inner error.
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::IoError(io_error) => io_error,
+            error => io::Error::new(error.io_error_kind(), error),
+        }
+    }
+}
+
+impl Error {
+    /// Returns whether sending the same request again might succeed
+    ///
+    /// This is the case for connection problems, timeouts and server errors, but not e.g. for invalid input, missing authorization or unparsable responses.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "client")]
+            Error::ReqwestError(error) => {
+                !error.is_builder()
+                    && !error.is_decode()
+                    && error.status().is_none_or(|status| status.is_server_error())
+            }
+            Error::UnexpectedStatus { status, .. } => *status >= 500,
+            Error::IoError(_) | Error::Timeout { .. } | Error::Maintenance { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the [ErrorCode] of the error, e.g. for looking up a localized message
+    pub fn code(&self) -> ErrorCode {
+        match &self {
+            #[cfg(feature = "client")]
+            Error::ReqwestError(reqwest_error) => {
+                if reqwest_error.is_timeout() {
+                    ErrorCode::Timeout
+                } else if reqwest_error.is_decode() {
+                    ErrorCode::InvalidResponse
+                } else {
+                    match reqwest_error.status() {
+                        Some(status) => status_code(status.as_u16()),
+                        None => ErrorCode::Network,
+                    }
+                }
+            }
+            Error::IoError(_) => ErrorCode::Io,
+            Error::UnexpectedStatus { status, .. } => status_code(*status),
+            Error::JsonError(_)
+            | Error::InvalidResponse { .. }
+            | Error::UnexpectedContentType { .. } => ErrorCode::InvalidResponse,
+            Error::UrlError(_)
+            | Error::ValidationError(_)
+            | Error::CsvError { .. }
+            | Error::OpmlError { .. } => ErrorCode::InvalidInput,
+            Error::InvalidConfiguration { .. } => ErrorCode::InvalidConfiguration,
+            #[cfg(feature = "subscriptions")]
+            Error::ConcurrentModification(_) => ErrorCode::ConcurrentModification,
+            Error::DeviceNotFound(_) => ErrorCode::DeviceNotFound,
+            Error::SubscriptionsNotPublic(_) => ErrorCode::SubscriptionsNotPublic,
+            Error::ReadOnly { .. } => ErrorCode::ReadOnly,
+            Error::Timeout { .. } => ErrorCode::Timeout,
+            Error::ResponseTooLarge { .. } => ErrorCode::InvalidResponse,
+            Error::Maintenance { .. } => ErrorCode::Maintenance,
+        }
+    }
+
+    /// Returns a concise, non-technical English description of the error for showing to users
+    ///
+    /// Use [code](Error::code) to show localized messages instead.
+    pub fn user_message(&self) -> &'static str {
+        self.code().user_message()
+    }
+
+    fn io_error_kind(&self) -> io::ErrorKind {
+        match &self {
+            Error::IoError(io_error) => io_error.kind(),
+            #[cfg(feature = "client")]
+            Error::ReqwestError(reqwest_error) => {
+                if reqwest_error.is_timeout() {
+                    io::ErrorKind::TimedOut
+                } else if reqwest_error.is_decode() {
+                    io::ErrorKind::InvalidData
+                } else {
+                    match reqwest_error.status().map(|status| status.as_u16()) {
+                        Some(401) | Some(403) => io::ErrorKind::PermissionDenied,
+                        Some(404) => io::ErrorKind::NotFound,
+                        _ => io::ErrorKind::Other,
+                    }
+                }
+            }
+            Error::UnexpectedStatus { status, .. } => match status {
+                401 | 403 => io::ErrorKind::PermissionDenied,
+                404 | 410 => io::ErrorKind::NotFound,
+                _ => io::ErrorKind::Other,
+            },
+            Error::JsonError(_)
+            | Error::InvalidResponse { .. }
+            | Error::UnexpectedContentType { .. }
+            | Error::CsvError { .. }
+            | Error::OpmlError { .. } => io::ErrorKind::InvalidData,
+            Error::ValidationError(_) | Error::UrlError(_) | Error::InvalidConfiguration { .. } => {
+                io::ErrorKind::InvalidInput
+            }
+            #[cfg(feature = "subscriptions")]
+            Error::ConcurrentModification(_) => io::ErrorKind::Other,
+            Error::DeviceNotFound(_) => io::ErrorKind::NotFound,
+            Error::SubscriptionsNotPublic(_) => io::ErrorKind::PermissionDenied,
+            Error::ReadOnly { .. } => io::ErrorKind::PermissionDenied,
+            Error::Timeout { .. } => io::ErrorKind::TimedOut,
+            Error::ResponseTooLarge { .. } => io::ErrorKind::InvalidData,
+            Error::Maintenance { .. } => io::ErrorKind::Other,
+        }
+    }
+}
+
+fn status_code(status: u16) -> ErrorCode {
+    match status {
+        401 | 403 => ErrorCode::Unauthorized,
+        404 | 410 => ErrorCode::NotFound,
+        429 => ErrorCode::RateLimited,
+        500..=599 => ErrorCode::ServerError,
+        _ => ErrorCode::InvalidResponse,
+    }
+}
+
+impl ErrorCode {
+    /// Returns the identifier of the code in snake case, e.g. `"device_not_found"`
+    ///
+    /// Identifiers are stable, so they can be used as keys of translation catalogs.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::Network => "network",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::ServerError => "server_error",
+            ErrorCode::Maintenance => "maintenance",
+            ErrorCode::InvalidResponse => "invalid_response",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::InvalidConfiguration => "invalid_configuration",
+            ErrorCode::ConcurrentModification => "concurrent_modification",
+            ErrorCode::DeviceNotFound => "device_not_found",
+            ErrorCode::SubscriptionsNotPublic => "subscriptions_not_public",
+            ErrorCode::ReadOnly => "read_only",
+            ErrorCode::Io => "io",
+        }
+    }
+
+    /// Returns a concise, non-technical English description for showing to users
+    pub fn user_message(self) -> &'static str {
+        match self {
+            ErrorCode::Network => "The server couldn't be reached. Check your internet connection.",
+            ErrorCode::Timeout => "The server took too long to respond. Please try again later.",
+            ErrorCode::Unauthorized => "Your username or password is incorrect.",
+            ErrorCode::NotFound => "The requested data doesn't exist on the server.",
+            ErrorCode::RateLimited => "Too many requests. Please wait a moment and try again.",
+            ErrorCode::ServerError => "The server has a problem. Please try again later.",
+            ErrorCode::Maintenance => "The service is in maintenance. Please try again later.",
+            ErrorCode::InvalidResponse => "The server sent a response that couldn't be read.",
+            ErrorCode::InvalidInput => "Some of the data is invalid and wasn't sent.",
+            ErrorCode::InvalidConfiguration => "Your account settings are incomplete or invalid.",
+            ErrorCode::ConcurrentModification => {
+                "The data was changed on another device. Please synchronize again."
+            }
+            ErrorCode::DeviceNotFound => "This device isn't registered with your account.",
+            ErrorCode::SubscriptionsNotPublic => "The subscriptions of this user aren't public.",
+            ErrorCode::ReadOnly => "Changes aren't allowed in read-only mode.",
+            ErrorCode::Io => "Data couldn't be read or saved on this device.",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            ValidationError::IncompletePlayAction(episode) => write!(
+                f,
+                "play action for {} requires both started and total or neither",
+                episode
+            ),
+            ValidationError::ConflictingChange(url) => {
+                write!(f, "{} is both added and removed", url)
+            }
+            ValidationError::InvalidFeedUrl(url) => {
+                write!(f, "{} is not a valid http or https feed URL", url)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ErrorCode, ValidationError};
+    use std::error::Error as _;
+    use std::io;
+    use std::time::Duration;
+
+    #[test]
+    fn convert_into_io_error() {
+        let io_error: io::Error = Error::DeviceNotFound(String::from("phone")).into();
+        assert_eq!(io::ErrorKind::NotFound, io_error.kind());
+        assert_eq!("device phone not found", io_error.to_string());
+
+        let io_error: io::Error =
+            Error::ValidationError(ValidationError::InvalidFeedUrl(String::from("feed"))).into();
+        assert_eq!(io::ErrorKind::InvalidInput, io_error.kind());
+
+        let io_error: io::Error =
+            Error::IoError(io::Error::new(io::ErrorKind::BrokenPipe, "closed")).into();
+        assert_eq!(io::ErrorKind::BrokenPipe, io_error.kind());
+        assert_eq!("closed", io_error.to_string());
+    }
+
+    #[test]
+    fn codes_of_statuses() {
+        let status = |status| Error::UnexpectedStatus {
+            url: String::from("https://gpodder.net/api/2/devices/username.json"),
+            status,
+        };
+
+        assert_eq!(ErrorCode::Unauthorized, status(401).code());
+        assert_eq!(ErrorCode::RateLimited, status(429).code());
+        assert_eq!(ErrorCode::ServerError, status(503).code());
+        assert_eq!(ErrorCode::InvalidResponse, status(302).code());
+        assert_eq!("unauthorized", status(401).code().to_string());
+        assert_eq!(
+            ErrorCode::Unauthorized.user_message(),
+            status(403).user_message()
+        );
+    }
+
+    #[test]
+    fn invalid_response_names_endpoint() {
+        let error = Error::InvalidResponse {
+            endpoint: String::from("/api/2/devices/username.json"),
+            source: serde_json::from_str::<u8>("[]").unwrap_err(),
+        };
+
+        assert_eq!(
+            "invalid response from /api/2/devices/username.json",
+            error.to_string()
+        );
+        assert!(error.source().is_some());
+
+        let io_error: io::Error = error.into();
+        assert_eq!(io::ErrorKind::InvalidData, io_error.kind());
+    }
+
+    #[test]
+    fn wrapped_errors_are_sources() {
+        let error = Error::IoError(io::Error::other("disk full"));
+        assert_eq!("disk full", error.source().unwrap().to_string());
+
+        let json_error = serde_json::from_str::<u8>("[]").unwrap_err();
+        let message = json_error.to_string();
+        let error = Error::JsonError(json_error);
+        assert_eq!(message, error.source().unwrap().to_string());
+    }
+
+    #[test]
+    fn timeout_is_retryable() {
+        let error = Error::Timeout {
+            endpoint: String::from("/api/2/devices/username.json"),
+            elapsed: Duration::from_millis(1500),
+        };
+
+        assert!(error.is_retryable());
+        assert_eq!(
+            "/api/2/devices/username.json timed out after 1500 ms",
+            error.to_string()
+        );
+
+        let io_error: io::Error = error.into();
+        assert_eq!(io::ErrorKind::TimedOut, io_error.kind());
+    }
+
+    #[test]
+    fn response_too_large_is_invalid_response() {
+        let error = Error::ResponseTooLarge {
+            endpoint: String::from("/api/2/devices/username.json"),
+            limit: 1024,
+        };
+
+        assert!(!error.is_retryable());
+        assert_eq!(ErrorCode::InvalidResponse, error.code());
+        assert_eq!(
+            "response of /api/2/devices/username.json exceeds 1024 bytes",
+            error.to_string()
+        );
+
+        let io_error: io::Error = error.into();
+        assert_eq!(io::ErrorKind::InvalidData, io_error.kind());
+    }
+
+    #[test]
+    fn maintenance_is_retryable() {
+        let error = Error::Maintenance {
+            endpoint: String::from("/api/2/devices/username.json"),
+            retry_after: Some(Duration::from_secs(120)),
+        };
+
+        assert!(error.is_retryable());
+        assert_eq!(ErrorCode::Maintenance, error.code());
+        assert_eq!("maintenance", error.code().as_str());
+        assert_eq!(
+            "/api/2/devices/username.json unavailable due to maintenance for 120 s",
+            error.to_string()
+        );
+    }
+}
@@ -1,9 +1,12 @@
 //! [Favorites API](https://gpoddernet.readthedocs.io/en/latest/api/reference/favorites.html)
 
+use crate::client::ApiArea;
 use crate::client::AuthenticatedClient;
 use crate::client::DeviceClient;
 use crate::directory::Episode;
 use crate::error::Error;
+use crate::reference::EpisodeRef;
+use std::collections::HashSet;
 
 /// see [get_favorite_episodes](GetFavoriteEpisodes::get_favorite_episodes)
 pub trait GetFavoriteEpisodes {
@@ -28,16 +31,87 @@ pub trait GetFavoriteEpisodes {
     ///
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/favorites.html#get-favorite-episodes)
     fn get_favorite_episodes(&self) -> Result<Vec<Episode>, Error>;
+
+    /// Get Favorite Episodes and compare them with a previous snapshot
+    ///
+    /// Episodes are matched by their [EpisodeRef], so changed metadata of a favorite isn't reported as a change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::favorite::GetFavoriteEpisodes;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    ///
+    /// let snapshot = client.get_favorite_episodes()?;
+    /// let update = client.refresh_favorite_episodes(&snapshot)?;
+    /// for episode in &update.added {
+    ///     println!("new favorite: {}", episode);
+    /// }
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn refresh_favorite_episodes(
+        &self,
+        previous: &[Episode],
+    ) -> Result<FavoriteEpisodesUpdate, Error> {
+        let episodes = self.get_favorite_episodes()?;
+
+        let current: HashSet<EpisodeRef> = episodes.iter().map(EpisodeRef::from).collect();
+        let previous_refs: HashSet<EpisodeRef> = previous.iter().map(EpisodeRef::from).collect();
+
+        let added = episodes
+            .iter()
+            .filter(|episode| !previous_refs.contains(&EpisodeRef::from(*episode)))
+            .cloned()
+            .collect();
+        let removed = previous
+            .iter()
+            .filter(|episode| !current.contains(&EpisodeRef::from(*episode)))
+            .cloned()
+            .collect();
+
+        Ok(FavoriteEpisodesUpdate {
+            episodes,
+            added,
+            removed,
+        })
+    }
+}
+
+/// Result of [refresh_favorite_episodes](GetFavoriteEpisodes::refresh_favorite_episodes)
+#[derive(Debug, Clone)]
+pub struct FavoriteEpisodesUpdate {
+    /// all current favorites, to be used as the next snapshot
+    pub episodes: Vec<Episode>,
+    /// favorites missing in the previous snapshot
+    pub added: Vec<Episode>,
+    /// favorites of the previous snapshot which aren't favorites anymore
+    pub removed: Vec<Episode>,
+}
+
+impl FavoriteEpisodesUpdate {
+    /// Returns `true` if favorites were neither added nor removed
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
 }
 
 impl GetFavoriteEpisodes for AuthenticatedClient {
     fn get_favorite_episodes(&self) -> Result<Vec<Episode>, Error> {
-        Ok(self
-            .get(&format!(
-                "https://gpodder.net/api/2/favorites/{}.json",
-                self.username
+        let episodes: Vec<Episode> = self
+            .get(&self.api_url(
+                ApiArea::Favorites,
+                &format!("favorites/{}.json", self.username),
             ))?
-            .json()?)
+            .json()?;
+        Ok(episodes
+            .into_iter()
+            .map(|episode| self.public_client.rewrite_episode(episode))
+            .collect())
     }
 }
 
@@ -46,3 +120,50 @@ impl GetFavoriteEpisodes for DeviceClient {
         self.authenticated_client.get_favorite_episodes()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::GetFavoriteEpisodes;
+    use crate::directory::Episode;
+    use crate::error::Error;
+
+    #[test]
+    fn deserialize_favorites_with_empty_links() {
+        let episodes: Vec<Episode> =
+            serde_json::from_str(include_str!("../tests/fixtures/favorites.json")).unwrap();
+
+        assert_eq!(2, episodes.len());
+        assert!(episodes[0].website.is_some());
+        assert!(episodes[0].mygpo_link.is_some());
+        assert_eq!(None, episodes[1].website);
+        assert_eq!(None, episodes[1].mygpo_link);
+    }
+
+    struct FakeFavorites(Vec<Episode>);
+
+    impl GetFavoriteEpisodes for FakeFavorites {
+        fn get_favorite_episodes(&self) -> Result<Vec<Episode>, Error> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn refresh_reports_added_and_removed_favorites() {
+        let episodes: Vec<Episode> =
+            serde_json::from_str(include_str!("../tests/fixtures/favorites.json")).unwrap();
+        let client = FakeFavorites(vec![episodes[1].clone()]);
+
+        let update = client.refresh_favorite_episodes(&episodes[..1]).unwrap();
+
+        assert_eq!(1, update.episodes.len());
+        assert_eq!(1, update.added.len());
+        assert_eq!(episodes[1].url, update.added[0].url);
+        assert_eq!(1, update.removed.len());
+        assert_eq!(episodes[0].url, update.removed[0].url);
+        assert!(!update.is_unchanged());
+        assert!(client
+            .refresh_favorite_episodes(&update.episodes)
+            .unwrap()
+            .is_unchanged());
+    }
+}
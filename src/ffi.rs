@@ -0,0 +1,202 @@
+//! C ABI for applications not written in Rust
+//!
+//! Enabled by the Cargo feature `ffi`. The crate is built as Rust library only, so dependent crates don't have to link anything else. Build a shared or static library with `cargo rustc --release --lib --features ffi --crate-type cdylib` or `--crate-type staticlib` and declare the functions in C like this:
+//!
+//! ```c
+//! typedef struct MygpoClient MygpoClient;
+//!
+//! MygpoClient *mygpo_client_new(const char *username, const char *password, const char *device_id);
+//! void mygpo_client_free(MygpoClient *client);
+//! char *mygpo_get_subscriptions(const MygpoClient *client);
+//! char *mygpo_upload_episode_actions(const MygpoClient *client, const char *actions);
+//! const char *mygpo_last_error(void);
+//! void mygpo_string_free(char *string);
+//! ```
+//!
+//! Data is exchanged as JSON in the format of the [gpodder.net API](https://gpoddernet.readthedocs.io/en/latest/api/reference/). Functions return `NULL` on failure; the message of the error is then available from `mygpo_last_error` on the same thread. Strings returned by the library must be released with `mygpo_string_free`.
+
+#![allow(unsafe_code)]
+
+use crate::client::{AuthenticatedClient, DeviceClient};
+use crate::episode::{EpisodeAction, UploadEpisodeActions};
+use crate::error::Error;
+use crate::reference::{DeviceId, Username};
+use crate::subscription::SubscriptionsOfDevice;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::io::{self, ErrorKind};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Opaque client handle, see [mygpo_client_new]
+#[derive(Debug)]
+pub struct MygpoClient(DeviceClient);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Create a client for the given device
+///
+/// Returns `NULL` if an argument is `NULL`, not valid UTF-8 or rejected by [DeviceClient::try_new]. Release the client with [mygpo_client_free].
+///
+/// # Safety
+///
+/// All arguments must be `NULL` or point to NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn mygpo_client_new(
+    username: *const c_char,
+    password: *const c_char,
+    device_id: *const c_char,
+) -> *mut MygpoClient {
+    guard(ptr::null_mut(), || {
+        let client = DeviceClient::try_new(
+            &Username::from(to_str(username)?),
+            to_str(password)?,
+            &DeviceId::from(to_str(device_id)?),
+        )?;
+        Ok(Box::into_raw(Box::new(MygpoClient(client))))
+    })
+}
+
+/// Release a client created by [mygpo_client_new]
+///
+/// # Safety
+///
+/// `client` must be `NULL` or returned by [mygpo_client_new] and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn mygpo_client_free(client: *mut MygpoClient) {
+    if !client.is_null() {
+        drop(Box::from_raw(client));
+    }
+}
+
+/// Returns the feed URLs of the subscriptions of the device as JSON array, see [get_subscriptions_of_device](SubscriptionsOfDevice::get_subscriptions_of_device)
+///
+/// Returns `NULL` on failure. Release the result with [mygpo_string_free].
+///
+/// # Safety
+///
+/// `client` must be `NULL` or a client returned by [mygpo_client_new].
+#[no_mangle]
+pub unsafe extern "C" fn mygpo_get_subscriptions(client: *const MygpoClient) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let subscriptions = to_client(client)?.get_subscriptions_of_device()?;
+        to_c_string(serde_json::to_string(&subscriptions)?)
+    })
+}
+
+/// Upload episode actions given as JSON array, see [upload_episode_actions](UploadEpisodeActions::upload_episode_actions)
+///
+/// Returns the response as JSON object, or `NULL` on failure. Release the result with [mygpo_string_free].
+///
+/// # Safety
+///
+/// `client` must be `NULL` or a client returned by [mygpo_client_new]. `actions` must be `NULL` or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn mygpo_upload_episode_actions(
+    client: *const MygpoClient,
+    actions: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let actions: Vec<EpisodeAction> = serde_json::from_str(to_str(actions)?)?;
+        let client: &AuthenticatedClient = to_client(client)?.as_ref();
+        let response = client.upload_episode_actions(&actions)?;
+        to_c_string(serde_json::to_string(&response)?)
+    })
+}
+
+/// Returns the message of the last error on the calling thread, or `NULL` if no call failed yet
+///
+/// The message is owned by the library and stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn mygpo_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Release a string returned by the library
+///
+/// # Safety
+///
+/// `string` must be `NULL` or returned by a function of this module other than [mygpo_last_error] and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn mygpo_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+fn guard<T, F: FnOnce() -> Result<T, Error>>(on_error: T, f: F) -> T {
+    // unwinding into C is undefined behavior, so panics are reported like errors
+    let message = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => return value,
+        Ok(Err(error)) => error.to_string(),
+        Err(_) => String::from("panic in mygpoclient"),
+    };
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+    on_error
+}
+
+unsafe fn to_str<'a>(string: *const c_char) -> Result<&'a str, Error> {
+    if string.is_null() {
+        return Err(invalid_argument("unexpected NULL string"));
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|e| invalid_argument(&e.to_string()))
+}
+
+unsafe fn to_client<'a>(client: *const MygpoClient) -> Result<&'a DeviceClient, Error> {
+    client
+        .as_ref()
+        .map(|client| &client.0)
+        .ok_or_else(|| invalid_argument("unexpected NULL client"))
+}
+
+fn to_c_string(string: String) -> Result<*mut c_char, Error> {
+    Ok(CString::new(string)
+        .map_err(|e| invalid_argument(&e.to_string()))?
+        .into_raw())
+}
+
+fn invalid_argument(message: &str) -> Error {
+    io::Error::new(ErrorKind::InvalidInput, message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mygpo_client_free, mygpo_client_new, mygpo_last_error, mygpo_string_free};
+    use std::ffi::{CStr, CString};
+    use std::ptr;
+
+    #[test]
+    fn invalid_client_reports_last_error() {
+        let username = CString::new("stefan").unwrap();
+        let password = CString::new("secret").unwrap();
+        let device_id = CString::new("invalid device").unwrap();
+
+        unsafe {
+            let client = mygpo_client_new(username.as_ptr(), password.as_ptr(), ptr::null());
+            assert!(client.is_null());
+            assert!(CStr::from_ptr(mygpo_last_error())
+                .to_string_lossy()
+                .contains("NULL"));
+
+            let client = mygpo_client_new(username.as_ptr(), password.as_ptr(), device_id.as_ptr());
+            assert!(client.is_null());
+
+            let device_id = CString::new("laptop").unwrap();
+            let client = mygpo_client_new(username.as_ptr(), password.as_ptr(), device_id.as_ptr());
+            assert!(!client.is_null());
+            mygpo_client_free(client);
+            mygpo_string_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! [Formats](https://gpoddernet.readthedocs.io/en/latest/api/reference/general.html#formats) of raw API responses
+
+use std::fmt;
+
+/// Output format of an API endpoint
+///
+/// Not every endpoint supports every format. See the documentation of the respective endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum Format {
+    /// JSON
+    Json,
+    /// JSON wrapped in a call of the contained JavaScript function name
+    Jsonp(String),
+    /// OPML
+    Opml,
+    /// plain text with one URL per line
+    Txt,
+    /// XML
+    Xml,
+}
+
+impl Format {
+    /// File extension of the endpoint URL, without leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Jsonp(_) => "jsonp",
+            Format::Opml => "opml",
+            Format::Txt => "txt",
+            Format::Xml => "xml",
+        }
+    }
+
+    pub(crate) fn query_parameters(&self) -> Vec<(&str, &str)> {
+        match self {
+            Format::Jsonp(callback) => vec![("jsonp", callback.as_str())],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+
+    #[test]
+    fn jsonp_query_parameters() {
+        assert_eq!(
+            vec![("jsonp", "callback")],
+            Format::Jsonp(String::from("callback")).query_parameters()
+        );
+        assert!(Format::Opml.query_parameters().is_empty());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("txt", format!("{}", Format::Txt));
+    }
+}
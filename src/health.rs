@@ -0,0 +1,129 @@
+//! Connection health checks
+//!
+//! Useful for showing the connectivity status to the user before attempting a full synchronization.
+
+use crate::client::{ApiArea, ApiResponse, AuthenticatedClient, DeviceClient, PublicClient};
+use crate::error::Error;
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::fmt;
+
+/// Result of [check_connection](CheckConnection::check_connection)
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ConnectionStatus {
+    /// service is reachable and credentials (if any) are valid
+    Ok,
+    /// service is reachable, but rejected the credentials
+    AuthFailed,
+    /// service is reachable, but responded with a server error
+    ServerDown,
+    /// service is not reachable, e.g. due to DNS, TLS or connection errors
+    NetworkError,
+    /// service responded with the contained unexpected HTTP status code
+    UnexpectedStatus(u16),
+}
+
+/// see [check_connection](CheckConnection::check_connection)
+pub trait CheckConnection {
+    /// Check Connection
+    ///
+    /// Performs a cheap request and classifies the result. [PublicClient] requests the toplist with one entry, authenticated clients request their list of devices to also verify the credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::health::{CheckConnection, ConnectionStatus};
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    ///
+    /// assert_eq!(ConnectionStatus::Ok, client.check_connection());
+    /// ```
+    fn check_connection(&self) -> ConnectionStatus;
+}
+
+impl CheckConnection for PublicClient {
+    fn check_connection(&self) -> ConnectionStatus {
+        classify(self.get(&self.url(ApiArea::Directory, "toplist/1.json")))
+    }
+}
+
+impl CheckConnection for AuthenticatedClient {
+    fn check_connection(&self) -> ConnectionStatus {
+        classify(
+            self.get(&self.api_url(ApiArea::Devices, &format!("devices/{}.json", self.username))),
+        )
+    }
+}
+
+impl CheckConnection for DeviceClient {
+    fn check_connection(&self) -> ConnectionStatus {
+        self.authenticated_client.check_connection()
+    }
+}
+
+impl ConnectionStatus {
+    /// Returns `true` if the status is [Ok](ConnectionStatus::Ok)
+    pub fn is_ok(self) -> bool {
+        self == ConnectionStatus::Ok
+    }
+}
+
+fn classify(result: Result<ApiResponse, Error>) -> ConnectionStatus {
+    match result {
+        Ok(response) => classify_status(response.status),
+        Err(_) => ConnectionStatus::NetworkError,
+    }
+}
+
+fn classify_status(status: StatusCode) -> ConnectionStatus {
+    if status.is_success() {
+        ConnectionStatus::Ok
+    } else if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        ConnectionStatus::AuthFailed
+    } else if status.is_server_error() {
+        ConnectionStatus::ServerDown
+    } else {
+        ConnectionStatus::UnexpectedStatus(status.as_u16())
+    }
+}
+
+impl fmt::Display for ConnectionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionStatus::Ok => write!(f, "connected"),
+            ConnectionStatus::AuthFailed => write!(f, "authentication failed"),
+            ConnectionStatus::ServerDown => write!(f, "server down"),
+            ConnectionStatus::NetworkError => write!(f, "network error"),
+            ConnectionStatus::UnexpectedStatus(status) => {
+                write!(f, "unexpected status {}", status)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_status, ConnectionStatus};
+    use reqwest::StatusCode;
+
+    #[test]
+    fn classify_status_codes() {
+        assert_eq!(ConnectionStatus::Ok, classify_status(StatusCode::OK));
+        assert_eq!(
+            ConnectionStatus::AuthFailed,
+            classify_status(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            ConnectionStatus::ServerDown,
+            classify_status(StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert_eq!(
+            ConnectionStatus::UnexpectedStatus(404),
+            classify_status(StatusCode::NOT_FOUND)
+        );
+    }
+}
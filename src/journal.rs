@@ -0,0 +1,269 @@
+//! Journal of local subscription changes
+//!
+//! Applications record every subscription change of the user in a [Journal]. Changes can be undone and redone until they are [uploaded](Journal::upload), which compacts them into a single delta for [upload_subscription_changes](SubscriptionChanges::upload_subscription_changes).
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::Error;
+use crate::subscription::{SubscriptionChanges, UploadSubscriptionChangesResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use url::Url;
+
+/// Subscription change recorded in a [Journal]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum JournalChange {
+    /// subscribe to the podcast with the contained feed URL
+    Add(Url),
+    /// unsubscribe from the podcast with the contained feed URL
+    Remove(Url),
+}
+
+/// Entry of a [Journal]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct JournalEntry {
+    /// recorded change
+    pub change: JournalChange,
+    /// UTC timestamp when the change was applied locally
+    pub timestamp: NaiveDateTime,
+}
+
+/// Log of local subscription changes with undo and redo
+///
+/// Serializable, so applications can persist pending changes between sessions. Changes are timestamped by the [SystemClock], or the [Clock] attached with [with_clock](Journal::with_clock), which isn't serialized.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::journal::Journal;
+/// use url::Url;
+///
+/// let mut journal = Journal::new();
+/// journal.add(Url::parse("http://example.com/feed.rss")?);
+/// journal.remove(Url::parse("http://example.org/podcast.php")?);
+/// journal.undo();
+///
+/// let (add, remove) = journal.compact();
+/// assert_eq!(vec![Url::parse("http://example.com/feed.rss")?], add);
+/// assert!(remove.is_empty());
+/// # Ok::<(), url::ParseError>(())
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+    undone: Vec<JournalEntry>,
+    #[serde(skip, default = "system_clock")]
+    clock: Arc<dyn Clock>,
+}
+
+fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+impl Journal {
+    /// Create an empty journal
+    pub fn new() -> Journal {
+        Journal::default()
+    }
+
+    /// Timestamp changes with the given [Clock] instead of the [SystemClock]
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Journal {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Record a subscription to the given feed URL
+    pub fn add(&mut self, url: Url) {
+        self.record(JournalChange::Add(url), self.clock.now());
+    }
+
+    /// Record an unsubscription from the given feed URL
+    pub fn remove(&mut self, url: Url) {
+        self.record(JournalChange::Remove(url), self.clock.now());
+    }
+
+    /// Record a change with an explicit timestamp
+    ///
+    /// Discards all changes that could be [redone](Journal::redo).
+    pub fn record(&mut self, change: JournalChange, timestamp: NaiveDateTime) {
+        self.undone.clear();
+        self.entries.push(JournalEntry { change, timestamp });
+    }
+
+    /// Undo the most recent change and return it
+    pub fn undo(&mut self) -> Option<&JournalEntry> {
+        let entry = self.entries.pop()?;
+        self.undone.push(entry);
+        self.undone.last()
+    }
+
+    /// Redo the most recently undone change and return it
+    pub fn redo(&mut self) -> Option<&JournalEntry> {
+        let entry = self.undone.pop()?;
+        self.entries.push(entry);
+        self.entries.last()
+    }
+
+    /// Returns the recorded changes, oldest first
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Returns `true` if there are no recorded changes
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Apply the recorded changes to the given subscription list
+    ///
+    /// The order of `subscriptions` is kept and added URLs are appended.
+    pub fn replay(&self, subscriptions: &[Url]) -> Vec<Url> {
+        let mut result = subscriptions.to_vec();
+        for entry in &self.entries {
+            match &entry.change {
+                JournalChange::Add(url) => {
+                    if !result.contains(url) {
+                        result.push(url.clone());
+                    }
+                }
+                JournalChange::Remove(url) => result.retain(|u| u != url),
+            }
+        }
+        result
+    }
+
+    /// Compact the recorded changes into URLs to add and URLs to remove
+    ///
+    /// Only the last change of each URL counts. The URLs are ordered by their last change.
+    pub fn compact(&self) -> (Vec<Url>, Vec<Url>) {
+        let mut last_changes: HashMap<&Url, usize> = HashMap::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let url = match &entry.change {
+                JournalChange::Add(url) | JournalChange::Remove(url) => url,
+            };
+            last_changes.insert(url, index);
+        }
+
+        let mut add = Vec::new();
+        let mut remove = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            match &entry.change {
+                JournalChange::Add(url) if last_changes.get(url) == Some(&index) => {
+                    add.push(url.clone())
+                }
+                JournalChange::Remove(url) if last_changes.get(url) == Some(&index) => {
+                    remove.push(url.clone())
+                }
+                _ => {}
+            }
+        }
+        (add, remove)
+    }
+
+    /// Upload the [compacted](Journal::compact) changes and clear the journal on success
+    ///
+    /// Nothing is sent if the journal is empty.
+    pub fn upload<C: SubscriptionChanges>(
+        &mut self,
+        client: &C,
+    ) -> Result<Option<UploadSubscriptionChangesResponse>, Error> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+
+        let (add, remove) = self.compact();
+        let response = client.upload_subscription_changes(&add, &remove)?;
+        self.entries.clear();
+        self.undone.clear();
+        Ok(Some(response))
+    }
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Journal {
+            entries: Vec::new(),
+            undone: Vec::new(),
+            clock: system_clock(),
+        }
+    }
+}
+
+/// Journals are equal if they hold the same changes, regardless of their clocks
+impl PartialEq for Journal {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries && self.undone == other.undone
+    }
+}
+
+impl Eq for Journal {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Journal, JournalChange};
+    use crate::clock::ManualClock;
+    use chrono::{Duration, NaiveDate};
+    use url::Url;
+
+    fn url(path: &str) -> Url {
+        Url::parse("http://example.com/")
+            .unwrap()
+            .join(path)
+            .unwrap()
+    }
+
+    #[test]
+    fn compact_keeps_last_change_per_url() {
+        let mut journal = Journal::new();
+        journal.add(url("a"));
+        journal.add(url("b"));
+        journal.remove(url("a"));
+        journal.remove(url("c"));
+        journal.add(url("c"));
+
+        assert_eq!(
+            (vec![url("b"), url("c")], vec![url("a")]),
+            journal.compact()
+        );
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut journal = Journal::new();
+        journal.add(url("a"));
+        journal.remove(url("b"));
+
+        assert_eq!(
+            JournalChange::Remove(url("b")),
+            journal.undo().unwrap().change
+        );
+        assert_eq!(vec![url("x"), url("a")], journal.replay(&[url("x")]));
+
+        assert_eq!(
+            JournalChange::Remove(url("b")),
+            journal.redo().unwrap().change
+        );
+        assert!(journal.redo().is_none());
+        assert_eq!(vec![url("a")], journal.replay(&[url("b")]));
+
+        journal.undo();
+        journal.add(url("c"));
+        assert!(journal.redo().is_none());
+    }
+
+    #[test]
+    fn timestamp_changes_with_clock() {
+        let start = NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0);
+        let clock = ManualClock::new(start);
+        let mut journal = Journal::new().with_clock(clock.clone());
+
+        journal.add(url("a"));
+        clock.advance(Duration::minutes(5));
+        journal.remove(url("a"));
+
+        assert_eq!(start, journal.entries()[0].timestamp);
+        assert_eq!(start + Duration::minutes(5), journal.entries()[1].timestamp);
+        assert_eq!(journal, journal.clone().with_clock(ManualClock::new(start)));
+    }
+}
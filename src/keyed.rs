@@ -0,0 +1,139 @@
+//! Identity of API types by a single key field
+//!
+//! Most types returned by the API are identified by a single field, e.g. a podcast by its feed URL or a device by its ID. Their [PartialEq], [Eq], [Ord], [PartialOrd] and [Hash](std::hash::Hash) implementations only consider this key, so e.g. a podcast with an updated subscriber count is still the same podcast.
+
+use crate::protocol::devices::Device;
+use crate::protocol::directory::{self, Episode, Tag};
+use crate::protocol::subscriptions;
+use crate::protocol::suggestions::Suggestion;
+use std::hash::Hash;
+use url::Url;
+
+/// Type identified by a single key
+pub trait Keyed {
+    /// type of the key
+    type Key: Ord + Hash + ?Sized;
+
+    /// Returns the key identifying this value
+    fn key(&self) -> &Self::Key;
+}
+
+/// Implements [PartialEq], [Eq], [Ord], [PartialOrd] and [Hash](std::hash::Hash) by [Keyed::key], which keeps them consistent with each other
+macro_rules! impl_cmp_by_key {
+    ($type:ty) => {
+        impl PartialEq for $type {
+            fn eq(&self, other: &Self) -> bool {
+                $crate::keyed::Keyed::key(self) == $crate::keyed::Keyed::key(other)
+            }
+        }
+
+        impl Eq for $type {}
+
+        impl Ord for $type {
+            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                $crate::keyed::Keyed::key(self).cmp($crate::keyed::Keyed::key(other))
+            }
+        }
+
+        impl PartialOrd for $type {
+            fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl ::std::hash::Hash for $type {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                ::std::hash::Hash::hash($crate::keyed::Keyed::key(self), state);
+            }
+        }
+    };
+}
+
+impl Keyed for Device {
+    type Key = str;
+
+    fn key(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Keyed for Tag {
+    type Key = str;
+
+    fn key(&self) -> &str {
+        &self.tag
+    }
+}
+
+impl Keyed for Episode {
+    type Key = Url;
+
+    fn key(&self) -> &Url {
+        &self.url
+    }
+}
+
+impl Keyed for subscriptions::Podcast {
+    type Key = Url;
+
+    fn key(&self) -> &Url {
+        &self.url
+    }
+}
+
+impl Keyed for directory::Podcast {
+    type Key = Url;
+
+    fn key(&self) -> &Url {
+        self.podcast.key()
+    }
+}
+
+impl Keyed for Suggestion {
+    type Key = Url;
+
+    fn key(&self) -> &Url {
+        &self.url
+    }
+}
+
+impl_cmp_by_key!(Device);
+impl_cmp_by_key!(Tag);
+impl_cmp_by_key!(Episode);
+impl_cmp_by_key!(subscriptions::Podcast);
+impl_cmp_by_key!(directory::Podcast);
+impl_cmp_by_key!(Suggestion);
+
+#[cfg(test)]
+mod tests {
+    use super::Keyed;
+    use crate::protocol::directory::Tag;
+    use std::cmp::Ordering;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn comparisons_only_consider_key() {
+        let tag1 = Tag {
+            title: String::from("Technology"),
+            tag: String::from("technology"),
+            usage: 530,
+        };
+        let tag2 = Tag {
+            title: String::from("Tech"),
+            tag: String::from("technology"),
+            usage: 7,
+        };
+
+        assert_eq!("technology", tag1.key());
+        assert_eq!(tag1, tag2);
+        assert_eq!(Ordering::Equal, tag1.cmp(&tag2));
+        assert_eq!(hash(&tag1), hash(&tag2));
+    }
+}
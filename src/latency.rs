@@ -0,0 +1,262 @@
+//! Warnings about requests exceeding a latency budget
+//!
+//! Attach a [LatencyBudget] and a [SlowRequestHandler] to a client with e.g. [with_latency_budget](crate::client::AuthenticatedClient::with_latency_budget). Every request taking longer than its budget is reported as [SlowRequest], which helps finding the endpoints dominating the synchronization time in the field. The duration covers sending the request and reading the whole response; every attempt of a [retried](crate::retry) request is measured on its own.
+
+use crate::client::{AuthenticatedClient, DeviceClient, PublicClient};
+use reqwest::Method;
+use serde::Serialize;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Request which took longer than its [LatencyBudget]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SlowRequest {
+    /// HTTP method of the request
+    pub method: String,
+    /// path of the requested URL
+    pub endpoint: String,
+    /// time passed between sending the request and reading the whole response or giving up
+    pub elapsed: Duration,
+    /// budget the request exceeded
+    pub budget: Duration,
+    /// status code of the response, if one was received
+    pub status: Option<u16>,
+}
+
+/// Hook notified about [SlowRequest]s
+///
+/// Called on the thread which sent the request, after the response was read, so implementations should return quickly.
+pub trait SlowRequestHandler: fmt::Debug + Send + Sync {
+    /// Handle a request which exceeded its budget
+    fn slow_request(&self, request: &SlowRequest);
+}
+
+/// Maximum duration of requests, optionally per endpoint
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::DeviceClient;
+/// use mygpoclient::latency::{LatencyBudget, SlowRequestLog};
+/// use mygpoclient::reference::{DeviceId, Username};
+/// use std::time::Duration;
+///
+/// let log = SlowRequestLog::new(100);
+/// let budget = LatencyBudget::new(Duration::from_secs(2))
+///     .with_endpoint("/episodes/", Duration::from_secs(10));
+/// let client = DeviceClient::new(&Username::from("username"), "password", &DeviceId::from("device"))
+///     .with_latency_budget(budget, log.clone());
+///
+/// // after synchronizing
+/// for request in log.slow_requests() {
+///     println!("{} {} took {} ms", request.method, request.endpoint, request.elapsed.as_millis());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LatencyBudget {
+    default: Duration,
+    endpoints: Vec<(String, Duration)>,
+}
+
+/// [SlowRequestHandler] keeping the last [SlowRequest]s
+///
+/// Clones share the same requests, so the log can be kept by the application after attaching it to a client.
+#[derive(Debug, Clone)]
+pub struct SlowRequestLog {
+    capacity: usize,
+    requests: Arc<Mutex<Vec<SlowRequest>>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct LatencyMonitor {
+    budget: LatencyBudget,
+    handler: Arc<dyn SlowRequestHandler>,
+}
+
+impl LatencyBudget {
+    /// Create budget allowing each request to take `default`
+    pub fn new(default: Duration) -> LatencyBudget {
+        LatencyBudget {
+            default,
+            endpoints: Vec::new(),
+        }
+    }
+
+    /// Allow requests whose path contains `path_fragment`, e.g. `"/episodes/"`, to take `budget`
+    ///
+    /// If several fragments match, the longest one applies.
+    pub fn with_endpoint(mut self, path_fragment: &str, budget: Duration) -> LatencyBudget {
+        self.endpoints.push((path_fragment.to_owned(), budget));
+        self
+    }
+
+    /// Returns the budget of requests to the given path
+    pub fn budget(&self, endpoint: &str) -> Duration {
+        self.endpoints
+            .iter()
+            .filter(|(fragment, _)| endpoint.contains(fragment.as_str()))
+            .max_by_key(|(fragment, _)| fragment.len())
+            .map_or(self.default, |(_, budget)| *budget)
+    }
+}
+
+impl SlowRequestLog {
+    /// Create log keeping the last `capacity` slow requests
+    pub fn new(capacity: usize) -> SlowRequestLog {
+        SlowRequestLog {
+            capacity,
+            requests: Arc::default(),
+        }
+    }
+
+    /// Returns the logged requests, oldest first
+    pub fn slow_requests(&self) -> Vec<SlowRequest> {
+        self.lock().clone()
+    }
+
+    /// Remove all logged requests
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<SlowRequest>> {
+        // a panic while holding the lock can't leave the requests in an inconsistent state
+        self.requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl SlowRequestHandler for SlowRequestLog {
+    fn slow_request(&self, request: &SlowRequest) {
+        let mut requests = self.lock();
+        if self.capacity == 0 {
+            return;
+        }
+        if requests.len() >= self.capacity {
+            requests.remove(0);
+        }
+        requests.push(request.clone());
+    }
+}
+
+impl LatencyMonitor {
+    pub(crate) fn observe(
+        &self,
+        method: &Method,
+        endpoint: &str,
+        elapsed: Duration,
+        status: Option<u16>,
+    ) {
+        let budget = self.budget.budget(endpoint);
+        if elapsed > budget {
+            self.handler.slow_request(&SlowRequest {
+                method: method.to_string(),
+                endpoint: endpoint.to_owned(),
+                elapsed,
+                budget,
+                status,
+            });
+        }
+    }
+}
+
+impl PublicClient {
+    /// Report requests exceeding the given [LatencyBudget] to `handler`
+    pub fn with_latency_budget<H: SlowRequestHandler + 'static>(
+        mut self,
+        budget: LatencyBudget,
+        handler: H,
+    ) -> PublicClient {
+        self.latency_monitor = Some(Arc::new(LatencyMonitor {
+            budget,
+            handler: Arc::new(handler),
+        }));
+        self
+    }
+}
+
+impl AuthenticatedClient {
+    /// Report requests exceeding the given [LatencyBudget] to `handler`
+    pub fn with_latency_budget<H: SlowRequestHandler + 'static>(
+        mut self,
+        budget: LatencyBudget,
+        handler: H,
+    ) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_latency_budget(budget, handler);
+        self
+    }
+}
+
+impl DeviceClient {
+    /// Report requests exceeding the given [LatencyBudget] to `handler`
+    pub fn with_latency_budget<H: SlowRequestHandler + 'static>(
+        mut self,
+        budget: LatencyBudget,
+        handler: H,
+    ) -> DeviceClient {
+        self.authenticated_client = self
+            .authenticated_client
+            .with_latency_budget(budget, handler);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LatencyBudget, LatencyMonitor, SlowRequestLog};
+    use reqwest::Method;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn most_specific_endpoint_budget_applies() {
+        let budget = LatencyBudget::new(Duration::from_secs(2))
+            .with_endpoint("/episodes/", Duration::from_secs(10))
+            .with_endpoint("/api/2/episodes/", Duration::from_secs(5));
+
+        assert_eq!(
+            Duration::from_secs(5),
+            budget.budget("/api/2/episodes/username.json")
+        );
+        assert_eq!(
+            Duration::from_secs(2),
+            budget.budget("/api/2/devices/username.json")
+        );
+    }
+
+    #[test]
+    fn report_requests_exceeding_budget() {
+        let log = SlowRequestLog::new(1);
+        let monitor = LatencyMonitor {
+            budget: LatencyBudget::new(Duration::from_secs(2)),
+            handler: Arc::new(log.clone()),
+        };
+
+        monitor.observe(
+            &Method::GET,
+            "/api/2/devices/username.json",
+            Duration::from_secs(1),
+            Some(200),
+        );
+        monitor.observe(
+            &Method::GET,
+            "/api/2/devices/username.json",
+            Duration::from_secs(3),
+            Some(200),
+        );
+        monitor.observe(
+            &Method::POST,
+            "/api/2/episodes/username.json",
+            Duration::from_secs(4),
+            None,
+        );
+
+        let requests = log.slow_requests();
+        assert_eq!(1, requests.len());
+        assert_eq!("POST", requests[0].method);
+        assert_eq!(Duration::from_secs(4), requests[0].elapsed);
+        assert_eq!(None, requests[0].status);
+    }
+}
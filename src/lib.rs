@@ -7,6 +7,34 @@
 //! All supported API features are represented by a trait.
 //!
 //! A client's implemented traits mark its capabilities.
+//!
+//! The [prelude] imports all clients and traits at once.
+//!
+//! # Features
+//!
+//! Each API area is behind a Cargo feature, so minimal clients only compile what they need. All of them are enabled by default.
+//!
+//! - `client`: HTTP [client] based on `reqwest`, enabled by every API area
+//! - `devices`: [device] (enables `directory` and `episodes`)
+//! - `directory`: [directory], [cache], [podcast_list] and [rewrite] (enables `subscriptions`)
+//! - `episodes`: [episode] and [analytics] (enables `subscriptions`)
+//! - `favorites`: [favorite] (enables `directory`)
+//! - `settings`: [settings]
+//! - `subscriptions`: [subscription], [journal] and [opml]
+//! - `suggestions`: [suggestion]
+//!
+//! Modules combining several areas, like [model] and [sync], require all of them.
+//!
+//! The request and response bodies in [protocol] are always available. With `default-features = false` the crate only provides them, e.g. for servers implementing the API, and doesn't depend on `reqwest`.
+//!
+//! Optional features, disabled by default:
+//!
+//! - `digest-auth`: answer Digest challenges in [Challenge](client::AuthenticationMode::Challenge) mode
+//! - `ffi`: C ABI in [ffi] (enables `episodes`)
+//! - `pyo3`: Python bindings in [python] (enables `devices`)
+//! - `rusqlite` and `sled`: persistent [SyncStore](sync::SyncStore)s in [sync::store]
+//! - `test-utils`: fixtures, a fake client and a fake server in `test_support` for testing applications (enables `devices`, `favorites`, `settings` and `suggestions`)
+//! - `uniffi`: Kotlin and Swift bindings in [mobile] (enables `episodes`)
 
 #![deny(
     clippy::all,
@@ -29,14 +57,102 @@
     unused_lifetimes,
     unused_qualifications
 )]
+#![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::expect_used))]
 #![allow(missing_doc_code_examples)]
+// internal helpers are shared by the API areas, so some of them are unused if areas are disabled
+#![cfg_attr(
+    not(all(
+        feature = "devices",
+        feature = "directory",
+        feature = "episodes",
+        feature = "favorites",
+        feature = "settings",
+        feature = "subscriptions",
+        feature = "suggestions"
+    )),
+    allow(dead_code, unused_macros)
+)]
 
+#[cfg(feature = "episodes")]
+pub mod analytics;
+#[cfg(feature = "client")]
+pub mod batch;
+#[cfg(any(feature = "episodes", feature = "settings"))]
+pub mod bulk;
+#[cfg(feature = "directory")]
+pub mod cache;
+#[cfg(feature = "client")]
 pub mod client;
+pub mod clock;
+#[cfg(feature = "client")]
+pub mod credentials;
+mod de;
+#[cfg(feature = "client")]
+pub mod debug;
+#[cfg(feature = "devices")]
 pub mod device;
+#[cfg(feature = "directory")]
 pub mod directory;
+#[cfg(feature = "episodes")]
 pub mod episode;
 pub mod error;
+#[cfg(feature = "favorites")]
 pub mod favorite;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod format;
+#[cfg(feature = "client")]
+pub mod health;
+#[cfg(feature = "subscriptions")]
+pub mod journal;
+pub mod keyed;
+#[cfg(feature = "client")]
+pub mod latency;
+#[cfg(feature = "uniffi")]
+pub mod mobile;
+#[cfg(all(feature = "episodes", feature = "settings"))]
+pub mod model;
+#[cfg(feature = "subscriptions")]
+pub mod multi_account;
+#[cfg(feature = "subscriptions")]
+pub mod opml;
+#[cfg(feature = "directory")]
+pub mod podcast_list;
+#[cfg(feature = "client")]
+pub mod prelude;
+pub mod protocol;
+#[cfg(feature = "pyo3")]
+pub mod python;
+#[cfg(feature = "client")]
+pub mod redaction;
+pub mod reference;
+#[cfg(feature = "client")]
+pub mod retry;
+#[cfg(feature = "directory")]
+pub mod rewrite;
+#[cfg(feature = "settings")]
 pub mod settings;
+#[cfg(feature = "subscriptions")]
 pub mod subscription;
+#[cfg(feature = "suggestions")]
 pub mod suggestion;
+#[cfg(feature = "episodes")]
+pub mod sync;
+#[cfg(feature = "test-utils")]
+pub mod test_support;
+pub mod util;
+
+#[cfg(feature = "client")]
+pub use client::default_public_client;
+
+// the scaffolding generated by UniFFI contains unsafe code
+#[cfg(feature = "uniffi")]
+#[allow(unsafe_code)]
+mod scaffolding {
+    uniffi::setup_scaffolding!();
+}
+
+// the code generated by the UniFFI derives refers to the tag at the crate root
+#[cfg(feature = "uniffi")]
+#[doc(hidden)]
+pub use scaffolding::UniFfiTag;
@@ -0,0 +1,319 @@
+//! Kotlin and Swift bindings generated by [UniFFI](https://mozilla.github.io/uniffi-rs/)
+//!
+//! Enabled by the Cargo feature `uniffi`. Build a shared library with `cargo rustc --release --lib --features uniffi --crate-type cdylib` and generate the bindings from it with `uniffi-bindgen generate --library`. URLs and timestamps are passed as strings and UNIX timestamps, as the generated languages have no shared representation of them.
+
+// the code generated by the UniFFI derives and exports contains unsafe code
+#![allow(unsafe_code)]
+
+use crate::client::{AuthenticatedClient, DeviceClient};
+use crate::episode::{EpisodeAction, EpisodeActionType, GetEpisodeActions, UploadEpisodeActions};
+use crate::error::{Error, ValidationError};
+use crate::reference::{DeviceId, Username};
+use crate::subscription::{SubscriptionChanges, SubscriptionsOfDevice};
+use chrono::NaiveDateTime;
+use std::fmt;
+use std::sync::Arc;
+use url::Url;
+
+/// Client for a single device of a user, see [DeviceClient]
+#[derive(Debug, uniffi::Object)]
+pub struct GpodderClient {
+    client: DeviceClient,
+}
+
+/// Error reported to the generated bindings
+#[derive(Debug, Clone, PartialEq, Eq, Hash, uniffi::Error)]
+pub enum GpodderError {
+    /// request failed or timed out
+    Network {
+        /// description of the problem
+        message: String,
+    },
+    /// server rejected the credentials
+    Authentication {
+        /// description of the problem
+        message: String,
+    },
+    /// arguments or response couldn't be processed
+    InvalidData {
+        /// description of the problem
+        message: String,
+    },
+    /// any other error
+    Other {
+        /// description of the problem
+        message: String,
+    },
+}
+
+/// Kind of an [EpisodeRecord], see [EpisodeActionType]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum EpisodeActionKind {
+    /// see [EpisodeActionType::Download]
+    Download,
+    /// see [EpisodeActionType::Delete]
+    Delete,
+    /// see [EpisodeActionType::Play]
+    Play,
+    /// see [EpisodeActionType::New]
+    New,
+    /// see [EpisodeActionType::Flattr]
+    Flattr,
+    /// see [EpisodeActionType::Unknown]; such actions can't be uploaded
+    Other,
+}
+
+/// Episode action as exchanged with the generated bindings, see [EpisodeAction]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, uniffi::Record)]
+pub struct EpisodeRecord {
+    /// feed URL of the podcast
+    pub podcast: String,
+    /// media URL of the episode
+    pub episode: String,
+    /// device ID on which the action has taken place
+    pub device: Option<String>,
+    /// kind of the action
+    pub kind: EpisodeActionKind,
+    /// UNIX timestamp when the action took place
+    pub timestamp: Option<i64>,
+    /// position in seconds at which playback was started, only for [Play](EpisodeActionKind::Play)
+    pub started: Option<u32>,
+    /// position in seconds at which playback was stopped, required for [Play](EpisodeActionKind::Play)
+    pub position: Option<u32>,
+    /// duration of the episode in seconds, only for [Play](EpisodeActionKind::Play)
+    pub total: Option<u32>,
+}
+
+/// Episode actions and the timestamp for the next request, see [GetEpisodeActionsResponse](crate::episode::GetEpisodeActionsResponse)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, uniffi::Record)]
+pub struct EpisodeActionsPage {
+    /// received episode actions
+    pub actions: Vec<EpisodeRecord>,
+    /// timestamp to pass as `since` to the next request
+    pub timestamp: u64,
+}
+
+#[uniffi::export]
+impl GpodderClient {
+    /// Create client, see [DeviceClient::try_new]
+    #[uniffi::constructor]
+    pub fn new(
+        username: String,
+        password: String,
+        device_id: String,
+    ) -> Result<Arc<GpodderClient>, GpodderError> {
+        Ok(Arc::new(GpodderClient {
+            client: DeviceClient::try_new(
+                &Username::from(username),
+                &password,
+                &DeviceId::from(device_id),
+            )?,
+        }))
+    }
+
+    /// Returns the feed URLs of the subscriptions of the device, see [get_subscriptions_of_device](SubscriptionsOfDevice::get_subscriptions_of_device)
+    pub fn subscriptions(&self) -> Result<Vec<String>, GpodderError> {
+        Ok(self
+            .client
+            .get_subscriptions_of_device()?
+            .into_iter()
+            .map(String::from)
+            .collect())
+    }
+
+    /// Add and remove subscriptions of the device and return the new timestamp, see [upload_subscription_changes](SubscriptionChanges::upload_subscription_changes)
+    pub fn upload_subscription_changes(
+        &self,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<u64, GpodderError> {
+        let response = self
+            .client
+            .upload_subscription_changes(&parse_urls(&add)?, &parse_urls(&remove)?)?;
+        Ok(response.timestamp)
+    }
+
+    /// Returns episode actions of all devices since `since`, see [get_episode_actions](GetEpisodeActions::get_episode_actions)
+    pub fn episode_actions(&self, since: Option<u64>) -> Result<EpisodeActionsPage, GpodderError> {
+        let response = self
+            .authenticated_client()
+            .get_episode_actions(None, since, false)?;
+        Ok(EpisodeActionsPage {
+            actions: response
+                .actions
+                .into_iter()
+                .map(EpisodeRecord::from)
+                .collect(),
+            timestamp: response.timestamp,
+        })
+    }
+
+    /// Upload episode actions and return the new timestamp, see [upload_episode_actions](UploadEpisodeActions::upload_episode_actions)
+    pub fn upload_episode_actions(&self, actions: Vec<EpisodeRecord>) -> Result<u64, GpodderError> {
+        let actions = actions
+            .into_iter()
+            .map(EpisodeAction::try_from_record)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self
+            .authenticated_client()
+            .upload_episode_actions(&actions)?
+            .timestamp)
+    }
+}
+
+impl GpodderClient {
+    fn authenticated_client(&self) -> &AuthenticatedClient {
+        self.client.as_ref()
+    }
+}
+
+impl From<EpisodeAction> for EpisodeRecord {
+    fn from(action: EpisodeAction) -> Self {
+        let (kind, started, position, total) = match action.action {
+            EpisodeActionType::Download => (EpisodeActionKind::Download, None, None, None),
+            EpisodeActionType::Delete => (EpisodeActionKind::Delete, None, None, None),
+            EpisodeActionType::Play {
+                started,
+                position,
+                total,
+            } => (EpisodeActionKind::Play, started, Some(position), total),
+            EpisodeActionType::New => (EpisodeActionKind::New, None, None, None),
+            EpisodeActionType::Flattr => (EpisodeActionKind::Flattr, None, None, None),
+            EpisodeActionType::Unknown { .. } => (EpisodeActionKind::Other, None, None, None),
+        };
+        EpisodeRecord {
+            podcast: action.podcast.into(),
+            episode: action.episode.into(),
+            device: action.device,
+            kind,
+            timestamp: action.timestamp.map(|timestamp| timestamp.timestamp()),
+            started,
+            position,
+            total,
+        }
+    }
+}
+
+impl EpisodeAction {
+    fn try_from_record(record: EpisodeRecord) -> Result<EpisodeAction, GpodderError> {
+        let action = match record.kind {
+            EpisodeActionKind::Download => EpisodeActionType::Download,
+            EpisodeActionKind::Delete => EpisodeActionType::Delete,
+            EpisodeActionKind::Play => EpisodeActionType::Play {
+                started: record.started,
+                position: record.position.ok_or_else(|| {
+                    invalid_data(format!(
+                        "play action for {} without position",
+                        record.episode
+                    ))
+                })?,
+                total: record.total,
+            },
+            EpisodeActionKind::New => EpisodeActionType::New,
+            EpisodeActionKind::Flattr => EpisodeActionType::Flattr,
+            EpisodeActionKind::Other => {
+                return Err(invalid_data(format!(
+                    "action of unknown type for {}",
+                    record.episode
+                )))
+            }
+        };
+        let timestamp = match record.timestamp {
+            Some(timestamp) => Some(
+                NaiveDateTime::from_timestamp_opt(timestamp, 0)
+                    .ok_or_else(|| invalid_data(format!("timestamp {} out of range", timestamp)))?,
+            ),
+            None => None,
+        };
+        Ok(EpisodeAction {
+            podcast: parse_url(&record.podcast)?,
+            episode: parse_url(&record.episode)?,
+            device: record.device,
+            action,
+            timestamp,
+        })
+    }
+}
+
+impl From<Error> for GpodderError {
+    fn from(error: Error) -> Self {
+        let message = error.to_string();
+        match error {
+            Error::ReqwestError(_) | Error::Timeout { .. } | Error::Maintenance { .. } => {
+                GpodderError::Network { message }
+            }
+            Error::UnexpectedStatus { status: 401, .. } => GpodderError::Authentication { message },
+            Error::JsonError(_)
+            | Error::UrlError(_)
+            | Error::InvalidResponse { .. }
+            | Error::ValidationError(_)
+            | Error::InvalidConfiguration { .. }
+            | Error::UnexpectedContentType { .. }
+            | Error::ResponseTooLarge { .. } => GpodderError::InvalidData { message },
+            _ => GpodderError::Other { message },
+        }
+    }
+}
+
+impl fmt::Display for GpodderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpodderError::Network { message }
+            | GpodderError::Authentication { message }
+            | GpodderError::InvalidData { message }
+            | GpodderError::Other { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for GpodderError {}
+
+fn parse_url(url: &str) -> Result<Url, GpodderError> {
+    Url::parse(url)
+        .map_err(|_| Error::ValidationError(ValidationError::InvalidFeedUrl(url.to_owned())).into())
+}
+
+fn parse_urls(urls: &[String]) -> Result<Vec<Url>, GpodderError> {
+    urls.iter().map(|url| parse_url(url)).collect()
+}
+
+fn invalid_data(message: String) -> GpodderError {
+    GpodderError::InvalidData { message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EpisodeActionKind, EpisodeRecord, GpodderError};
+    use crate::episode::EpisodeAction;
+    use crate::error::Error;
+    use chrono::NaiveDate;
+    use url::Url;
+
+    #[test]
+    fn episode_record_roundtrip() {
+        let action = EpisodeAction::new_play(
+            Url::parse("http://example.com/feed.rss").unwrap(),
+            Url::parse("http://example.com/s01e01.mp3").unwrap(),
+            Some(NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0)),
+            15,
+            120,
+            500,
+        );
+
+        let record = EpisodeRecord::from(action.clone());
+
+        assert_eq!(EpisodeActionKind::Play, record.kind);
+        assert_eq!(Some(1_577_869_200), record.timestamp);
+        assert_eq!(action, EpisodeAction::try_from_record(record).unwrap());
+    }
+
+    #[test]
+    fn rejected_credentials_are_authentication_errors() {
+        let error = GpodderError::from(Error::UnexpectedStatus {
+            url: String::from("https://gpodder.net/api/2/devices/username.json"),
+            status: 401,
+        });
+
+        assert!(matches!(error, GpodderError::Authentication { .. }));
+    }
+}
@@ -0,0 +1,201 @@
+//! Views combining data of several API areas
+
+use crate::analytics::COMPLETION_PERCENTAGE;
+use crate::client::{AuthenticatedClient, DeviceClient};
+use crate::episode::{self, EpisodeAction, EpisodeActionType, GetEpisodeActions};
+use crate::error::Error;
+use crate::reference::EpisodeRef;
+use crate::settings::GetEpisodeSettings;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::panic;
+use std::thread;
+use url::Url;
+
+/// Episode setting marking an episode as favorite
+pub const FAVORITE_SETTING: &str = "is_favorite";
+
+/// State of an episode as seen by a player, merged from [episode actions](crate::episode) and [episode settings](crate::settings)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeStatus {
+    /// feed URL of the podcast
+    pub podcast: Url,
+    /// media URL of the episode
+    pub episode: Url,
+    /// most recent action for the episode
+    pub latest_action: Option<EpisodeAction>,
+    /// position (in seconds) of the most recent play action
+    pub position: Option<u32>,
+    /// total length (in seconds) of the episode, if known from a play action
+    pub total: Option<u32>,
+    /// `true` if [COMPLETION_PERCENTAGE] of the episode has been played
+    pub completed: bool,
+    /// `true` if the episode is marked as favorite in its [settings](FAVORITE_SETTING)
+    pub is_favorite: bool,
+    /// all settings of the episode
+    pub settings: HashMap<String, String>,
+}
+
+impl EpisodeStatus {
+    /// Merge the given actions and settings of an episode
+    ///
+    /// Actions for other episodes are ignored. Actions without timestamp count as older than actions with timestamp.
+    pub fn new(
+        podcast: Url,
+        episode: Url,
+        actions: &[EpisodeAction],
+        settings: HashMap<String, String>,
+    ) -> EpisodeStatus {
+        let mut actions: Vec<&EpisodeAction> = actions
+            .iter()
+            .filter(|action| action.episode == episode)
+            .collect();
+        actions.sort_by_key(|action| action.timestamp);
+
+        let latest_play = actions.iter().rev().find_map(|action| match action.action {
+            EpisodeActionType::Play {
+                position, total, ..
+            } => Some((position, total)),
+            _ => None,
+        });
+        let position = latest_play.map(|(position, _)| position);
+        let total = latest_play.and_then(|(_, total)| total);
+        let completed = match (position, total) {
+            (Some(position), Some(total)) if total > 0 => {
+                u64::from(position) * 100 >= u64::from(total) * COMPLETION_PERCENTAGE
+            }
+            _ => false,
+        };
+        let is_favorite = settings
+            .get(FAVORITE_SETTING)
+            .is_some_and(|value| value == "true" || value == "1");
+
+        EpisodeStatus {
+            podcast,
+            episode,
+            latest_action: actions.last().copied().cloned(),
+            position,
+            total,
+            completed,
+            is_favorite,
+            settings,
+        }
+    }
+
+    /// Returns the played share of the episode between 0 and 1, see [EpisodeActionType::progress_fraction]
+    pub fn progress_fraction(&self) -> Option<f64> {
+        self.position
+            .and_then(|position| episode::progress_fraction(position, self.total))
+    }
+
+    /// Returns `true` if at least the fraction `threshold` of the episode has been played, see [EpisodeActionType::is_finished]
+    ///
+    /// Unlike [completed](EpisodeStatus::completed), the threshold can be chosen, e.g. from a user preference.
+    pub fn is_finished(&self, threshold: f64) -> bool {
+        self.progress_fraction()
+            .is_some_and(|fraction| fraction >= threshold)
+    }
+}
+
+impl AuthenticatedClient {
+    /// Get the [EpisodeStatus] of an episode
+    ///
+    /// The aggregated episode actions of the podcast and the episode settings are requested in parallel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::reference::EpisodeRef;
+    /// use url::Url;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    ///
+    /// let status = client.episode_status(EpisodeRef::new(
+    ///     Url::parse("http://example.com/feed1.rss").unwrap(),
+    ///     Url::parse("http://example.com/files/s01e20.mp3").unwrap(),
+    /// ))?;
+    /// println!("position: {:?}, favorite: {}", status.position, status.is_favorite);
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn episode_status(&self, episode: EpisodeRef) -> Result<EpisodeStatus, Error> {
+        let (actions, settings) = thread::scope(|scope| {
+            let actions =
+                scope.spawn(|| self.get_episode_actions(Some(episode.podcast.clone()), None, true));
+            let settings = self.get_episode_settings(episode.clone());
+            let actions = actions
+                .join()
+                .unwrap_or_else(|payload| panic::resume_unwind(payload));
+            (actions, settings)
+        });
+
+        Ok(EpisodeStatus::new(
+            episode.podcast,
+            episode.episode,
+            &actions?.actions.into_vec(),
+            settings?,
+        ))
+    }
+}
+
+impl DeviceClient {
+    /// Get the [EpisodeStatus] of an episode, see [AuthenticatedClient::episode_status]
+    pub fn episode_status(&self, episode: EpisodeRef) -> Result<EpisodeStatus, Error> {
+        self.authenticated_client.episode_status(episode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EpisodeStatus, FAVORITE_SETTING};
+    use crate::episode::EpisodeAction;
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+    use url::Url;
+
+    #[test]
+    fn merge_actions_and_settings() {
+        let podcast = Url::parse("http://example.com/feed1.rss").unwrap();
+        let episode = Url::parse("http://example.com/files/s01e20.mp3").unwrap();
+        let other_episode = Url::parse("http://example.com/files/s01e21.mp3").unwrap();
+        let day = |day| Some(NaiveDate::from_ymd(2020, 1, day).and_hms(9, 0, 0));
+
+        let actions = vec![
+            EpisodeAction::new_play(podcast.clone(), episode.clone(), day(2), 480, 0, 500),
+            EpisodeAction::new_download(podcast.clone(), episode.clone(), day(1)),
+            EpisodeAction::new_play(podcast.clone(), other_episode, day(3), 10, 0, 500),
+        ];
+        let mut settings = HashMap::new();
+        settings.insert(String::from(FAVORITE_SETTING), String::from("true"));
+
+        let status = EpisodeStatus::new(podcast, episode, &actions, settings);
+
+        assert_eq!(Some(actions[0].clone()), status.latest_action);
+        assert_eq!(Some(480), status.position);
+        assert_eq!(Some(500), status.total);
+        assert!(status.completed);
+        assert!(status.is_finished(0.95));
+        assert!(!status.is_finished(0.99));
+        assert!(status.is_favorite);
+    }
+
+    #[test]
+    fn status_without_data() {
+        let status = EpisodeStatus::new(
+            Url::parse("http://example.com/feed1.rss").unwrap(),
+            Url::parse("http://example.com/files/s01e20.mp3").unwrap(),
+            &[],
+            HashMap::new(),
+        );
+
+        assert_eq!(None, status.latest_action);
+        assert_eq!(None, status.position);
+        assert!(!status.completed);
+        assert_eq!(None, status.progress_fraction());
+        assert!(!status.is_favorite);
+    }
+}
@@ -0,0 +1,132 @@
+//! Utilities for managing several accounts at once
+//!
+//! Useful for e.g. a household server synchronizing the accounts of all of its users.
+
+use crate::client::AuthenticatedClient;
+use crate::error::Error;
+use crate::subscription::{GetAllSubscriptions, Podcast};
+use std::collections::BTreeMap;
+use std::panic;
+use std::thread;
+
+/// Set of [AuthenticatedClient]s keyed by an account ID chosen by the application
+///
+/// Fan-out methods run the request for all accounts concurrently and report the result of each account separately, so one failing account doesn't hide the results of the others.
+#[derive(Debug, Clone, Default)]
+pub struct MultiAccountClient {
+    clients: BTreeMap<String, AuthenticatedClient>,
+}
+
+impl MultiAccountClient {
+    /// Create [MultiAccountClient] without accounts
+    pub fn new() -> MultiAccountClient {
+        Default::default()
+    }
+
+    /// Add client for the given account ID, returning the client previously registered for it
+    pub fn insert(
+        &mut self,
+        account_id: &str,
+        client: AuthenticatedClient,
+    ) -> Option<AuthenticatedClient> {
+        self.clients.insert(account_id.to_owned(), client)
+    }
+
+    /// Remove client of the given account ID
+    pub fn remove(&mut self, account_id: &str) -> Option<AuthenticatedClient> {
+        self.clients.remove(account_id)
+    }
+
+    /// Returns the client of the given account ID
+    pub fn get(&self, account_id: &str) -> Option<&AuthenticatedClient> {
+        self.clients.get(account_id)
+    }
+
+    /// Returns all account IDs in ascending order
+    pub fn account_ids(&self) -> impl Iterator<Item = &str> {
+        self.clients.keys().map(String::as_str)
+    }
+
+    /// Returns the number of accounts
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Returns `true` if there are no accounts
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Call `request` for every account concurrently, one thread per account
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::device::ListDevices;
+    /// use mygpoclient::multi_account::MultiAccountClient;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let mut client = MultiAccountClient::new();
+    /// client.insert("alice", AuthenticatedClient::new(&username, &password));
+    ///
+    /// let devices = client.for_each_account(|client| client.list_devices());
+    /// assert!(devices["alice"].is_ok());
+    /// ```
+    pub fn for_each_account<T, F>(&self, request: F) -> BTreeMap<String, Result<T, Error>>
+    where
+        T: Send,
+        F: Fn(&AuthenticatedClient) -> Result<T, Error> + Sync,
+    {
+        let request = &request;
+        thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .clients
+                .iter()
+                .map(|(account_id, client)| (account_id, scope.spawn(move || request(client))))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|(account_id, handle)| {
+                    let result = handle
+                        .join()
+                        .unwrap_or_else(|payload| panic::resume_unwind(payload));
+                    (account_id.clone(), result)
+                })
+                .collect()
+        })
+    }
+
+    /// [Get all subscriptions](GetAllSubscriptions::get_all_subscriptions) of every account concurrently
+    pub fn get_all_subscriptions(&self) -> BTreeMap<String, Result<Vec<Podcast>, Error>> {
+        self.for_each_account(|client| client.get_all_subscriptions())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiAccountClient;
+    use crate::client::AuthenticatedClient;
+
+    #[test]
+    fn for_each_account_reports_every_account() {
+        let mut client = MultiAccountClient::new();
+        client.insert("bob", AuthenticatedClient::new("bob-username", "password"));
+        client.insert(
+            "alice",
+            AuthenticatedClient::new("alice-username", "password"),
+        );
+
+        let usernames = client.for_each_account(|client| Ok(client.username.to_string()));
+
+        assert_eq!(
+            vec!["alice", "bob"],
+            client.account_ids().collect::<Vec<_>>()
+        );
+        assert_eq!("alice-username", usernames["alice"].as_ref().unwrap());
+        assert_eq!("bob-username", usernames["bob"].as_ref().unwrap());
+    }
+}
@@ -0,0 +1,329 @@
+//! Minimal reading and writing of [OPML](http://opml.org/spec2.opml) subscription lists
+//!
+//! [import_subscriptions] reads the OPML exports of other podcast clients like Apple Podcasts or Pocket Casts, e.g. to upload them with [upload_subscriptions_of_device](crate::subscription::SubscriptionsOfDevice::upload_subscriptions_of_device) when a user switches to gpodder.net.
+
+use crate::error::Error;
+use std::collections::HashSet;
+use std::fmt::Write;
+use url::Url;
+
+/// Feed read from an OPML export by [import_feeds]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Feed {
+    /// feed URL
+    pub url: Url,
+    /// title of the podcast, taken from the `text` or `title` attribute
+    pub title: Option<String>,
+}
+
+/// Read the feed URLs of an OPML export of another podcast client
+///
+/// Parsing is tolerant of the dialects of common clients:
+/// - outlines nested in folders are flattened
+/// - element and attribute names are case-insensitive, e.g. `xmlurl` instead of `xmlUrl`
+/// - the `url` attribute is used for outlines without `xmlUrl`, unless their `type` is something else than `rss`, e.g. `link` for websites
+/// - outlines without feed URL, invalid URLs and URLs other than `http` and `https` are skipped
+/// - duplicate feeds are only returned once, in the order of their first occurrence
+///
+/// Fails only if the document isn't OPML at all.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::opml::import_subscriptions;
+/// use url::Url;
+///
+/// let export = r#"<?xml version="1.0" encoding="utf-8"?>
+/// <opml version="1.0">
+///   <head><title>Pocket Casts Feeds</title></head>
+///   <body>
+///     <outline text="feeds">
+///       <outline type="rss" text="Example" xmlUrl="http://example.com/feed.rss"/>
+///       <outline type="rss" title="Other" url="https://example.org/podcast.xml"/>
+///     </outline>
+///   </body>
+/// </opml>"#;
+///
+/// assert_eq!(
+///     vec![Url::parse("http://example.com/feed.rss").unwrap(), Url::parse("https://example.org/podcast.xml").unwrap()],
+///     import_subscriptions(export)?
+/// );
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub fn import_subscriptions(opml: &str) -> Result<Vec<Url>, Error> {
+    Ok(import_feeds(opml)?
+        .into_iter()
+        .map(|feed| feed.url)
+        .collect())
+}
+
+/// Read the feeds of an OPML export of another podcast client, including their titles
+///
+/// See [import_subscriptions] for the supported dialects. The title is taken from the `text` attribute, or from `title` if `text` is missing or empty.
+pub fn import_feeds(opml: &str) -> Result<Vec<Feed>, Error> {
+    if !opml.to_ascii_lowercase().contains("<opml") {
+        return Err(opml_error("missing opml element"));
+    }
+
+    let mut seen = HashSet::new();
+    Ok(outlines(opml)
+        .filter_map(|attributes| {
+            let attribute = |name: &str| {
+                attributes
+                    .iter()
+                    .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+                    .map(|(_, value)| value.trim())
+                    .filter(|value| !value.is_empty())
+            };
+            let rss = attribute("type").is_none_or(|kind| kind.eq_ignore_ascii_case("rss"));
+            let url = match attribute("xmlUrl") {
+                Some(url) => url,
+                None if rss => attribute("url")?,
+                None => return None,
+            };
+            let url = Url::parse(url).ok()?;
+            if url.scheme() != "http" && url.scheme() != "https" {
+                return None;
+            }
+            Some(Feed {
+                url,
+                title: attribute("text")
+                    .or_else(|| attribute("title"))
+                    .map(str::to_owned),
+            })
+        })
+        .filter(|feed| seen.insert(feed.url.clone()))
+        .collect())
+}
+
+/// Returns an OPML document with one `outline` element per `(text, xmlUrl)` pair
+pub(crate) fn write<'a>(
+    title: &str,
+    outlines: impl IntoIterator<Item = (&'a str, &'a Url)>,
+) -> String {
+    let mut opml =
+        String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<opml version=\"2.0\">\n");
+    // writing to a String can't fail
+    let _ = writeln!(opml, "  <head><title>{}</title></head>", escape(title));
+    opml.push_str("  <body>\n");
+    for (text, url) in outlines {
+        let _ = writeln!(
+            opml,
+            "    <outline type=\"rss\" text=\"{}\" xmlUrl=\"{}\"/>",
+            escape(text),
+            escape(url.as_str())
+        );
+    }
+    opml.push_str("  </body>\n</opml>\n");
+    opml
+}
+
+/// Returns the `xmlUrl` attributes of all `outline` elements in document order
+pub(crate) fn feed_urls(opml: &str) -> Result<Vec<Url>, Error> {
+    if !opml.contains("<opml") {
+        return Err(opml_error("missing opml element"));
+    }
+
+    outlines(opml)
+        .filter_map(|attributes| {
+            attributes
+                .into_iter()
+                .find(|(name, _)| *name == "xmlUrl")
+                .map(|(_, value)| value)
+        })
+        .map(|url| {
+            Url::parse(&url).map_err(|e| opml_error(&format!("invalid xmlUrl {}: {}", url, e)))
+        })
+        .collect()
+}
+
+/// Returns the attributes of each `outline` element
+fn outlines(opml: &str) -> impl Iterator<Item = Vec<(&str, String)>> {
+    const OUTLINE: &str = "<outline";
+    // ASCII lowercasing keeps byte offsets, so they are valid for the original document
+    let starts: Vec<usize> = opml
+        .to_ascii_lowercase()
+        .match_indices(OUTLINE)
+        .map(|(start, _)| start)
+        .collect();
+    starts
+        .into_iter()
+        .map(move |start| &opml[start + OUTLINE.len()..])
+        .filter(|rest| rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>'))
+        .map(|rest| attributes(&rest[..rest.find('>').unwrap_or(rest.len())]))
+}
+
+fn attributes(mut tag: &str) -> Vec<(&str, String)> {
+    let mut attributes = Vec::new();
+
+    while let Some(equals) = tag.find('=') {
+        let name = tag[..equals].trim();
+        let value = tag[equals + 1..].trim_start();
+        let quote = match value.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => quote,
+            _ => break,
+        };
+        let end = match value[1..].find(quote) {
+            Some(end) => end + 1,
+            None => break,
+        };
+
+        attributes.push((name, unescape(&value[1..end])));
+        tag = &value[end + 1..];
+    }
+
+    attributes
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => numeric_character_reference(entity),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn numeric_character_reference(entity: &str) -> Option<char> {
+    let code = match entity.strip_prefix("#x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => entity.strip_prefix('#')?.parse().ok()?,
+    };
+    std::char::from_u32(code)
+}
+
+fn opml_error(reason: &str) -> Error {
+    Error::OpmlError {
+        reason: reason.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{feed_urls, import_feeds, import_subscriptions, write, Feed};
+    use url::Url;
+
+    #[test]
+    fn read_feed_urls() {
+        let opml = r#"<?xml version="1.0" encoding="utf-8"?>
+<opml version="2.0">
+  <head><title>gpodder.net subscriptions</title></head>
+  <body>
+    <outline text="Folder">
+      <outline type="rss" text="A &amp; B" xmlUrl="http://example.com/feed.php?a=1&amp;b=2" />
+      <outline xmlUrl = 'https://example.org/podcast.xml'/>
+    </outline>
+    <outlineExtension xmlUrl="http://example.net/ignored.xml"/>
+  </body>
+</opml>"#;
+
+        assert_eq!(
+            vec![
+                Url::parse("http://example.com/feed.php?a=1&b=2").unwrap(),
+                Url::parse("https://example.org/podcast.xml").unwrap(),
+            ],
+            feed_urls(opml).unwrap()
+        );
+    }
+
+    #[test]
+    fn reject_invalid_documents() {
+        assert!(feed_urls("http://example.com/feed.xml").is_err());
+        assert!(feed_urls(r#"<opml><body><outline xmlUrl="not a url"/></body></opml>"#).is_err());
+    }
+
+    #[test]
+    fn written_document_can_be_read() {
+        let url = Url::parse("http://example.com/feed.php?a=1&b=2").unwrap();
+
+        let opml = write("A <list>", vec![("A & B", &url)]);
+
+        assert!(opml.contains(r#"text="A &amp; B""#));
+        assert!(opml.contains("<title>A &lt;list&gt;</title>"));
+        assert_eq!(vec![url], feed_urls(&opml).unwrap());
+    }
+
+    #[test]
+    fn import_third_party_dialects() {
+        let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="1.0">
+  <body>
+    <outline text="Podcasts">
+      <outline text="Folder">
+        <OUTLINE type="rss" text="" title="Nested" xmlurl=" http://example.com/nested.rss "/>
+      </outline>
+      <outline type="link" text="Website" url="https://example.org/"/>
+      <outline type="RSS" text="Typed" url="https://example.org/podcast.xml"/>
+      <outline text="Untyped" url="https://example.net/podcast.xml"/>
+      <outline type="rss" text="Duplicate" xmlUrl="http://example.com/nested.rss"/>
+      <outline type="rss" text="Broken" xmlUrl="not a url"/>
+      <outline type="rss" text="Local" xmlUrl="file:///home/user/feed.xml"/>
+    </outline>
+  </body>
+</opml>"#;
+
+        assert_eq!(
+            vec![
+                Feed {
+                    url: Url::parse("http://example.com/nested.rss").unwrap(),
+                    title: Some(String::from("Nested")),
+                },
+                Feed {
+                    url: Url::parse("https://example.org/podcast.xml").unwrap(),
+                    title: Some(String::from("Typed")),
+                },
+                Feed {
+                    url: Url::parse("https://example.net/podcast.xml").unwrap(),
+                    title: Some(String::from("Untyped")),
+                },
+            ],
+            import_feeds(opml).unwrap()
+        );
+        assert_eq!(3, import_subscriptions(opml).unwrap().len());
+        assert!(import_subscriptions("<html></html>").is_err());
+    }
+}
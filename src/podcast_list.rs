@@ -0,0 +1,221 @@
+//! Reading of public lists of the [Podcast Lists API](https://gpoddernet.readthedocs.io/en/latest/api/reference/podcastlists.html)
+//!
+//! Users can curate lists of podcasts and share them. Lists shared by other users can be retrieved with [get_public_podcast_list](GetPublicPodcastList::get_public_podcast_list), exported to OPML and linked by their [share URL](PodcastList::share_url).
+
+use crate::client::{ApiArea, AuthenticatedClient, DeviceClient, PublicClient};
+use crate::error::Error;
+use crate::opml;
+use crate::reference::{ListName, Username};
+use crate::subscription::{with_path_segments, Podcast};
+use serde::Serialize;
+use std::fmt;
+use url::Url;
+
+/// Podcast list of a user as returned by [get_public_podcast_list](GetPublicPodcastList::get_public_podcast_list)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PodcastList {
+    /// name of the user owning the list
+    pub username: Username,
+    /// URL-friendly name of the list
+    pub name: ListName,
+    /// podcasts of the list
+    pub podcasts: Vec<Podcast>,
+    /// URL of the list on the website of the server it was retrieved from
+    pub share_url: Url,
+}
+
+/// see [get_public_podcast_list](GetPublicPodcastList::get_public_podcast_list)
+pub trait GetPublicPodcastList {
+    /// Get a podcast list of another user
+    ///
+    /// # Parameters
+    ///
+    /// - `username`: name of the user owning the list
+    /// - `list_name`: URL-friendly name of the list, as contained in its [share URL](PodcastList::share_url)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::podcast_list::GetPublicPodcastList;
+    /// use mygpoclient::reference::{ListName, Username};
+    ///
+    /// let list = PublicClient::default()
+    ///     .get_public_podcast_list(&Username::from("stefan"), &ListName::from("favorites"))?;
+    /// println!("{} ({} podcasts)", list.share_url, list.podcasts.len());
+    ///
+    /// let opml = list.to_opml();
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    ///
+    /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/podcastlists.html#get-a-podcast-list)
+    fn get_public_podcast_list(
+        &self,
+        username: &Username,
+        list_name: &ListName,
+    ) -> Result<PodcastList, Error>;
+}
+
+impl PodcastList {
+    /// Returns the list as OPML document, e.g. for importing it into other podcast clients
+    pub fn to_opml(&self) -> String {
+        opml::write(
+            &format!("{} by {}", self.name, self.username),
+            self.podcasts
+                .iter()
+                .map(|podcast| (podcast.title.as_str(), &podcast.url)),
+        )
+    }
+}
+
+impl PublicClient {
+    /// Returns the URL of a podcast list on the website of the configured server
+    pub fn podcast_list_share_url(
+        &self,
+        username: &Username,
+        list_name: &ListName,
+    ) -> Result<Url, Error> {
+        with_path_segments(
+            &format!("{}/user", self.base_url()),
+            &[username.as_str(), "lists", list_name.as_str()],
+        )
+    }
+}
+
+impl AuthenticatedClient {
+    /// Returns the URL of a podcast list on the website of the configured server, see [PublicClient::podcast_list_share_url]
+    pub fn podcast_list_share_url(
+        &self,
+        username: &Username,
+        list_name: &ListName,
+    ) -> Result<Url, Error> {
+        self.public_client
+            .podcast_list_share_url(username, list_name)
+    }
+}
+
+impl DeviceClient {
+    /// Returns the URL of a podcast list on the website of the configured server, see [PublicClient::podcast_list_share_url]
+    pub fn podcast_list_share_url(
+        &self,
+        username: &Username,
+        list_name: &ListName,
+    ) -> Result<Url, Error> {
+        self.authenticated_client
+            .podcast_list_share_url(username, list_name)
+    }
+}
+
+impl GetPublicPodcastList for PublicClient {
+    fn get_public_podcast_list(
+        &self,
+        username: &Username,
+        list_name: &ListName,
+    ) -> Result<PodcastList, Error> {
+        let podcasts: Vec<Podcast> = self
+            .get(&self.api_url(
+                ApiArea::Directory,
+                &format!("lists/{}/list/{}.json", username, list_name),
+            ))?
+            .json()?;
+        Ok(PodcastList {
+            username: username.clone(),
+            name: list_name.clone(),
+            podcasts: podcasts
+                .into_iter()
+                .map(|podcast| self.rewrite_podcast(podcast))
+                .collect(),
+            share_url: self.podcast_list_share_url(username, list_name)?,
+        })
+    }
+}
+
+impl GetPublicPodcastList for AuthenticatedClient {
+    fn get_public_podcast_list(
+        &self,
+        username: &Username,
+        list_name: &ListName,
+    ) -> Result<PodcastList, Error> {
+        self.public_client
+            .get_public_podcast_list(username, list_name)
+    }
+}
+
+impl GetPublicPodcastList for DeviceClient {
+    fn get_public_podcast_list(
+        &self,
+        username: &Username,
+        list_name: &ListName,
+    ) -> Result<PodcastList, Error> {
+        self.authenticated_client
+            .get_public_podcast_list(username, list_name)
+    }
+}
+
+impl fmt::Display for PodcastList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} by {}", self.name, self.username)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PodcastList;
+    use crate::client::{AuthenticatedClient, PublicClient};
+    use crate::reference::{ListName, Username};
+    use crate::subscription::Podcast;
+    use url::Url;
+
+    #[test]
+    fn share_url_encodes_names() {
+        assert_eq!(
+            "https://gpodder.net/user/some%20user/lists/my-list",
+            PublicClient::default()
+                .podcast_list_share_url(&Username::from("some user"), &ListName::from("my-list"))
+                .unwrap()
+                .as_str()
+        );
+    }
+
+    #[test]
+    fn share_url_uses_base_url() {
+        assert_eq!(
+            "https://gpodder.example/user/username/lists/my-list",
+            AuthenticatedClient::new("username", "password")
+                .with_base_url("https://gpodder.example/")
+                .unwrap()
+                .podcast_list_share_url(&Username::from("username"), &ListName::from("my-list"))
+                .unwrap()
+                .as_str()
+        );
+    }
+
+    #[test]
+    fn export_to_opml() {
+        let list = PodcastList {
+            username: Username::from("username"),
+            name: ListName::from("favorites"),
+            podcasts: vec![Podcast {
+                url: Url::parse("http://example.com/feed.rss").unwrap(),
+                title: String::from("Example & Co"),
+                author: None,
+                description: String::new(),
+                subscribers: 1,
+                subscribers_last_week: 1,
+                logo_url: None,
+                scaled_logo_url: None,
+                website: None,
+                mygpo_link: Url::parse("http://gpodder.net/podcast/1").unwrap(),
+            }],
+            share_url: Url::parse("https://gpodder.net/user/username/lists/favorites").unwrap(),
+        };
+
+        let opml = list.to_opml();
+
+        assert!(opml.contains("<title>favorites by username</title>"));
+        assert!(opml.contains(r#"text="Example &amp; Co" xmlUrl="http://example.com/feed.rss""#));
+    }
+}
@@ -0,0 +1,50 @@
+//! Clients, capability traits and error types in one import
+//!
+//! ```
+//! use mygpoclient::prelude::*;
+//!
+//! # use mygpoclient::reference::{DeviceId, Username};
+//! # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+//! # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+//! # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+//! #
+//! let client = DeviceClient::new(&username, &password, &deviceid);
+//!
+//! let subscriptions = client.get_subscriptions_of_device()?;
+//! let settings = client.get_device_settings()?;
+//! #
+//! # Ok::<(), Error>(())
+//! ```
+//!
+//! Only traits of enabled features are exported. Hooks like [RetryPolicy](crate::retry::RetryPolicy) aren't part of the prelude, as only applications implementing them need them.
+
+pub use crate::client::{AuthenticatedClient, DeviceClient, PublicClient};
+pub use crate::error::{Error, Result};
+pub use crate::health::CheckConnection;
+pub use crate::util::ToPrettyJson;
+
+#[cfg(feature = "devices")]
+pub use crate::device::{GetDeviceUpdates, ListDevices, UpdateDeviceData};
+#[cfg(feature = "directory")]
+pub use crate::directory::{
+    CheckFeed, FetchLogo, PodcastSearch, PodcastToplist, RetrieveEpisodeData, RetrievePodcastData,
+    RetrievePodcastsForTag, RetrieveTopTags,
+};
+#[cfg(feature = "episodes")]
+pub use crate::episode::{GetEpisodeActions, UploadEpisodeActions};
+#[cfg(feature = "favorites")]
+pub use crate::favorite::GetFavoriteEpisodes;
+#[cfg(feature = "directory")]
+pub use crate::podcast_list::GetPublicPodcastList;
+#[cfg(feature = "settings")]
+pub use crate::settings::{
+    GetAccountSettings, GetDeviceSettings, GetEpisodeSettings, GetPodcastSettings,
+    GetSettingsOfDevice, SaveAccountSettings, SaveDeviceSettings, SaveEpisodeSettings,
+    SavePodcastSettings, SaveSettingsOfDevice,
+};
+#[cfg(feature = "subscriptions")]
+pub use crate::subscription::{
+    GetAllSubscriptions, GetPublicSubscriptions, SubscriptionChanges, SubscriptionsOfDevice,
+};
+#[cfg(feature = "suggestions")]
+pub use crate::suggestion::RetrieveSuggestedPodcasts;
@@ -0,0 +1,44 @@
+//! Wire format of the gpodder.net API
+//!
+//! Defines the request and response bodies of every endpoint this crate knows, grouped like the [API reference](https://gpoddernet.readthedocs.io/en/latest/api/reference/). All of them implement [Serialize](serde::Serialize) and [Deserialize](serde::Deserialize), so servers implementing the API can use them without going through a client. Their fields only use types of `serde`, `url` and `chrono`.
+//!
+//! This module doesn't depend on any API feature or on the HTTP client, so with `default-features = false` the crate only provides these types and doesn't pull in `reqwest`. The API modules re-export the types they use.
+//!
+//! The types describe version [API_VERSION] of the API. Changes of their serialized form are breaking changes of this crate and only happen in a new major version.
+//!
+//! Bodies which are plain lists or maps aren't wrapped; the documentation of each submodule lists them.
+
+pub mod device_sync;
+pub mod devices;
+pub mod directory;
+pub mod episodes;
+pub mod podcast_lists;
+pub mod settings;
+pub mod subscriptions;
+pub mod suggestions;
+
+/// Version of the API described by this module, as in `/api/2/`
+pub const API_VERSION: u8 = 2;
+
+/// [Favorites API](https://gpoddernet.readthedocs.io/en/latest/api/reference/favorites.html)
+///
+/// Favorites are returned as `Vec<Episode>`.
+pub mod favorites {
+    pub use super::directory::Episode;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::subscriptions::UploadSubscriptionChangesRequest;
+
+    #[test]
+    fn deserialize_request_of_api_documentation() {
+        let request: UploadSubscriptionChangesRequest = serde_json::from_str(
+            r#"{"add": ["http://example.com/feed.rss", "http://example.org/podcast.php"], "remove": ["http://example.net/foo.xml"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(2, request.add.len());
+        assert_eq!("http://example.net/foo.xml", request.remove[0].as_str());
+    }
+}
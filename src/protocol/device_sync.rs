@@ -0,0 +1,66 @@
+//! [Device Synchronization API](https://gpoddernet.readthedocs.io/en/latest/api/reference/sync.html)
+//!
+//! Devices in the same sync group share their subscriptions. The groups are returned as [DeviceSyncStatus] and changed with a [DeviceSyncRequest], which is answered with the new [DeviceSyncStatus].
+
+use crate::reference::DeviceId;
+use serde::{Deserialize, Serialize};
+
+/// Synchronization status of the devices of a user
+///
+/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/sync.html#get-sync-status
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceSyncStatus {
+    /// groups of devices which are synchronized with each other
+    #[serde(default)]
+    pub synchronized: Vec<Vec<DeviceId>>,
+    /// devices which aren't synchronized with any other device
+    #[serde(default, rename = "not-synchronized")]
+    pub not_synchronized: Vec<DeviceId>,
+}
+
+/// Request body for starting and stopping the synchronization of devices
+///
+/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/sync.html#start-stop-sync
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceSyncRequest {
+    /// groups of devices to synchronize with each other
+    #[serde(default)]
+    pub synchronize: Vec<Vec<DeviceId>>,
+    /// devices to remove from their sync group
+    #[serde(default, rename = "stop-synchronize")]
+    pub stop_synchronize: Vec<DeviceId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeviceSyncRequest, DeviceSyncStatus};
+    use crate::reference::DeviceId;
+
+    #[test]
+    fn deserialize_status_of_api_documentation() {
+        let status: DeviceSyncStatus = serde_json::from_str(
+            r#"{"synchronized": [["notebook", "n900"], ["pc-home", "pc-work"]], "not-synchronized": ["pc-test"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(2, status.synchronized.len());
+        assert_eq!(
+            vec![DeviceId::from("notebook"), DeviceId::from("n900")],
+            status.synchronized[0]
+        );
+        assert_eq!(vec![DeviceId::from("pc-test")], status.not_synchronized);
+    }
+
+    #[test]
+    fn serialize_request_like_api_documentation() {
+        let request = DeviceSyncRequest {
+            synchronize: vec![vec![DeviceId::from("notebook"), DeviceId::from("n900")]],
+            stop_synchronize: vec![DeviceId::from("pc-test")],
+        };
+
+        assert_eq!(
+            r#"{"synchronize":[["notebook","n900"]],"stop-synchronize":["pc-test"]}"#,
+            serde_json::to_string(&request).unwrap()
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! [Device API](https://gpoddernet.readthedocs.io/en/latest/api/reference/devices.html)
+//!
+//! Devices of a user are returned as `Vec<Device>`.
+
+use super::directory::{parse_datetime, Episode};
+use super::episodes::EpisodeActionType;
+use super::subscriptions::Podcast;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use url::Url;
+
+/// Type of the [Device]
+#[serde(rename_all = "lowercase")]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum DeviceType {
+    /// desktop computer
+    Desktop,
+    /// portable computer
+    Laptop,
+    /// smartphone/tablet
+    Mobile,
+    /// server
+    Server,
+    /// any type of device, which doesn't fit another variant
+    Other,
+}
+
+/// Devices are used throughout the API to identify a device / a client application.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Device {
+    /// A device ID can be any string matching the regular expression `[\w.-]+`. The client application MUST generate a string to be used as its device ID, and SHOULD ensure that it is unique within the user account. A good approach is to combine the application name and the name of the host it is running on.
+    ///
+    /// If two applications share a device ID, this might cause subscriptions to be overwritten on the server side. While it is possible to retrieve a list of devices and their IDs from the server, this SHOULD NOT be used to let a user select an existing device ID.
+    pub id: String,
+    /// Human readable label for the device
+    pub caption: String,
+    /// Type of the device
+    #[serde(rename(serialize = "type", deserialize = "type"))]
+    pub device_type: DeviceType,
+    /// number of subscriptions for this device
+    pub subscriptions: u32,
+    /// UTC time of the last action of the device, if provided by the server
+    ///
+    /// `None` as well if the server sends it in an unknown format, so such devices can still be listed.
+    #[serde(
+        default,
+        alias = "last_action",
+        deserialize_with = "deserialize_last_seen",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_seen: Option<NaiveDateTime>,
+    /// user agent of the last request of the device, if provided by the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+}
+
+fn deserialize_last_seen<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error> {
+    let last_seen = Value::deserialize(deserializer)?;
+    Ok(last_seen.as_str().and_then(parse_datetime))
+}
+
+/// Request body of [update_device_data](crate::device::UpdateDeviceData::update_device_data)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeviceData {
+    /// new caption, `None` to keep the current one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// new type, `None` to keep the current one
+    #[serde(rename(serialize = "type", deserialize = "type"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_type: Option<DeviceType>,
+}
+
+/// episode update information as used in [DeviceUpdates]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeUpdate {
+    /// episode
+    #[serde(flatten)]
+    pub episode: Episode,
+    /// latest episode action reported for this episode
+    pub status: Option<EpisodeActionType>,
+}
+
+/// updated information for a device as returned by [get_device_updates](crate::device::GetDeviceUpdates::get_device_updates)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeviceUpdates {
+    /// list of subscriptions to be added
+    pub add: Vec<Podcast>,
+    /// list of URLs to be unsubscribed
+    pub rem: Vec<Url>,
+    /// list of updated episodes
+    pub updates: Vec<EpisodeUpdate>,
+    /// current timestamp; for retrieving changes since the last query
+    #[serde(deserialize_with = "crate::de::timestamp")]
+    pub timestamp: u64,
+}
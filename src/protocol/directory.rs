@@ -0,0 +1,167 @@
+//! [Directory API](https://gpoddernet.readthedocs.io/en/latest/api/reference/directory.html)
+//!
+//! Tags are returned as `Vec<Tag>`, podcasts of a tag and search results as `Vec<Podcast>` of the [subscriptions](super::subscriptions) module.
+
+use super::subscriptions;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::iter::FromIterator;
+use url::Url;
+
+/// Podcast tag
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Tag {
+    /// more reader-friendly representation of tag
+    pub title: String,
+    /// actual tag, unique identifier
+    pub tag: String,
+    /// number of podcasts using this tag
+    pub usage: u32,
+}
+
+/// Podcast in the [toplist](crate::directory::PodcastToplist::podcast_toplist)
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Podcast {
+    /// podcast data
+    #[serde(flatten)]
+    pub podcast: subscriptions::Podcast,
+    /// current rank in toplist, starting at 1
+    ///
+    /// If the server doesn't provide it, [podcast_toplist](crate::directory::PodcastToplist::podcast_toplist) derives it from the order of the response.
+    #[serde(default)]
+    pub position: Option<u32>,
+    /// rank in toplist one week before, if provided by the server
+    #[serde(default, alias = "old_position")]
+    pub position_last_week: Option<u32>,
+}
+
+/// Podcasts returned by [podcast_toplist](crate::directory::PodcastToplist::podcast_toplist), best ranked first
+///
+/// Consume it with [IntoIterator] rather than relying on its storage, so toplists can be read lazily in the future. [into_vec](Toplist::into_vec) collects the podcasts, e.g. for sorting.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(transparent)]
+pub struct Toplist(Vec<Podcast>);
+
+/// Owning iterator over a [Toplist]
+#[derive(Debug, Clone)]
+pub struct ToplistIntoIter(std::vec::IntoIter<Podcast>);
+
+/// Podcast episode
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Episode {
+    /// title
+    pub title: String,
+    /// media url
+    pub url: Url,
+    /// podcast title
+    pub podcast_title: String,
+    /// podcast feed url
+    pub podcast_url: Url,
+    /// description
+    pub description: String,
+    /// website, `None` if missing or empty
+    #[serde(default, deserialize_with = "deserialize_optional_url")]
+    pub website: Option<Url>,
+    /// gpodder internal link, `None` if missing or empty
+    #[serde(default, deserialize_with = "deserialize_optional_url")]
+    pub mygpo_link: Option<Url>,
+    /// release date in UTC, `None` if missing or in an unknown format
+    #[serde(default, deserialize_with = "deserialize_released")]
+    pub released: Option<NaiveDateTime>,
+}
+
+fn deserialize_optional_url<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Url>, D::Error> {
+    let url: Option<String> = Option::deserialize(deserializer)?;
+    match url.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(url) => Url::parse(url).map(Some).map_err(de::Error::custom),
+    }
+}
+
+fn deserialize_released<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error> {
+    let released: Option<String> = Option::deserialize(deserializer)?;
+    Ok(released.as_deref().and_then(parse_datetime))
+}
+
+/// Parse a date and time in UTC, accepting the formats used by different server implementations
+pub(super) fn parse_datetime(datetime: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(datetime)
+        .or_else(|_| DateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%S%.f%z"))
+        .map(|datetime| datetime.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%S%.f"))
+        .or_else(|_| NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M:%S%.f"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(datetime, "%Y-%m-%d").map(|date| date.and_hms(0, 0, 0))
+        })
+        .ok()
+}
+
+impl Toplist {
+    /// Returns the number of podcasts
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the toplist contains no podcasts
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the podcasts, best ranked first
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Podcast> + '_ {
+        self.0.iter()
+    }
+
+    /// Collect the podcasts into a [Vec]
+    pub fn into_vec(self) -> Vec<Podcast> {
+        self.0
+    }
+}
+
+impl From<Vec<Podcast>> for Toplist {
+    fn from(podcasts: Vec<Podcast>) -> Self {
+        Toplist(podcasts)
+    }
+}
+
+impl FromIterator<Podcast> for Toplist {
+    fn from_iter<I: IntoIterator<Item = Podcast>>(iter: I) -> Self {
+        Toplist(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Toplist {
+    type Item = Podcast;
+    type IntoIter = ToplistIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ToplistIntoIter(self.0.into_iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a Toplist {
+    type Item = &'a Podcast;
+    type IntoIter = std::slice::Iter<'a, Podcast>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Iterator for ToplistIntoIter {
+    type Item = Podcast;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ToplistIntoIter {}
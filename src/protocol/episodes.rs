@@ -0,0 +1,288 @@
+//! [Episode Actions API](https://gpoddernet.readthedocs.io/en/latest/api/reference/events.html)
+//!
+//! Episode actions are uploaded as `Vec<EpisodeAction>`.
+
+use super::subscriptions::UrlRewrite;
+use chrono::NaiveDateTime;
+use serde::de;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use url::Url;
+
+/// Type of an [EpisodeAction]
+///
+/// Serialized as the `action` field of the [EpisodeAction] and the fields specific to the type. Action types this crate doesn't know yet are kept as [Unknown](EpisodeActionType::Unknown), so new server-side types don't break deserializing the history.
+///
+/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/events.html#episode-action-types
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum EpisodeActionType {
+    /// download event, so that other clients know where a file has already been downloaded
+    Download,
+    /// delete event, so that other clients know where a file has already been deleted
+    Delete,
+    /// play event, so that other clients know where to start playback
+    Play {
+        /// the position (in seconds) at which the client stopped playback
+        position: u32,
+        /// the position (in seconds) at which the client started playback. Requires total to be set.
+        started: Option<u32>,
+        /// the total length of the file in seconds. Requires started to be set.
+        total: Option<u32>,
+    },
+    /// Resets previous events. This state needs to be interpreted by receiving clients and does not delete any information on the webservice.
+    New,
+    /// flattr event, issued when the user flattrs (donates to) an episode
+    ///
+    /// This type is not part of the documented API, but is accepted by gpodder.net and serialized as `"flattr"`.
+    Flattr,
+    /// action type unknown to this version of the crate, e.g. one added to the API later
+    ///
+    /// Serialized as received, so stored and re-uploaded histories keep unknown actions. They can't be written as [CSV](crate::episode::ExportFormat::Csv), which has no columns for their fields.
+    Unknown {
+        /// value of the `action` field
+        action: String,
+        /// all fields of the action not belonging to [EpisodeAction] itself
+        fields: ExtraFields,
+    },
+}
+
+/// Additional fields of an [Unknown](EpisodeActionType::Unknown) episode action
+///
+/// Ordered and hashed by their JSON representation.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ExtraFields(pub Map<String, Value>);
+
+/// Action types known to this crate, (de)serialized by the derived implementations
+#[serde(rename_all = "lowercase", tag = "action")]
+#[derive(Serialize, Deserialize)]
+enum KnownActionType {
+    Download,
+    Delete,
+    Play {
+        position: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        started: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        total: Option<u32>,
+    },
+    New,
+    Flattr,
+}
+
+const KNOWN_ACTIONS: [&str; 5] = ["download", "delete", "play", "new", "flattr"];
+
+/// Episode-related event
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct EpisodeAction {
+    /// feed URL to the podcast feed the episode belongs to
+    pub podcast: Url,
+    /// media URL of the episode
+    pub episode: Url,
+    /// device ID on which the action has taken place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// see [EpisodeActionType](./enum.EpisodeActionType.html)
+    #[serde(flatten)]
+    pub action: EpisodeActionType,
+    /// UTC timestamp when the action took place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<NaiveDateTime>,
+}
+
+/// Response to [upload_episode_actions](crate::episode::UploadEpisodeActions::upload_episode_actions)
+///
+/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/events.html#upload-episode-actions
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct UploadEpisodeActionsResponse {
+    /// the current timestamp; for retrieving changes since the last query
+    #[serde(deserialize_with = "crate::de::timestamp")]
+    pub timestamp: u64,
+    /// list of URLs that have been rewritten (sanitized, see bug:747 and bug:862) as a list of tuples. The client SHOULD parse this list and update the local subscription and episode list accordingly (the server only sanitizes the URL, so the semantic “content” should stay the same and therefore the client can simply update the URL value locally and use it for future updates.
+    ///
+    /// URLs that are not allowed (currently all URLs that contain non-ASCII characters or don’t start with either http or https) are rewritten to the empty string and are ignored by the Webservice. Their [sanitized](UrlRewrite::sanitized) URL is `None`.
+    #[serde(default)]
+    pub update_urls: Vec<UrlRewrite>,
+}
+
+/// Response to [get_episode_actions](crate::episode::GetEpisodeActions::get_episode_actions)
+///
+/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/events.html#get-episode-actions
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct GetEpisodeActionsResponse {
+    /// see [EpisodeAction](./struct.EpisodeAction.html)
+    pub actions: ActionList,
+    /// new timestamp that the client SHOULD save and use for subsequent requests
+    #[serde(deserialize_with = "crate::de::timestamp")]
+    pub timestamp: u64,
+}
+
+/// Episode actions returned by [get_episode_actions](crate::episode::GetEpisodeActions::get_episode_actions)
+///
+/// Consume it with [IntoIterator] rather than relying on its storage, so lists can be read lazily in the future. [into_vec](ActionList::into_vec) collects the actions, e.g. for functions taking slices.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[serde(transparent)]
+pub struct ActionList(Vec<EpisodeAction>);
+
+/// Owning iterator over an [ActionList]
+#[derive(Debug, Clone)]
+pub struct ActionListIntoIter(std::vec::IntoIter<EpisodeAction>);
+
+impl ActionList {
+    /// Returns the number of actions
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the list contains no actions
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the actions in the order returned by the server
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &EpisodeAction> + '_ {
+        self.0.iter()
+    }
+
+    /// Collect the actions into a [Vec]
+    pub fn into_vec(self) -> Vec<EpisodeAction> {
+        self.0
+    }
+}
+
+impl From<Vec<EpisodeAction>> for ActionList {
+    fn from(actions: Vec<EpisodeAction>) -> Self {
+        ActionList(actions)
+    }
+}
+
+impl FromIterator<EpisodeAction> for ActionList {
+    fn from_iter<I: IntoIterator<Item = EpisodeAction>>(iter: I) -> Self {
+        ActionList(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for ActionList {
+    type Item = EpisodeAction;
+    type IntoIter = ActionListIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ActionListIntoIter(self.0.into_iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a ActionList {
+    type Item = &'a EpisodeAction;
+    type IntoIter = std::slice::Iter<'a, EpisodeAction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Iterator for ActionListIntoIter {
+    type Item = EpisodeAction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ActionListIntoIter {}
+
+impl Serialize for EpisodeActionType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let known = match self {
+            EpisodeActionType::Download => KnownActionType::Download,
+            EpisodeActionType::Delete => KnownActionType::Delete,
+            EpisodeActionType::Play {
+                position,
+                started,
+                total,
+            } => KnownActionType::Play {
+                position: *position,
+                started: *started,
+                total: *total,
+            },
+            EpisodeActionType::New => KnownActionType::New,
+            EpisodeActionType::Flattr => KnownActionType::Flattr,
+            EpisodeActionType::Unknown { action, fields } => {
+                let mut map = serializer.serialize_map(None)?;
+                map.serialize_entry("action", action)?;
+                for (key, value) in fields.0.iter().filter(|(key, _)| *key != "action") {
+                    map.serialize_entry(key, value)?;
+                }
+                return map.end();
+            }
+        };
+        known.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EpisodeActionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = Map::deserialize(deserializer)?;
+        let action = match fields.get("action") {
+            Some(Value::String(action)) => action.clone(),
+            Some(_) => return Err(de::Error::custom("action is not a string")),
+            None => return Err(de::Error::missing_field("action")),
+        };
+        if !KNOWN_ACTIONS.contains(&action.as_str()) {
+            fields.remove("action");
+            return Ok(EpisodeActionType::Unknown {
+                action,
+                fields: ExtraFields(fields),
+            });
+        }
+
+        Ok(
+            match KnownActionType::deserialize(Value::Object(fields)).map_err(de::Error::custom)? {
+                KnownActionType::Download => EpisodeActionType::Download,
+                KnownActionType::Delete => EpisodeActionType::Delete,
+                KnownActionType::Play {
+                    position,
+                    started,
+                    total,
+                } => EpisodeActionType::Play {
+                    position,
+                    started,
+                    total,
+                },
+                KnownActionType::New => EpisodeActionType::New,
+                KnownActionType::Flattr => EpisodeActionType::Flattr,
+            },
+        )
+    }
+}
+
+impl ExtraFields {
+    fn to_json(&self) -> String {
+        serde_json::to_string(&self.0).unwrap_or_default()
+    }
+}
+
+impl Hash for ExtraFields {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_json().hash(state);
+    }
+}
+
+impl PartialOrd for ExtraFields {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExtraFields {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_json().cmp(&other.to_json())
+    }
+}
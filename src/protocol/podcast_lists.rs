@@ -0,0 +1,42 @@
+//! [Podcast Lists API](https://gpoddernet.readthedocs.io/en/latest/api/reference/podcastlists.html)
+//!
+//! Podcasts of a list are returned as `Vec<Podcast>` of the [subscriptions](super::subscriptions) module, and uploaded when creating or updating a list as `Vec<Url>`.
+
+use crate::reference::ListName;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Podcast list as contained in the lists of a user
+///
+/// [gpodder.net API Documentation]: https://gpoddernet.readthedocs.io/en/latest/api/reference/podcastlists.html#get-lists-of-user
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct PodcastListInfo {
+    /// human-readable title of the list
+    pub title: String,
+    /// URL-friendly name of the list, unique for its user
+    pub name: ListName,
+    /// URL of the list on the website of the server
+    pub web: Url,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PodcastListInfo;
+    use crate::reference::ListName;
+
+    #[test]
+    fn deserialize_lists_of_api_documentation() {
+        let lists: Vec<PodcastListInfo> = serde_json::from_str(
+            r#"[{"title": "My Python Podcasts", "name": "my-python-podcasts", "web": "http://gpodder.net/user/username/lists/my-python-podcasts"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(1, lists.len());
+        assert_eq!("My Python Podcasts", lists[0].title);
+        assert_eq!(ListName::from("my-python-podcasts"), lists[0].name);
+        assert_eq!(
+            "http://gpodder.net/user/username/lists/my-python-podcasts",
+            lists[0].web.as_str()
+        );
+    }
+}
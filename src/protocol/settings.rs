@@ -0,0 +1,17 @@
+//! [Settings API](https://gpoddernet.readthedocs.io/en/latest/api/reference/settings.html)
+//!
+//! Settings are returned as `HashMap<String, String>`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Request body of the methods saving settings, e.g. [save_account_settings](crate::settings::SaveAccountSettings::save_account_settings)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveSettingsRequest {
+    /// settings to set
+    #[serde(default)]
+    pub set: HashMap<String, String>,
+    /// keys of settings to remove
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
@@ -0,0 +1,104 @@
+//! [Subscriptions API](https://gpoddernet.readthedocs.io/en/latest/api/reference/subscriptions.html)
+//!
+//! Subscriptions of a device are uploaded and returned as `Vec<Url>`, all subscriptions of a user as `Vec<Podcast>`.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use url::Url;
+
+/// Podcast
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Podcast {
+    /// feed URL
+    pub url: Url,
+    /// title of podcast
+    pub title: String,
+    /// author of podcast
+    pub author: Option<String>,
+    /// description of podcast
+    pub description: String,
+    /// number of subscribers on service
+    pub subscribers: u32,
+    /// number of subscribers on service one week before
+    pub subscribers_last_week: u32,
+    /// URL to logo of podcast
+    pub logo_url: Option<Url>,
+    /// URL to a scaled logo of podcast
+    pub scaled_logo_url: Option<Url>,
+    /// website of podcast
+    pub website: Option<Url>,
+    /// service-internal feed URL
+    pub mygpo_link: Url,
+}
+
+/// Request body of [upload_subscription_changes](crate::subscription::SubscriptionChanges::upload_subscription_changes)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadSubscriptionChangesRequest {
+    /// URLs of podcasts to subscribe to
+    #[serde(default)]
+    pub add: Vec<Url>,
+    /// URLs of podcasts to unsubscribe from
+    #[serde(default)]
+    pub remove: Vec<Url>,
+}
+
+/// Response to [upload_subscription_changes](crate::subscription::SubscriptionChanges::upload_subscription_changes)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct UploadSubscriptionChangesResponse {
+    /// timestamp/ID that can be used for requesting changes since this upload in a subsequent API call
+    #[serde(deserialize_with = "crate::de::timestamp")]
+    pub timestamp: u64,
+    /// list of URLs that have been rewritten as a list of tuples
+    ///
+    /// The client SHOULD parse this list and update the local subscription list accordingly (the server only sanitizes the URL, so the semantic “content” should stay the same and therefore the client can simply update the URL value locally and use it for future updates.
+    ///
+    /// The [sanitized](UrlRewrite::sanitized) URL is `None` if the server rejected the URL. [upload_subscription_changes_checked](crate::subscription::SubscriptionChanges::upload_subscription_changes_checked) sorts the uploaded URLs by these outcomes.
+    #[serde(default)]
+    pub update_urls: Vec<UrlRewrite>,
+}
+
+/// URL rewritten by the server, as listed in the `update_urls` of upload responses
+///
+/// Serialized like the server does, i.e. as pair of the original and the sanitized URL with an empty string for rejected URLs.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct UrlRewrite {
+    /// URL as uploaded
+    pub original: Url,
+    /// URL stored by the server instead, `None` if the server rejected the URL, i.e. rewrote it to an empty string
+    pub sanitized: Option<Url>,
+}
+
+/// Response to [get_subscription_changes](crate::subscription::SubscriptionChanges::get_subscription_changes)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct GetSubscriptionChangesResponse {
+    /// The timestamp SHOULD be stored by the client in order to provide it in the since parameter in the next request.
+    #[serde(deserialize_with = "crate::de::timestamp")]
+    pub timestamp: u64,
+    /// URLs that should be added
+    pub add: Vec<Url>,
+    /// URLs that should be removed
+    pub remove: Vec<Url>,
+}
+
+impl Serialize for UrlRewrite {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (
+            self.original.as_str(),
+            self.sanitized.as_ref().map_or("", Url::as_str),
+        )
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for UrlRewrite {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (original, sanitized): (Url, Option<String>) = Deserialize::deserialize(deserializer)?;
+        let sanitized = match sanitized.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(sanitized) => Some(Url::parse(sanitized).map_err(de::Error::custom)?),
+        };
+        Ok(UrlRewrite {
+            original,
+            sanitized,
+        })
+    }
+}
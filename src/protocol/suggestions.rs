@@ -0,0 +1,27 @@
+//! [Suggestions API](https://gpoddernet.readthedocs.io/en/latest/api/reference/suggestions.html)
+//!
+//! Suggestions are returned as `Vec<Suggestion>`.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// A podcast suggestion as returned by [retrieve_suggested_podcasts](crate::suggestion::RetrieveSuggestedPodcasts::retrieve_suggested_podcasts)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Suggestion {
+    /// website of podcast
+    pub website: Url,
+    /// service-internal feed link
+    pub mygpo_link: Url,
+    /// description of podcast
+    pub description: String,
+    /// number of subscribers on service
+    pub subscribers: u32,
+    /// title of podcast
+    pub title: String,
+    /// feed URL
+    pub url: Url,
+    /// number of subscribers on service one week before
+    pub subscribers_last_week: u32,
+    /// URL to logo of podcast
+    pub logo_url: Option<Url>,
+}
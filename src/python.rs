@@ -0,0 +1,235 @@
+//! Python bindings built with [PyO3](https://pyo3.rs/)
+//!
+//! Enabled by the Cargo feature `pyo3`. Build the extension module with e.g. `maturin build --features pyo3,pyo3/extension-module`.
+//!
+//! The bindings are not a drop-in replacement for the [original Python mygpoclient](https://github.com/gpodder/mygpoclient). Many method names are borrowed from it, but its `MygpoClient` class takes the device ID per call and returns objects, while these bindings mirror the Rust clients: a `DeviceClient` is bound to one device, responses are returned as the `dict`s and `list`s of the JSON API, and URLs are passed as `str`. Scripts written for the original library need to be adapted.
+//!
+//! ```python
+//! import mygpoclient
+//!
+//! client = mygpoclient.PublicClient()
+//! for podcast in client.get_toplist(10):
+//!     print(podcast["title"])
+//! ```
+
+// the glue generated by PyO3 contains unsafe code
+#![allow(unsafe_code)]
+
+use crate::client;
+use crate::device::ListDevices;
+use crate::directory::{
+    PodcastSearch, PodcastToplist, RetrieveEpisodeData, RetrievePodcastData,
+    RetrievePodcastsForTag, RetrieveTopTags,
+};
+use crate::episode::{EpisodeAction, GetEpisodeActions, UploadEpisodeActions};
+use crate::error::Error;
+use crate::reference::{DeviceId, EpisodeRef, Username};
+use crate::subscription::{GetAllSubscriptions, SubscriptionChanges, SubscriptionsOfDevice};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use url::Url;
+
+create_exception!(
+    mygpoclient,
+    MygpoError,
+    PyException,
+    "Error raised by requests to gpodder.net"
+);
+
+/// Python class `PublicClient`, see [client::PublicClient]
+#[pyclass(name = "PublicClient")]
+#[derive(Debug)]
+pub struct PyPublicClient(client::PublicClient);
+
+/// Python class `AuthenticatedClient`, see [client::AuthenticatedClient]
+#[pyclass(name = "AuthenticatedClient")]
+#[derive(Debug)]
+pub struct PyAuthenticatedClient(client::AuthenticatedClient);
+
+/// Python class `DeviceClient`, see [client::DeviceClient]
+#[pyclass(name = "DeviceClient")]
+#[derive(Debug)]
+pub struct PyDeviceClient(client::DeviceClient);
+
+#[pymethods]
+impl PyPublicClient {
+    #[new]
+    fn new() -> Self {
+        PyPublicClient(client::PublicClient::default())
+    }
+
+    /// see [podcast_toplist](PodcastToplist::podcast_toplist)
+    #[pyo3(signature = (count = 50))]
+    fn get_toplist(&self, py: Python<'_>, count: u8) -> PyResult<PyObject> {
+        let toplist = py.allow_threads(|| self.0.podcast_toplist(count, None))?;
+        to_python(py, &toplist)
+    }
+
+    /// see [podcast_search](PodcastSearch::podcast_search)
+    fn search_podcasts(&self, py: Python<'_>, query: &str) -> PyResult<PyObject> {
+        let podcasts = py.allow_threads(|| self.0.podcast_search(query, None))?;
+        to_python(py, &podcasts)
+    }
+
+    /// see [retrieve_top_tags](RetrieveTopTags::retrieve_top_tags)
+    #[pyo3(signature = (count = 50))]
+    fn get_toptags(&self, py: Python<'_>, count: u8) -> PyResult<PyObject> {
+        let tags = py.allow_threads(|| self.0.retrieve_top_tags(count))?;
+        to_python(py, &tags)
+    }
+
+    /// see [retrieve_podcasts_for_tag](RetrievePodcastsForTag::retrieve_podcasts_for_tag)
+    #[pyo3(signature = (tag, count = 50))]
+    fn get_podcasts_of_a_tag(&self, py: Python<'_>, tag: &str, count: u8) -> PyResult<PyObject> {
+        let podcasts = py.allow_threads(|| self.0.retrieve_podcasts_for_tag(tag.into(), count))?;
+        to_python(py, &podcasts)
+    }
+
+    /// see [retrieve_podcast_data](RetrievePodcastData::retrieve_podcast_data)
+    fn get_podcast_data(&self, py: Python<'_>, podcast_url: &str) -> PyResult<PyObject> {
+        let url = parse_url(podcast_url)?;
+        let podcast = py.allow_threads(|| self.0.retrieve_podcast_data(url))?;
+        to_python(py, &podcast)
+    }
+
+    /// see [retrieve_episode_data](RetrieveEpisodeData::retrieve_episode_data)
+    fn get_episode_data(
+        &self,
+        py: Python<'_>,
+        podcast_url: &str,
+        episode_url: &str,
+    ) -> PyResult<PyObject> {
+        let episode = EpisodeRef::new(parse_url(podcast_url)?, parse_url(episode_url)?);
+        let episode = py.allow_threads(|| self.0.retrieve_episode_data(episode))?;
+        to_python(py, &episode)
+    }
+}
+
+#[pymethods]
+impl PyAuthenticatedClient {
+    #[new]
+    fn new(username: &str, password: &str) -> PyResult<Self> {
+        Ok(PyAuthenticatedClient(client::AuthenticatedClient::try_new(
+            username, password,
+        )?))
+    }
+
+    /// see [list_devices](ListDevices::list_devices)
+    fn get_devices(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let devices = py.allow_threads(|| self.0.list_devices())?;
+        to_python(py, &devices)
+    }
+
+    /// see [get_all_subscriptions](GetAllSubscriptions::get_all_subscriptions)
+    fn get_all_subscriptions(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let podcasts = py.allow_threads(|| self.0.get_all_subscriptions())?;
+        to_python(py, &podcasts)
+    }
+
+    /// see [upload_episode_actions](UploadEpisodeActions::upload_episode_actions)
+    ///
+    /// `actions` is a list of `dict`s in the format of the API and the new timestamp is returned.
+    fn upload_episode_actions(&self, py: Python<'_>, actions: &PyAny) -> PyResult<u64> {
+        let actions: Vec<EpisodeAction> = from_python(py, actions)?;
+        let response = py.allow_threads(|| self.0.upload_episode_actions(&actions))?;
+        Ok(response.timestamp)
+    }
+
+    /// see [get_episode_actions](GetEpisodeActions::get_episode_actions)
+    #[pyo3(signature = (since = None, podcast = None))]
+    fn download_episode_actions(
+        &self,
+        py: Python<'_>,
+        since: Option<u64>,
+        podcast: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let podcast = podcast.map(parse_url).transpose()?;
+        let response = py.allow_threads(|| self.0.get_episode_actions(podcast, since, false))?;
+        to_python(py, &response)
+    }
+}
+
+#[pymethods]
+impl PyDeviceClient {
+    #[new]
+    fn new(username: &str, password: &str, device_id: &str) -> PyResult<Self> {
+        Ok(PyDeviceClient(client::DeviceClient::try_new(
+            &Username::from(username),
+            password,
+            &DeviceId::from(device_id),
+        )?))
+    }
+
+    /// see [get_subscriptions_of_device](SubscriptionsOfDevice::get_subscriptions_of_device)
+    fn get_subscriptions(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let subscriptions = py.allow_threads(|| self.0.get_subscriptions_of_device())?;
+        to_python(py, &subscriptions)
+    }
+
+    /// see [upload_subscription_changes](SubscriptionChanges::upload_subscription_changes)
+    ///
+    /// Returns the response with the new timestamp and rewritten URLs.
+    fn update_subscriptions(
+        &self,
+        py: Python<'_>,
+        add: Vec<&str>,
+        remove: Vec<&str>,
+    ) -> PyResult<PyObject> {
+        let add = parse_urls(&add)?;
+        let remove = parse_urls(&remove)?;
+        let response = py.allow_threads(|| self.0.upload_subscription_changes(&add, &remove))?;
+        to_python(py, &response)
+    }
+
+    /// see [get_subscription_changes](SubscriptionChanges::get_subscription_changes)
+    #[pyo3(signature = (since = 0))]
+    fn pull_subscriptions(&self, py: Python<'_>, since: u64) -> PyResult<PyObject> {
+        let changes = py.allow_threads(|| self.0.get_subscription_changes(since))?;
+        to_python(py, &changes)
+    }
+}
+
+/// Python module `mygpoclient`
+#[pymodule]
+fn mygpoclient(py: Python<'_>, module: &PyModule) -> PyResult<()> {
+    module.add_class::<PyPublicClient>()?;
+    module.add_class::<PyAuthenticatedClient>()?;
+    module.add_class::<PyDeviceClient>()?;
+    module.add("MygpoError", py.get_type::<MygpoError>())?;
+    Ok(())
+}
+
+impl From<Error> for PyErr {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::UrlError(_) | Error::ValidationError(_) | Error::InvalidConfiguration { .. } => {
+                PyValueError::new_err(error.to_string())
+            }
+            _ => MygpoError::new_err(error.to_string()),
+        }
+    }
+}
+
+fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(Error::from)?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.into())
+}
+
+fn from_python<T: DeserializeOwned>(py: Python<'_>, value: &PyAny) -> PyResult<T> {
+    let json: String = py
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&json).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+fn parse_url(url: &str) -> PyResult<Url> {
+    Ok(Url::parse(url).map_err(Error::from)?)
+}
+
+fn parse_urls(urls: &[&str]) -> PyResult<Vec<Url>> {
+    urls.iter().map(|url| parse_url(url)).collect()
+}
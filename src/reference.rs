@@ -0,0 +1,174 @@
+//! Typed references to API entities
+//!
+//! Methods taking several URLs or strings accept these wrappers instead, so mixed-up arguments are type errors.
+
+#[cfg(feature = "directory")]
+use crate::directory::{self, Episode};
+#[cfg(feature = "episodes")]
+use crate::episode::EpisodeAction;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use url::Url;
+
+/// Episode identified by the feed URL of its podcast and its media URL
+///
+/// Accepted by APIs concerning a single episode, so the two URLs can't be swapped at call sites.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::reference::EpisodeRef;
+/// use url::Url;
+///
+/// let episode = EpisodeRef {
+///     podcast: Url::parse("http://example.com/feed1.rss").unwrap(),
+///     episode: Url::parse("http://example.com/files/s01e20.mp3").unwrap(),
+/// };
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct EpisodeRef {
+    /// feed URL of the podcast the episode belongs to
+    pub podcast: Url,
+    /// media URL of the episode
+    pub episode: Url,
+}
+
+impl EpisodeRef {
+    /// Create [EpisodeRef]
+    pub fn new(podcast: Url, episode: Url) -> EpisodeRef {
+        EpisodeRef { podcast, episode }
+    }
+}
+
+#[cfg(feature = "episodes")]
+impl From<&EpisodeAction> for EpisodeRef {
+    fn from(action: &EpisodeAction) -> Self {
+        EpisodeRef::new(action.podcast.clone(), action.episode.clone())
+    }
+}
+
+#[cfg(feature = "directory")]
+impl From<&Episode> for EpisodeRef {
+    fn from(episode: &Episode) -> Self {
+        EpisodeRef::new(episode.podcast_url.clone(), episode.url.clone())
+    }
+}
+
+impl fmt::Display for EpisodeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {}", self.episode, self.podcast)
+    }
+}
+
+/// Name of a gpodder.net user
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::reference::Username;
+///
+/// let username = Username::from("stefan");
+/// assert_eq!("stefan", username.as_str());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[serde(transparent)]
+pub struct Username(String);
+
+/// ID of a device of a user
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[serde(transparent)]
+pub struct DeviceId(String);
+
+/// URL-friendly name of a podcast list of a user, as contained in its [share URL](crate::podcast_list::PodcastList::share_url)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[serde(transparent)]
+pub struct ListName(String);
+
+/// Name of a podcast tag, as in [tag](crate::directory::Tag::tag)
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[serde(transparent)]
+pub struct TagName<'a>(&'a str);
+
+macro_rules! impl_string_reference {
+    ($type:ident) => {
+        impl $type {
+            /// Returns the wrapped string
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<&str> for $type {
+            fn from(value: &str) -> Self {
+                $type(value.to_owned())
+            }
+        }
+
+        impl From<String> for $type {
+            fn from(value: String) -> Self {
+                $type(value)
+            }
+        }
+
+        impl AsRef<str> for $type {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+impl_string_reference!(Username);
+impl_string_reference!(DeviceId);
+impl_string_reference!(ListName);
+
+impl<'a> TagName<'a> {
+    /// Returns the wrapped tag name
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+impl<'a> From<&'a str> for TagName<'a> {
+    fn from(tag: &'a str) -> Self {
+        TagName(tag)
+    }
+}
+
+#[cfg(feature = "directory")]
+impl<'a> From<&'a directory::Tag> for TagName<'a> {
+    fn from(tag: &'a directory::Tag) -> Self {
+        TagName(&tag.tag)
+    }
+}
+
+impl fmt::Display for TagName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+#[cfg(all(test, feature = "episodes"))]
+mod tests {
+    use super::EpisodeRef;
+    use crate::episode::EpisodeAction;
+    use url::Url;
+
+    #[test]
+    fn episode_ref_of_action() {
+        let podcast = Url::parse("http://example.com/feed1.rss").unwrap();
+        let episode = Url::parse("http://example.com/files/s01e20.mp3").unwrap();
+        let action = EpisodeAction::new_download(podcast.clone(), episode.clone(), None);
+
+        let episode_ref = EpisodeRef::from(&action);
+
+        assert_eq!(podcast, episode_ref.podcast);
+        assert_eq!(episode, episode_ref.episode);
+    }
+}
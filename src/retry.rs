@@ -0,0 +1,197 @@
+//! Retrying failed requests
+//!
+//! Attach a [RetryPolicy] to a client with e.g. [with_retry_policy](crate::client::AuthenticatedClient::with_retry_policy). After every attempt, the client asks the policy whether and when to send the request again. Without a policy, requests are sent once.
+
+use crate::error::Error;
+use reqwest::Method;
+use std::fmt;
+use std::time::Duration;
+use url::Url;
+
+/// Outcome of an attempt to send a request, as passed to a [RetryPolicy]
+#[derive(Debug)]
+pub struct RetryAttempt<'a> {
+    /// HTTP method of the request
+    pub method: &'a Method,
+    /// URL of the request
+    pub url: &'a Url,
+    /// number of the attempt, starting at 1
+    pub attempt: u32,
+    /// status code of the response, if one was received
+    pub status: Option<u16>,
+    /// error which occurred while sending the request or reading the response
+    pub error: Option<&'a Error>,
+}
+
+/// Rules for retrying requests
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::AuthenticatedClient;
+/// use mygpoclient::retry::{ExponentialBackoff, RetryAttempt, RetryPolicy};
+/// use reqwest::Method;
+/// use std::time::Duration;
+///
+/// /// Never retries POST requests
+/// #[derive(Debug)]
+/// struct NoPostRetries(ExponentialBackoff);
+///
+/// impl RetryPolicy for NoPostRetries {
+///     fn retry_delay(&self, attempt: &RetryAttempt<'_>) -> Option<Duration> {
+///         if *attempt.method == Method::POST {
+///             None
+///         } else {
+///             self.0.retry_delay(attempt)
+///         }
+///     }
+/// }
+///
+/// let client = AuthenticatedClient::new("username", "password")
+///     .with_retry_policy(NoPostRetries(ExponentialBackoff::default()));
+/// ```
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    /// Returns the delay before the next attempt, or `None` to return the outcome of this attempt
+    fn retry_delay(&self, attempt: &RetryAttempt<'_>) -> Option<Duration>;
+}
+
+/// [RetryPolicy] doubling the delay after every attempt
+///
+/// Retries [retryable errors](Error::is_retryable) as well as responses with status `429 Too Many Requests`, `502 Bad Gateway`, `503 Service Unavailable` and `504 Gateway Timeout`.
+///
+/// During [maintenance](Error::Maintenance), the announced `Retry-After` delay is waited instead. If it exceeds the maximum delay, the error is returned right away, so callers like the [Scheduler](crate::sync::Scheduler) can postpone their work instead of blocking.
+///
+/// The default is 3 retries, starting with a delay of 500 ms and never waiting longer than 30 s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExponentialBackoff {
+    max_retries: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl ExponentialBackoff {
+    /// Retry at most `max_retries` times
+    pub fn new(max_retries: u32) -> ExponentialBackoff {
+        ExponentialBackoff {
+            max_retries,
+            ..ExponentialBackoff::default()
+        }
+    }
+
+    /// Wait `initial_delay` before the first retry
+    pub fn with_initial_delay(mut self, initial_delay: Duration) -> ExponentialBackoff {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// Never wait longer than `max_delay` before a retry
+    pub fn with_max_delay(mut self, max_delay: Duration) -> ExponentialBackoff {
+        self.max_delay = max_delay;
+        self
+    }
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        ExponentialBackoff {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_delay(&self, attempt: &RetryAttempt<'_>) -> Option<Duration> {
+        if attempt.attempt > self.max_retries {
+            return None;
+        }
+
+        if let Some(Error::Maintenance {
+            retry_after: Some(retry_after),
+            ..
+        }) = attempt.error
+        {
+            return Some(*retry_after).filter(|retry_after| *retry_after <= self.max_delay);
+        }
+
+        let retryable = attempt.error.is_some_and(Error::is_retryable)
+            || matches!(
+                attempt.status,
+                Some(429) | Some(502) | Some(503) | Some(504)
+            );
+        if !retryable {
+            return None;
+        }
+
+        Some(
+            self.initial_delay
+                .checked_mul(2u32.saturating_pow(attempt.attempt - 1))
+                .map_or(self.max_delay, |delay| delay.min(self.max_delay)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExponentialBackoff, RetryAttempt, RetryPolicy};
+    use crate::error::Error;
+    use reqwest::Method;
+    use std::time::Duration;
+    use url::Url;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = ExponentialBackoff::new(3).with_max_delay(Duration::from_millis(1500));
+        let url = Url::parse("https://gpodder.net/api/2/devices/username.json").unwrap();
+        let attempt = |attempt, status| RetryAttempt {
+            method: &Method::GET,
+            url: &url,
+            attempt,
+            status: Some(status),
+            error: None,
+        };
+
+        assert_eq!(
+            Some(Duration::from_millis(500)),
+            policy.retry_delay(&attempt(1, 503))
+        );
+        assert_eq!(
+            Some(Duration::from_millis(1000)),
+            policy.retry_delay(&attempt(2, 429))
+        );
+        assert_eq!(
+            Some(Duration::from_millis(1500)),
+            policy.retry_delay(&attempt(3, 503))
+        );
+        assert_eq!(None, policy.retry_delay(&attempt(4, 503)));
+        assert_eq!(None, policy.retry_delay(&attempt(1, 200)));
+        assert_eq!(None, policy.retry_delay(&attempt(1, 404)));
+    }
+
+    #[test]
+    fn honor_retry_after_of_maintenance() {
+        let policy = ExponentialBackoff::new(3).with_max_delay(Duration::from_secs(30));
+        let url = Url::parse("https://gpodder.net/api/2/devices/username.json").unwrap();
+        let maintenance = |retry_after| Error::Maintenance {
+            endpoint: String::from("/api/2/devices/username.json"),
+            retry_after,
+        };
+        let delay = |error: &Error| {
+            policy.retry_delay(&RetryAttempt {
+                method: &Method::GET,
+                url: &url,
+                attempt: 1,
+                status: None,
+                error: Some(error),
+            })
+        };
+
+        assert_eq!(
+            Some(Duration::from_secs(20)),
+            delay(&maintenance(Some(Duration::from_secs(20))))
+        );
+        assert_eq!(None, delay(&maintenance(Some(Duration::from_secs(3600)))));
+        assert_eq!(Some(Duration::from_millis(500)), delay(&maintenance(None)));
+    }
+}
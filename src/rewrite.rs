@@ -0,0 +1,142 @@
+//! Rewriting of media URLs, e.g. to route downloads through a proxy or mirror
+//!
+//! Attach a [UrlRewriter] to a client with e.g. [with_url_rewriter](crate::client::PublicClient::with_url_rewriter). It's applied to the media URLs of episodes and the logo URLs of podcasts returned by the [directory](crate::directory) and [podcast list](crate::podcast_list) APIs as well as to favorite episodes. Feed URLs aren't rewritten, as they identify podcasts in requests to gpodder.net.
+
+use crate::client::{AuthenticatedClient, DeviceClient, PublicClient};
+use crate::directory::Episode;
+use crate::subscription::Podcast;
+use std::fmt;
+use std::sync::Arc;
+use url::Url;
+
+/// Hook rewriting media and logo URLs returned by gpodder.net
+pub trait UrlRewriter: fmt::Debug + Send + Sync {
+    /// Returns the URL to use instead of `url`
+    fn rewrite(&self, url: Url) -> Url;
+}
+
+/// [UrlRewriter] replacing a URL prefix, e.g. the host of the original server with the one of a mirror
+///
+/// URLs not starting with the prefix are kept.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::PublicClient;
+/// use mygpoclient::rewrite::{PrefixRewriter, UrlRewriter};
+/// use url::Url;
+///
+/// let rewriter = PrefixRewriter::new("http://example.com/files/", "https://mirror.example.org/example.com/");
+/// assert_eq!(
+///     "https://mirror.example.org/example.com/s01e20.mp3",
+///     rewriter.rewrite(Url::parse("http://example.com/files/s01e20.mp3").unwrap()).as_str()
+/// );
+///
+/// let client = PublicClient::default().with_url_rewriter(rewriter);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrefixRewriter {
+    from: String,
+    to: String,
+}
+
+impl PrefixRewriter {
+    /// Replace the prefix `from` with `to`
+    pub fn new(from: &str, to: &str) -> PrefixRewriter {
+        PrefixRewriter {
+            from: from.to_owned(),
+            to: to.to_owned(),
+        }
+    }
+}
+
+impl UrlRewriter for PrefixRewriter {
+    fn rewrite(&self, url: Url) -> Url {
+        match url.as_str().strip_prefix(self.from.as_str()) {
+            Some(rest) => Url::parse(&format!("{}{}", self.to, rest)).unwrap_or(url),
+            None => url,
+        }
+    }
+}
+
+impl PublicClient {
+    /// Rewrite media and logo URLs of responses with the given [UrlRewriter]
+    pub fn with_url_rewriter<R: UrlRewriter + 'static>(mut self, url_rewriter: R) -> PublicClient {
+        self.url_rewriter = Some(Arc::new(url_rewriter));
+        self
+    }
+
+    pub(crate) fn rewrite_podcast(&self, mut podcast: Podcast) -> Podcast {
+        if let Some(url_rewriter) = &self.url_rewriter {
+            podcast.logo_url = podcast.logo_url.map(|url| url_rewriter.rewrite(url));
+            podcast.scaled_logo_url = podcast.scaled_logo_url.map(|url| url_rewriter.rewrite(url));
+        }
+        podcast
+    }
+
+    pub(crate) fn rewrite_episode(&self, mut episode: Episode) -> Episode {
+        if let Some(url_rewriter) = &self.url_rewriter {
+            episode.url = url_rewriter.rewrite(episode.url);
+        }
+        episode
+    }
+}
+
+impl AuthenticatedClient {
+    /// Rewrite media and logo URLs of responses with the given [UrlRewriter]
+    pub fn with_url_rewriter<R: UrlRewriter + 'static>(
+        mut self,
+        url_rewriter: R,
+    ) -> AuthenticatedClient {
+        self.public_client = self.public_client.with_url_rewriter(url_rewriter);
+        self
+    }
+}
+
+impl DeviceClient {
+    /// Rewrite media and logo URLs of responses with the given [UrlRewriter]
+    pub fn with_url_rewriter<R: UrlRewriter + 'static>(mut self, url_rewriter: R) -> DeviceClient {
+        self.authenticated_client = self.authenticated_client.with_url_rewriter(url_rewriter);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrefixRewriter;
+    use crate::client::PublicClient;
+    use crate::subscription::Podcast;
+    use url::Url;
+
+    #[test]
+    fn rewrite_logo_urls_only() {
+        let client = PublicClient::default().with_url_rewriter(PrefixRewriter::new(
+            "http://example.com/",
+            "https://proxy.example.org/",
+        ));
+        let podcast = Podcast {
+            url: Url::parse("http://example.com/feed.rss").unwrap(),
+            title: String::from("Podcast"),
+            author: None,
+            description: String::new(),
+            subscribers: 1,
+            subscribers_last_week: 1,
+            logo_url: Some(Url::parse("http://example.com/logo.png").unwrap()),
+            scaled_logo_url: Some(Url::parse("http://other.example.com/logo.png").unwrap()),
+            website: None,
+            mygpo_link: Url::parse("http://gpodder.net/podcast/1").unwrap(),
+        };
+
+        let podcast = client.rewrite_podcast(podcast);
+
+        assert_eq!("http://example.com/feed.rss", podcast.url.as_str());
+        assert_eq!(
+            Some("https://proxy.example.org/logo.png"),
+            podcast.logo_url.as_ref().map(Url::as_str)
+        );
+        assert_eq!(
+            Some("http://other.example.com/logo.png"),
+            podcast.scaled_logo_url.as_ref().map(Url::as_str)
+        );
+    }
+}
@@ -1,16 +1,45 @@
 //! [Settings API](https://gpoddernet.readthedocs.io/en/latest/api/reference/settings.html)
+//!
+//! Besides arbitrary key-value pairs, the sync group of a device is available through the typed accessors [get_sync_group](DeviceClient::get_sync_group) and [set_sync_group](DeviceClient::set_sync_group). It is stored in the device settings under [SYNC_GROUP_KEY].
+//!
+//! Likewise, whether a podcast appears in the public profile of the user is available through [get_podcast_privacy](AuthenticatedClient::get_podcast_privacy) and [set_podcast_private](AuthenticatedClient::set_podcast_private). It is stored in the podcast settings under [PUBLIC_KEY]. Whether the whole profile is public, e.g. for sharing links to the subscriptions of the user, is stored in the account settings under [PUBLIC_SUBSCRIPTIONS_KEY] and available through [is_profile_public](AuthenticatedClient::is_profile_public) and [set_profile_public](AuthenticatedClient::set_profile_public).
 
+use crate::bulk::BulkResult;
+use crate::client::ApiArea;
+use crate::client::ApiResponse;
 use crate::client::AuthenticatedClient;
 use crate::client::DeviceClient;
 use crate::error::Error;
-use serde::Serialize;
+use crate::reference::{DeviceId, EpisodeRef};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use url::Url;
 
-#[derive(Serialize)]
-pub(crate) struct SaveSettingsRequest {
-    pub(crate) set: HashMap<String, String>,
-    pub(crate) remove: Vec<String>,
+pub use crate::protocol::settings::SaveSettingsRequest;
+
+/// Device setting holding the name of the group of devices whose subscriptions are kept identical
+///
+/// Clients only agree on the group if they use the same key, so prefer the typed accessors [get_sync_group](DeviceClient::get_sync_group) and [set_sync_group](DeviceClient::set_sync_group) over writing it directly.
+pub const SYNC_GROUP_KEY: &str = "sync_group";
+
+/// Podcast setting holding whether the subscription to the podcast is public
+///
+/// Prefer the typed accessors [get_podcast_privacy](AuthenticatedClient::get_podcast_privacy) and [set_podcast_private](AuthenticatedClient::set_podcast_private) over writing it directly.
+pub const PUBLIC_KEY: &str = "public";
+
+/// Account setting holding whether the profile and subscriptions of the user are public
+///
+/// This is the key gpodder.net itself reads for the subscriptions page and OPML export of a user, see [its list of well-known settings](https://github.com/gpodder/mygpo/blob/master/mygpo/users/settings.py). Prefer the typed accessors [is_profile_public](AuthenticatedClient::is_profile_public) and [set_profile_public](AuthenticatedClient::set_profile_public) over writing it directly.
+pub const PUBLIC_SUBSCRIPTIONS_KEY: &str = "public_subscriptions";
+
+/// Visibility of a podcast subscription to other users, see [get_podcast_privacy](AuthenticatedClient::get_podcast_privacy)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum PodcastPrivacy {
+    /// subscription is shown in the public profile of the user, which is the default
+    Public,
+    /// subscription is hidden from other users
+    Private,
 }
 
 /// see [save_account_settings](SaveAccountSettings::save_account_settings)
@@ -61,9 +90,10 @@ pub trait SaveDeviceSettings {
     /// use mygpoclient::settings::SaveDeviceSettings;
     /// use std::collections::HashMap;
     ///
-    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
     /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
-    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
     /// #
     /// let client = DeviceClient::new(&username, &password, &deviceid);
     /// let mut set = HashMap::new();
@@ -88,6 +118,45 @@ pub trait SaveDeviceSettings {
     ) -> Result<HashMap<String, String>, Error>;
 }
 
+/// see [save_settings_of_device](SaveSettingsOfDevice::save_settings_of_device)
+pub trait SaveSettingsOfDevice {
+    /// Save Device Settings of the device with the given ID
+    ///
+    /// Like [save_device_settings](SaveDeviceSettings::save_device_settings), but usable without a [DeviceClient]. Returns [DeviceNotFound](Error::DeviceNotFound) if the device doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::reference::DeviceId;
+    /// use mygpoclient::settings::SaveSettingsOfDevice;
+    /// use std::collections::HashMap;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    /// let mut set = HashMap::new();
+    /// set.insert(String::from("setting1"), String::from("value1"));
+    ///
+    /// let settings = client.save_settings_of_device(&DeviceId::from(deviceid), set.clone(), Vec::new())?;
+    /// assert!(set.iter().all(|(key, value)| settings.get_key_value(key).unwrap() == (key, value)));
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    ///
+    /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/settings.html#save-settings)
+    fn save_settings_of_device(
+        &self,
+        device_id: &DeviceId,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+    ) -> Result<HashMap<String, String>, Error>;
+}
+
 /// see [save_podcast_settings](SavePodcastSettings::save_podcast_settings)
 pub trait SavePodcastSettings {
     /// Save Podcast Settings
@@ -125,6 +194,31 @@ pub trait SavePodcastSettings {
         remove: Vec<String>,
         podcast: Url,
     ) -> Result<HashMap<String, String>, Error>;
+
+    /// Save the same Podcast Settings for each of the given podcasts
+    ///
+    /// All podcasts are processed, even if saving the settings of some of them fails. The returned [BulkResult] contains the index of the podcast with each result.
+    ///
+    /// # See also
+    ///
+    /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/settings.html#save-settings)
+    fn save_podcast_settings_of_all(
+        &self,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+        podcasts: &[Url],
+    ) -> BulkResult<HashMap<String, String>> {
+        let mut result = BulkResult::default();
+
+        for (index, podcast) in podcasts.iter().enumerate() {
+            result.push(
+                index..index + 1,
+                self.save_podcast_settings(set.clone(), remove.clone(), podcast.clone()),
+            );
+        }
+
+        result
+    }
 }
 
 /// see [save_episode_settings](SaveEpisodeSettings::save_episode_settings)
@@ -135,6 +229,7 @@ pub trait SaveEpisodeSettings {
     ///
     /// ```
     /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::reference::EpisodeRef;
     /// use mygpoclient::settings::SaveEpisodeSettings;
     /// use std::collections::HashMap;
     /// use url::Url;
@@ -148,7 +243,8 @@ pub trait SaveEpisodeSettings {
     /// set.insert(String::from("setting2"), String::from("value2"));
     /// let remove = vec![String::from("setting3"), String::from("setting4")];
     ///
-    /// let settings = client.save_episode_settings(set.clone(), remove.clone(), Url::parse("http://example.com/feed1.rss").unwrap(), Url::parse("http://example.com/files/s01e20.mp3").unwrap())?;
+    /// let episode = EpisodeRef::new(Url::parse("http://example.com/feed1.rss").unwrap(), Url::parse("http://example.com/files/s01e20.mp3").unwrap());
+    /// let settings = client.save_episode_settings(set.clone(), remove.clone(), episode)?;
     /// assert!(set.iter().all(|(key, value)| settings.get_key_value(key).unwrap() == (key, value)));
     /// assert!(remove.iter().all(|key| settings.get(key).is_none()));
     /// #
@@ -162,8 +258,7 @@ pub trait SaveEpisodeSettings {
         &self,
         set: HashMap<String, String>,
         remove: Vec<String>,
-        podcast: Url,
-        episode: Url,
+        episode: EpisodeRef,
     ) -> Result<HashMap<String, String>, Error>;
 }
 
@@ -203,9 +298,10 @@ pub trait GetDeviceSettings {
     /// use mygpoclient::client::DeviceClient;
     /// use mygpoclient::settings::GetDeviceSettings;
     ///
-    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
     /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
-    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
     /// #
     /// let client = DeviceClient::new(&username, &password, &deviceid);
     ///
@@ -220,6 +316,39 @@ pub trait GetDeviceSettings {
     fn get_device_settings(&self) -> Result<HashMap<String, String>, Error>;
 }
 
+/// see [get_settings_of_device](GetSettingsOfDevice::get_settings_of_device)
+pub trait GetSettingsOfDevice {
+    /// Get Device Settings of the device with the given ID
+    ///
+    /// Like [get_device_settings](GetDeviceSettings::get_device_settings), but usable without a [DeviceClient]. Returns [DeviceNotFound](Error::DeviceNotFound) if the device doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::reference::DeviceId;
+    /// use mygpoclient::settings::GetSettingsOfDevice;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    ///
+    /// let settings = client.get_settings_of_device(&DeviceId::from(deviceid))?;
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    ///
+    /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/settings.html#get-settings)
+    fn get_settings_of_device(
+        &self,
+        device_id: &DeviceId,
+    ) -> Result<HashMap<String, String>, Error>;
+}
+
 /// see [get_podcast_settings](GetPodcastSettings::get_podcast_settings)
 pub trait GetPodcastSettings {
     /// Get Podcast Settings
@@ -255,6 +384,7 @@ pub trait GetEpisodeSettings {
     ///
     /// ```
     /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::reference::EpisodeRef;
     /// use mygpoclient::settings::GetEpisodeSettings;
     /// use url::Url;
     ///
@@ -263,7 +393,8 @@ pub trait GetEpisodeSettings {
     /// #
     /// let client = AuthenticatedClient::new(&username, &password);
     ///
-    /// let settings = client.get_episode_settings(Url::parse("http://example.com/feed1.rss").unwrap(), Url::parse("http://example.com/files/s01e20.mp3").unwrap())?;
+    /// let episode = EpisodeRef::new(Url::parse("http://example.com/feed1.rss").unwrap(), Url::parse("http://example.com/files/s01e20.mp3").unwrap());
+    /// let settings = client.get_episode_settings(episode)?;
     /// #
     /// # Ok::<(), mygpoclient::error::Error>(())
     /// ```
@@ -271,11 +402,7 @@ pub trait GetEpisodeSettings {
     /// # See also
     ///
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/settings.html#get-settings)
-    fn get_episode_settings(
-        &self,
-        podcast: Url,
-        episode: Url,
-    ) -> Result<HashMap<String, String>, Error>;
+    fn get_episode_settings(&self, episode: EpisodeRef) -> Result<HashMap<String, String>, Error>;
 }
 
 impl SaveAccountSettings for AuthenticatedClient {
@@ -284,15 +411,14 @@ impl SaveAccountSettings for AuthenticatedClient {
         set: HashMap<String, String>,
         remove: Vec<String>,
     ) -> Result<HashMap<String, String>, Error> {
-        Ok(self
-            .post(
-                &format!(
-                    "https://gpodder.net/api/2/settings/{}/account.json",
-                    self.username
-                ),
-                &SaveSettingsRequest { set, remove },
-            )?
-            .json()?)
+        self.post(
+            &self.api_url(
+                ApiArea::Settings,
+                &format!("settings/{}/account.json", self.username),
+            ),
+            &SaveSettingsRequest { set, remove },
+        )?
+        .json()
     }
 }
 
@@ -312,16 +438,42 @@ impl SaveDeviceSettings for DeviceClient {
         set: HashMap<String, String>,
         remove: Vec<String>,
     ) -> Result<HashMap<String, String>, Error> {
-        Ok(self
-            .post_with_query(
-                &format!(
-                    "https://gpodder.net/api/2/settings/{}/device.json",
-                    self.authenticated_client.username
-                ),
-                &SaveSettingsRequest { set, remove },
-                &[&("device", self.device_id.as_str())],
-            )?
-            .json()?)
+        self.authenticated_client.save_settings_of_device(
+            &DeviceId::from(&*self.device_id),
+            set,
+            remove,
+        )
+    }
+}
+
+impl SaveSettingsOfDevice for AuthenticatedClient {
+    fn save_settings_of_device(
+        &self,
+        device_id: &DeviceId,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+    ) -> Result<HashMap<String, String>, Error> {
+        let response = self.post_with_query(
+            &self.api_url(
+                ApiArea::Settings,
+                &format!("settings/{}/device.json", self.username),
+            ),
+            &SaveSettingsRequest { set, remove },
+            &[&("device", device_id.as_str())],
+        )?;
+        device_settings(response, device_id.as_str())
+    }
+}
+
+impl SaveSettingsOfDevice for DeviceClient {
+    fn save_settings_of_device(
+        &self,
+        device_id: &DeviceId,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+    ) -> Result<HashMap<String, String>, Error> {
+        self.authenticated_client
+            .save_settings_of_device(device_id, set, remove)
     }
 }
 
@@ -332,16 +484,15 @@ impl SavePodcastSettings for AuthenticatedClient {
         remove: Vec<String>,
         podcast: Url,
     ) -> Result<HashMap<String, String>, Error> {
-        Ok(self
-            .post_with_query(
-                &format!(
-                    "https://gpodder.net/api/2/settings/{}/podcast.json",
-                    self.username
-                ),
-                &SaveSettingsRequest { set, remove },
-                &[&("podcast", podcast.as_str())],
-            )?
-            .json()?)
+        self.post_with_query(
+            &self.api_url(
+                ApiArea::Settings,
+                &format!("settings/{}/podcast.json", self.username),
+            ),
+            &SaveSettingsRequest { set, remove },
+            &[&("podcast", podcast.as_str())],
+        )?
+        .json()
     }
 }
 
@@ -362,22 +513,20 @@ impl SaveEpisodeSettings for AuthenticatedClient {
         &self,
         set: HashMap<String, String>,
         remove: Vec<String>,
-        podcast: Url,
-        episode: Url,
+        episode: EpisodeRef,
     ) -> Result<HashMap<String, String>, Error> {
-        Ok(self
-            .post_with_query(
-                &format!(
-                    "https://gpodder.net/api/2/settings/{}/episode.json",
-                    self.username
-                ),
-                &SaveSettingsRequest { set, remove },
-                &[
-                    &("podcast", podcast.as_str()),
-                    &("episode", episode.as_str()),
-                ],
-            )?
-            .json()?)
+        self.post_with_query(
+            &self.api_url(
+                ApiArea::Settings,
+                &format!("settings/{}/episode.json", self.username),
+            ),
+            &SaveSettingsRequest { set, remove },
+            &[
+                &("podcast", episode.podcast.as_str()),
+                &("episode", episode.episode.as_str()),
+            ],
+        )?
+        .json()
     }
 }
 
@@ -386,22 +535,20 @@ impl SaveEpisodeSettings for DeviceClient {
         &self,
         set: HashMap<String, String>,
         remove: Vec<String>,
-        podcast: Url,
-        episode: Url,
+        episode: EpisodeRef,
     ) -> Result<HashMap<String, String>, Error> {
         self.authenticated_client
-            .save_episode_settings(set, remove, podcast, episode)
+            .save_episode_settings(set, remove, episode)
     }
 }
 
 impl GetAccountSettings for AuthenticatedClient {
     fn get_account_settings(&self) -> Result<HashMap<String, String>, Error> {
-        Ok(self
-            .get(&format!(
-                "https://gpodder.net/api/2/settings/{}/account.json",
-                self.username
-            ))?
-            .json()?)
+        self.get(&self.api_url(
+            ApiArea::Settings,
+            &format!("settings/{}/account.json", self.username),
+        ))?
+        .json()
     }
 }
 
@@ -413,29 +560,242 @@ impl GetAccountSettings for DeviceClient {
 
 impl GetDeviceSettings for DeviceClient {
     fn get_device_settings(&self) -> Result<HashMap<String, String>, Error> {
-        Ok(self
-            .get_with_query(
-                &format!(
-                    "https://gpodder.net/api/2/settings/{}/device.json",
-                    self.authenticated_client.username
-                ),
-                &[&("device", self.device_id.as_str())],
-            )?
-            .json()?)
+        self.authenticated_client
+            .get_settings_of_device(&DeviceId::from(&*self.device_id))
+    }
+}
+
+impl GetSettingsOfDevice for AuthenticatedClient {
+    fn get_settings_of_device(
+        &self,
+        device_id: &DeviceId,
+    ) -> Result<HashMap<String, String>, Error> {
+        let response = self.get_with_query(
+            &self.api_url(
+                ApiArea::Settings,
+                &format!("settings/{}/device.json", self.username),
+            ),
+            &[&("device", device_id.as_str())],
+        )?;
+        device_settings(response, device_id.as_str())
+    }
+}
+
+impl GetSettingsOfDevice for DeviceClient {
+    fn get_settings_of_device(
+        &self,
+        device_id: &DeviceId,
+    ) -> Result<HashMap<String, String>, Error> {
+        self.authenticated_client.get_settings_of_device(device_id)
+    }
+}
+
+impl DeviceClient {
+    /// Get the sync group of this device
+    ///
+    /// Returns `None` if the device isn't part of a sync group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::DeviceClient;
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// client.set_sync_group(Some("household"))?;
+    /// assert_eq!(Some(String::from("household")), client.get_sync_group()?);
+    ///
+    /// client.set_sync_group(None)?;
+    /// assert_eq!(None, client.get_sync_group()?);
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    ///
+    /// - [SYNC_GROUP_KEY]
+    pub fn get_sync_group(&self) -> Result<Option<String>, Error> {
+        Ok(sync_group(self.get_device_settings()?))
+    }
+
+    /// Set the sync group of this device, or remove it from its sync group with `None`
+    ///
+    /// # See also
+    ///
+    /// - [get_sync_group](DeviceClient::get_sync_group)
+    pub fn set_sync_group(&self, sync_group: Option<&str>) -> Result<(), Error> {
+        let (set, remove) = match sync_group {
+            Some(sync_group) => {
+                let mut set = HashMap::new();
+                set.insert(SYNC_GROUP_KEY.to_owned(), sync_group.to_owned());
+                (set, Vec::new())
+            }
+            None => (HashMap::new(), vec![SYNC_GROUP_KEY.to_owned()]),
+        };
+        self.save_device_settings(set, remove)?;
+        Ok(())
     }
 }
 
+impl AuthenticatedClient {
+    /// Get whether the subscription to `podcast` is public
+    ///
+    /// Podcasts without the setting are [public](PodcastPrivacy::Public).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::settings::PodcastPrivacy;
+    /// use url::Url;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    /// let podcast = Url::parse("http://goinglinux.com/mp3podcast.xml").unwrap();
+    ///
+    /// client.set_podcast_private(podcast.clone(), true)?;
+    /// assert_eq!(PodcastPrivacy::Private, client.get_podcast_privacy(podcast.clone())?);
+    ///
+    /// client.set_podcast_private(podcast.clone(), false)?;
+    /// assert_eq!(PodcastPrivacy::Public, client.get_podcast_privacy(podcast)?);
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    ///
+    /// - [PUBLIC_KEY]
+    pub fn get_podcast_privacy(&self, podcast: Url) -> Result<PodcastPrivacy, Error> {
+        Ok(podcast_privacy(&self.get_podcast_settings(podcast)?))
+    }
+
+    /// Hide the subscription to `podcast` from other users, or show it again with `false`
+    ///
+    /// # See also
+    ///
+    /// - [get_podcast_privacy](AuthenticatedClient::get_podcast_privacy)
+    pub fn set_podcast_private(&self, podcast: Url, private: bool) -> Result<(), Error> {
+        let mut set = HashMap::new();
+        set.insert(PUBLIC_KEY.to_owned(), (!private).to_string());
+        self.save_podcast_settings(set, Vec::new(), podcast)?;
+        Ok(())
+    }
+
+    /// Get whether the profile and subscriptions of this user are visible to other users
+    ///
+    /// Profiles without the setting are public.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    ///
+    /// client.set_profile_public(true)?;
+    /// assert!(client.is_profile_public()?);
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    ///
+    /// - [PUBLIC_SUBSCRIPTIONS_KEY]
+    pub fn is_profile_public(&self) -> Result<bool, Error> {
+        Ok(is_public(
+            &self.get_account_settings()?,
+            PUBLIC_SUBSCRIPTIONS_KEY,
+        ))
+    }
+
+    /// Show the profile and subscriptions of this user to other users, or hide them with `false`
+    ///
+    /// # See also
+    ///
+    /// - [is_profile_public](AuthenticatedClient::is_profile_public)
+    pub fn set_profile_public(&self, public: bool) -> Result<(), Error> {
+        let mut set = HashMap::new();
+        set.insert(PUBLIC_SUBSCRIPTIONS_KEY.to_owned(), public.to_string());
+        self.save_account_settings(set, Vec::new())?;
+        Ok(())
+    }
+}
+
+impl DeviceClient {
+    /// Get whether the subscription to `podcast` is public, see [AuthenticatedClient::get_podcast_privacy]
+    pub fn get_podcast_privacy(&self, podcast: Url) -> Result<PodcastPrivacy, Error> {
+        self.authenticated_client.get_podcast_privacy(podcast)
+    }
+
+    /// Hide the subscription to `podcast` from other users, see [AuthenticatedClient::set_podcast_private]
+    pub fn set_podcast_private(&self, podcast: Url, private: bool) -> Result<(), Error> {
+        self.authenticated_client
+            .set_podcast_private(podcast, private)
+    }
+
+    /// Get whether the profile of this user is public, see [AuthenticatedClient::is_profile_public]
+    pub fn is_profile_public(&self) -> Result<bool, Error> {
+        self.authenticated_client.is_profile_public()
+    }
+
+    /// Show the profile of this user to other users, see [AuthenticatedClient::set_profile_public]
+    pub fn set_profile_public(&self, public: bool) -> Result<(), Error> {
+        self.authenticated_client.set_profile_public(public)
+    }
+}
+
+fn podcast_privacy(settings: &HashMap<String, String>) -> PodcastPrivacy {
+    if is_public(settings, PUBLIC_KEY) {
+        PodcastPrivacy::Public
+    } else {
+        PodcastPrivacy::Private
+    }
+}
+
+/// Returns `false` only if the setting `key` is explicitly disabled
+fn is_public(settings: &HashMap<String, String>, key: &str) -> bool {
+    match settings.get(key).map(|public| public.trim()) {
+        Some(public) => !public.eq_ignore_ascii_case("false") && public != "0",
+        None => true,
+    }
+}
+
+fn sync_group(mut settings: HashMap<String, String>) -> Option<String> {
+    settings
+        .remove(SYNC_GROUP_KEY)
+        .filter(|sync_group| !sync_group.is_empty())
+}
+
+fn device_settings(
+    response: ApiResponse,
+    device_id: &str,
+) -> Result<HashMap<String, String>, Error> {
+    if response.status == StatusCode::NOT_FOUND {
+        return Err(Error::DeviceNotFound(device_id.to_owned()));
+    }
+    response.json()
+}
+
 impl GetPodcastSettings for AuthenticatedClient {
     fn get_podcast_settings(&self, podcast: Url) -> Result<HashMap<String, String>, Error> {
-        Ok(self
-            .get_with_query(
-                &format!(
-                    "https://gpodder.net/api/2/settings/{}/podcast.json",
-                    self.username
-                ),
-                &[&("podcast", podcast.as_str())],
-            )?
-            .json()?)
+        self.get_with_query(
+            &self.api_url(
+                ApiArea::Settings,
+                &format!("settings/{}/podcast.json", self.username),
+            ),
+            &[&("podcast", podcast.as_str())],
+        )?
+        .json()
     }
 }
 
@@ -446,33 +806,102 @@ impl GetPodcastSettings for DeviceClient {
 }
 
 impl GetEpisodeSettings for AuthenticatedClient {
-    fn get_episode_settings(
-        &self,
-        podcast: Url,
-        episode: Url,
-    ) -> Result<HashMap<String, String>, Error> {
-        Ok(self
-            .get_with_query(
-                &format!(
-                    "https://gpodder.net/api/2/settings/{}/episode.json",
-                    self.username
-                ),
-                &[
-                    &("podcast", podcast.as_str()),
-                    &("episode", episode.as_str()),
-                ],
-            )?
-            .json()?)
+    fn get_episode_settings(&self, episode: EpisodeRef) -> Result<HashMap<String, String>, Error> {
+        self.get_with_query(
+            &self.api_url(
+                ApiArea::Settings,
+                &format!("settings/{}/episode.json", self.username),
+            ),
+            &[
+                &("podcast", episode.podcast.as_str()),
+                &("episode", episode.episode.as_str()),
+            ],
+        )?
+        .json()
     }
 }
 
 impl GetEpisodeSettings for DeviceClient {
-    fn get_episode_settings(
-        &self,
-        podcast: Url,
-        episode: Url,
-    ) -> Result<HashMap<String, String>, Error> {
-        self.authenticated_client
-            .get_episode_settings(podcast, episode)
+    fn get_episode_settings(&self, episode: EpisodeRef) -> Result<HashMap<String, String>, Error> {
+        self.authenticated_client.get_episode_settings(episode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        device_settings, is_public, podcast_privacy, sync_group, PodcastPrivacy, PUBLIC_KEY,
+        PUBLIC_SUBSCRIPTIONS_KEY, SYNC_GROUP_KEY,
+    };
+    use crate::client::ApiResponse;
+    use crate::error::Error;
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+    use std::collections::HashMap;
+    use url::Url;
+
+    fn response(status: StatusCode, body: &str) -> ApiResponse {
+        ApiResponse {
+            url: Url::parse("https://gpodder.net/api/2/settings/username/device.json").unwrap(),
+            status,
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn missing_device_is_reported() {
+        match device_settings(response(StatusCode::NOT_FOUND, ""), "phone") {
+            Err(Error::DeviceNotFound(device_id)) => assert_eq!("phone", device_id),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn device_settings_are_parsed() {
+        let settings = device_settings(
+            response(StatusCode::OK, r#"{"setting1":"value1"}"#),
+            "phone",
+        )
+        .unwrap();
+
+        assert_eq!(Some(&String::from("value1")), settings.get("setting1"));
+    }
+
+    #[test]
+    fn sync_group_is_read_from_device_settings() {
+        let mut settings = HashMap::new();
+        assert_eq!(None, sync_group(settings.clone()));
+
+        settings.insert(SYNC_GROUP_KEY.to_owned(), String::new());
+        assert_eq!(None, sync_group(settings.clone()));
+
+        settings.insert(SYNC_GROUP_KEY.to_owned(), String::from("household"));
+        assert_eq!(Some(String::from("household")), sync_group(settings));
+    }
+
+    #[test]
+    fn podcast_privacy_is_read_from_podcast_settings() {
+        let mut settings = HashMap::new();
+        assert_eq!(PodcastPrivacy::Public, podcast_privacy(&settings));
+
+        settings.insert(PUBLIC_KEY.to_owned(), String::from("true"));
+        assert_eq!(PodcastPrivacy::Public, podcast_privacy(&settings));
+
+        settings.insert(PUBLIC_KEY.to_owned(), String::from("False"));
+        assert_eq!(PodcastPrivacy::Private, podcast_privacy(&settings));
+
+        settings.insert(PUBLIC_KEY.to_owned(), String::from("0"));
+        assert_eq!(PodcastPrivacy::Private, podcast_privacy(&settings));
+    }
+
+    #[test]
+    fn profile_is_public_unless_disabled() {
+        let mut settings = HashMap::new();
+        assert!(is_public(&settings, PUBLIC_SUBSCRIPTIONS_KEY));
+
+        settings.insert(PUBLIC_SUBSCRIPTIONS_KEY.to_owned(), String::from("false"));
+        assert!(!is_public(&settings, PUBLIC_SUBSCRIPTIONS_KEY));
+        assert!(is_public(&settings, PUBLIC_KEY));
     }
 }
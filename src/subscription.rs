@@ -1,67 +1,219 @@
 //! [Subscriptions API](https://gpoddernet.readthedocs.io/en/latest/api/reference/subscriptions.html)
 
+use crate::client::ApiArea;
+use crate::client::ApiResponse;
 use crate::client::AuthenticatedClient;
 use crate::client::DeviceClient;
-use crate::error::Error;
+use crate::client::PublicClient;
+use crate::client::Strictness;
+use crate::error::{Error, ValidationError};
+use crate::format::Format;
+use crate::opml;
+use crate::reference::Username;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use std::cmp::Ordering;
+#[cfg(feature = "zero-copy")]
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::fmt;
-use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 use url::Url;
 
-/// Podcast
-#[derive(Serialize, Deserialize, Debug, Clone, Eq)]
-pub struct Podcast {
+pub use crate::protocol::subscriptions::{
+    GetSubscriptionChangesResponse, Podcast, UploadSubscriptionChangesRequest,
+    UploadSubscriptionChangesResponse, UrlRewrite,
+};
+
+/// [Podcast] borrowing its string fields from the deserialized response body where possible
+///
+/// Deserialize it with `serde_json::from_slice` or `serde_json::from_str` to avoid allocations in tight loops. URLs are not parsed until [into_owned](PodcastRef::into_owned) is called.
+#[cfg(feature = "zero-copy")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PodcastRef<'a> {
     /// feed URL
-    pub url: Url,
+    #[serde(borrow)]
+    pub url: Cow<'a, str>,
     /// title of podcast
-    pub title: String,
+    #[serde(borrow)]
+    pub title: Cow<'a, str>,
     /// author of podcast
-    pub author: Option<String>,
+    #[serde(borrow)]
+    pub author: Option<Cow<'a, str>>,
     /// description of podcast
-    pub description: String,
+    #[serde(borrow)]
+    pub description: Cow<'a, str>,
     /// number of subscribers on service
-    pub subscribers: u16,
+    pub subscribers: u32,
     /// number of subscribers on service one week before
-    pub subscribers_last_week: u16,
+    pub subscribers_last_week: u32,
     /// URL to logo of podcast
-    pub logo_url: Option<Url>,
+    #[serde(borrow)]
+    pub logo_url: Option<Cow<'a, str>>,
     /// URL to a scaled logo of podcast
-    pub scaled_logo_url: Option<Url>,
+    #[serde(borrow)]
+    pub scaled_logo_url: Option<Cow<'a, str>>,
     /// website of podcast
-    pub website: Option<Url>,
+    #[serde(borrow)]
+    pub website: Option<Cow<'a, str>>,
     /// service-internal feed URL
-    pub mygpo_link: Url,
+    #[serde(borrow)]
+    pub mygpo_link: Cow<'a, str>,
 }
 
-#[derive(Serialize)]
-pub(crate) struct UploadSubscriptionChangesRequest {
-    pub(crate) add: Vec<Url>,
-    pub(crate) remove: Vec<Url>,
+/// Podcast feed URL which passes the URL checks of the server
+///
+/// Only `http` and `https` URLs with a host are accepted. When parsing a string, non-ASCII characters are rejected instead of being percent-encoded, like the server does.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::subscription::FeedUrl;
+///
+/// assert!(FeedUrl::parse("https://example.com/feed.xml").is_ok());
+/// assert!(FeedUrl::parse("ftp://example.com/feed.xml").is_err());
+/// assert!(FeedUrl::parse("https://example.com/föed.xml").is_err());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[serde(try_from = "Url", into = "Url")]
+pub struct FeedUrl(Url);
+
+/// Correction applied by [FeedUrl::parse_lenient]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum UrlFix {
+    /// surrounding whitespace was removed
+    TrimmedWhitespace,
+    /// the contained podcast scheme, e.g. `feed`, was replaced with `http`
+    ReplacedScheme(String),
+    /// `http://` was prepended to an input without scheme
+    AddedScheme,
+    /// scheme or host were lowercased
+    Lowercased,
+    /// `/` was appended to an input without path
+    AddedTrailingSlash,
 }
 
-/// Response to [upload_subscription_changes](SubscriptionChanges::upload_subscription_changes)
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
-pub struct UploadSubscriptionChangesResponse {
-    /// timestamp/ID that can be used for requesting changes since this upload in a subsequent API call
-    pub timestamp: u64,
-    /// list of URLs that have been rewritten as a list of tuples
-    ///
-    /// The client SHOULD parse this list and update the local subscription list accordingly (the server only sanitizes the URL, so the semantic “content” should stay the same and therefore the client can simply update the URL value locally and use it for future updates.
-    pub update_urls: Vec<(Url, Url)>,
+/// [FeedUrl] parsed by [FeedUrl::parse_lenient] together with the corrections applied to the input
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct LenientFeedUrl {
+    /// input as given by the user
+    pub input: String,
+    /// corrected feed URL
+    pub feed_url: FeedUrl,
+    /// corrections applied to the input, empty if it was valid already
+    pub fixes: Vec<UrlFix>,
 }
 
-/// Response to [get_subscription_changes](SubscriptionChanges::get_subscription_changes)
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
-pub struct GetSubscriptionChangesResponse {
-    /// The timestamp SHOULD be stored by the client in order to provide it in the since parameter in the next request.
-    pub timestamp: u64,
-    /// URLs that should be added
+/// Subscriptions to add and to remove to get from one subscription list to another
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SubscriptionDelta {
+    /// URLs missing in the current list, in the order of the target list
     pub add: Vec<Url>,
-    /// URLs that should be removed
+    /// URLs missing in the target list, in the order of the current list
     pub remove: Vec<Url>,
 }
 
+/// Local subscription list of a device together with the timestamp of its last synchronization, see [pull_and_apply](DeviceClient::pull_and_apply)
+///
+/// Serializable, so applications can persist it between sessions.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LocalSubscriptions {
+    /// feed URLs of the subscribed podcasts
+    pub subscriptions: Vec<Url>,
+    /// timestamp returned by the last [get_subscription_changes](SubscriptionChanges::get_subscription_changes)
+    pub since: u64,
+}
+
+/// Result of [set_subscriptions](DeviceClient::set_subscriptions)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionUpdate {
+    /// changes between the previous and the new subscriptions of the device
+    pub delta: SubscriptionDelta,
+    /// how the changes were uploaded
+    pub upload: SubscriptionUpload,
+}
+
+/// Upload performed by [set_subscriptions](DeviceClient::set_subscriptions)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionUpload {
+    /// subscriptions already matched, nothing was uploaded
+    Unchanged,
+    /// only the [delta](SubscriptionUpdate::delta) was uploaded with [upload_subscription_changes](SubscriptionChanges::upload_subscription_changes)
+    Changes(UploadSubscriptionChangesResponse),
+    /// the whole list was uploaded with [upload_subscriptions_of_device](SubscriptionsOfDevice::upload_subscriptions_of_device)
+    Full,
+}
+
+/// Outcome of [upload_subscription_changes_checked](SubscriptionChanges::upload_subscription_changes_checked) for each uploaded URL
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriptionUploadOutcome {
+    /// timestamp/ID that can be used for requesting changes since this upload in a subsequent API call
+    pub timestamp: u64,
+    /// URLs stored as uploaded
+    pub accepted: Vec<Url>,
+    /// uploaded URLs and the sanitized URLs the server stored instead
+    pub rewritten: Vec<(Url, Url)>,
+    /// URLs ignored by the server, e.g. because they contain non-ASCII characters
+    pub rejected: Vec<Url>,
+}
+
+impl SubscriptionUploadOutcome {
+    fn new(
+        add: &[Url],
+        remove: &[Url],
+        response: UploadSubscriptionChangesResponse,
+    ) -> SubscriptionUploadOutcome {
+        let mut outcome = SubscriptionUploadOutcome {
+            timestamp: response.timestamp,
+            ..Default::default()
+        };
+        let mut seen = HashSet::new();
+        for url in add.iter().chain(remove).filter(|url| seen.insert(*url)) {
+            let rewrite = response
+                .update_urls
+                .iter()
+                .find(|rewrite| &rewrite.original == url);
+            match rewrite.map(|rewrite| &rewrite.sanitized) {
+                Some(Some(sanitized)) if sanitized != url => {
+                    outcome.rewritten.push((url.clone(), sanitized.clone()))
+                }
+                Some(None) => outcome.rejected.push(url.clone()),
+                _ => outcome.accepted.push(url.clone()),
+            }
+        }
+        outcome
+    }
+
+    /// Returns `true` if the server rejected none of the URLs
+    pub fn is_fully_accepted(&self) -> bool {
+        self.rejected.is_empty()
+    }
+}
+
+/// Format of a subscription list, see [get_all_subscriptions_in_format](GetAllSubscriptions::get_all_subscriptions_in_format)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum SubscriptionFormat {
+    /// JSON, returned as typed data
+    Json,
+    /// OPML, returned as feed URLs
+    Opml,
+    /// plain text with one URL per line, returned as is
+    Txt,
+    /// XML, returned as is
+    Xml,
+}
+
+/// Subscription list in a [SubscriptionFormat]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionList {
+    /// podcasts with metadata
+    Podcasts(Vec<Podcast>),
+    /// feed URLs
+    Urls(Vec<Url>),
+    /// raw response body
+    Raw(String),
+}
+
 /// see [get_all_subscriptions](GetAllSubscriptions::get_all_subscriptions)
 pub trait GetAllSubscriptions {
     /// Get All Subscriptions
@@ -88,6 +240,34 @@ pub trait GetAllSubscriptions {
     ///
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/subscriptions.html#get-all-subscriptions)
     fn get_all_subscriptions(&self) -> Result<Vec<Podcast>, Error>;
+
+    /// Get All Subscriptions in the given format
+    ///
+    /// [Json](SubscriptionFormat::Json) returns [Podcasts](SubscriptionList::Podcasts), [Opml](SubscriptionFormat::Opml) returns [Urls](SubscriptionList::Urls) and the other formats return the [Raw](SubscriptionList::Raw) response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::subscription::{GetAllSubscriptions, SubscriptionFormat};
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    ///
+    /// let subscriptions = client.get_all_subscriptions_in_format(SubscriptionFormat::Txt)?;
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    ///
+    /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/subscriptions.html#get-all-subscriptions)
+    fn get_all_subscriptions_in_format(
+        &self,
+        format: SubscriptionFormat,
+    ) -> Result<SubscriptionList, Error>;
 }
 
 /// Get and upload subscriptions of a device
@@ -100,9 +280,10 @@ pub trait SubscriptionsOfDevice {
     /// use mygpoclient::client::DeviceClient;
     /// use mygpoclient::subscription::SubscriptionsOfDevice;
     ///
-    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
     /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
-    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
     /// #
     /// let client = DeviceClient::new(&username, &password, &deviceid);
     ///
@@ -115,11 +296,101 @@ pub trait SubscriptionsOfDevice {
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/subscriptions.html#get-subscriptions-of-device)
     fn get_subscriptions_of_device(&self) -> Result<Vec<Url>, Error>;
 
+    /// Get Subscriptions of Device in the given format
+    ///
+    /// [Json](SubscriptionFormat::Json) and [Opml](SubscriptionFormat::Opml) return [Urls](SubscriptionList::Urls), the other formats return the [Raw](SubscriptionList::Raw) response.
+    ///
+    /// # See also
+    /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/subscriptions.html#get-subscriptions-of-device)
+    fn get_subscriptions_of_device_in_format(
+        &self,
+        format: SubscriptionFormat,
+    ) -> Result<SubscriptionList, Error>;
+
     /// Upload the current subscription list of the given user to the server.
     ///
+    /// Duplicate URLs are only uploaded once. With [Strict](Strictness::Strict) validation, an [InvalidFeedUrl](ValidationError::InvalidFeedUrl) error is returned without contacting the server if a URL isn't a valid [FeedUrl].
+    ///
     /// # See also
     /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/subscriptions.html#upload-subscriptions-of-device)
     fn upload_subscriptions_of_device(&self, subscriptions: &[Url]) -> Result<(), Error>;
+
+    /// Upload the subscription list only if it hasn't changed on the server since `timestamp`
+    ///
+    /// `timestamp` is the timestamp of the caller's snapshot of the subscription list, e.g. from [get_subscription_changes](SubscriptionChanges::get_subscription_changes). If another client changed the list since then, [ConcurrentModification](Error::ConcurrentModification) is returned with these changes and nothing is uploaded. The caller can merge the changes and retry.
+    ///
+    /// The check and the upload are separate requests, so a change in between is still overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::DeviceClient;
+    /// use mygpoclient::error::Error;
+    /// use mygpoclient::subscription::{SubscriptionChanges, SubscriptionsOfDevice};
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// let snapshot = client.get_subscription_changes(0)?;
+    /// match client.upload_subscriptions_of_device_if_unmodified(&snapshot.add, snapshot.timestamp) {
+    ///     Err(Error::ConcurrentModification(changes)) => println!("merge {} first", changes),
+    ///     result => result?,
+    /// }
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    ///
+    /// # See also
+    /// - [gpodder.net API Documentation](https://gpoddernet.readthedocs.io/en/latest/api/reference/subscriptions.html#upload-subscriptions-of-device)
+    fn upload_subscriptions_of_device_if_unmodified(
+        &self,
+        subscriptions: &[Url],
+        timestamp: u64,
+    ) -> Result<(), Error>;
+}
+
+/// see [get_public_subscriptions](GetPublicSubscriptions::get_public_subscriptions)
+pub trait GetPublicSubscriptions {
+    /// Get the subscriptions another user has made public
+    ///
+    /// No credentials are sent, so the result is the same for every client. Podcasts which the user has marked as private in their settings are not included.
+    ///
+    /// Returns [SubscriptionsNotPublic](Error::SubscriptionsNotPublic) if the user doesn't share their subscriptions or doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::PublicClient;
+    /// use mygpoclient::error::Error;
+    /// use mygpoclient::reference::Username;
+    /// use mygpoclient::subscription::GetPublicSubscriptions;
+    ///
+    /// match PublicClient::default().get_public_subscriptions(&Username::from("stefan")) {
+    ///     Ok(subscriptions) => println!("{} subscriptions", subscriptions.len()),
+    ///     Err(Error::SubscriptionsNotPublic(username)) => println!("{} doesn't share", username),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn get_public_subscriptions(&self, username: &Username) -> Result<Vec<Url>, Error>;
+}
+
+/// Links to the public subscriptions of a user, as returned by [public_subscription_urls](AuthenticatedClient::public_subscription_urls)
+///
+/// The links only work for other users if the profile of the user is public, which can be changed in the account settings.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PublicSubscriptionUrls {
+    /// profile page of the user on the website
+    pub profile: Url,
+    /// subscriptions on the website
+    pub web: Url,
+    /// subscriptions as OPML document, e.g. for importing them into other podcast clients, see [get_public_subscriptions](GetPublicSubscriptions::get_public_subscriptions)
+    pub opml: Url,
 }
 
 /// Get or upload subscription changes
@@ -128,6 +399,8 @@ pub trait SubscriptionChanges {
     ///
     /// Only deltas are supported here. Timestamps are not supported, and are issued by the server.
     ///
+    /// Duplicate URLs are only uploaded once. If a URL is contained in both `add` and `remove`, a [ConflictingChange](ValidationError::ConflictingChange) error is returned without contacting the server. The same applies to URLs which aren't a valid [FeedUrl] with [Strict](Strictness::Strict) validation.
+    ///
     /// # Examples
     ///
     /// ```
@@ -135,9 +408,10 @@ pub trait SubscriptionChanges {
     /// use mygpoclient::subscription::SubscriptionChanges;
     /// use url::Url;
     ///
-    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
     /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
-    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
     /// #
     /// let client = DeviceClient::new(&username, &password, &deviceid);
     ///
@@ -161,6 +435,42 @@ pub trait SubscriptionChanges {
         remove: &[Url],
     ) -> Result<UploadSubscriptionChangesResponse, Error>;
 
+    /// Upload Subscription Changes and sort the uploaded URLs by whether the server accepted, rewrote or rejected them
+    ///
+    /// Behaves like [upload_subscription_changes](SubscriptionChanges::upload_subscription_changes), but saves the inspection of [update_urls](UploadSubscriptionChangesResponse::update_urls).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::DeviceClient;
+    /// use mygpoclient::subscription::SubscriptionChanges;
+    /// use url::Url;
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// # let url = Url::parse("http://example.com/feed.rss").unwrap();
+    /// #
+    /// let outcome = client.upload_subscription_changes_checked(&[url], &[])?;
+    /// for url in &outcome.rejected {
+    ///     eprintln!("rejected by server: {}", url);
+    /// }
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn upload_subscription_changes_checked(
+        &self,
+        add: &[Url],
+        remove: &[Url],
+    ) -> Result<SubscriptionUploadOutcome, Error> {
+        let response = self.upload_subscription_changes(add, remove)?;
+        Ok(SubscriptionUploadOutcome::new(add, remove, response))
+    }
+
     /// Get Subscription Changes
     ///
     /// # Examples
@@ -169,9 +479,10 @@ pub trait SubscriptionChanges {
     /// use mygpoclient::client::DeviceClient;
     /// use mygpoclient::subscription::SubscriptionChanges;
     ///
-    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
     /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
-    /// # let deviceid = std::env::var("GPODDER_NET_DEVICEID").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
     /// #
     /// let client = DeviceClient::new(&username, &password, &deviceid);
     ///
@@ -191,12 +502,28 @@ pub trait SubscriptionChanges {
 
 impl GetAllSubscriptions for AuthenticatedClient {
     fn get_all_subscriptions(&self) -> Result<Vec<Podcast>, Error> {
-        Ok(self
-            .get(&format!(
-                "https://gpodder.net/subscriptions/{}.json",
-                self.username
-            ))?
-            .json()?)
+        self.get(&self.url(
+            ApiArea::Subscriptions,
+            &format!("subscriptions/{}.json", self.username),
+        ))?
+        .json()
+    }
+
+    fn get_all_subscriptions_in_format(
+        &self,
+        format: SubscriptionFormat,
+    ) -> Result<SubscriptionList, Error> {
+        let response = self.get(&self.url(
+            ApiArea::Subscriptions,
+            &format!("subscriptions/{}.{}", self.username, format),
+        ))?;
+        Ok(match format {
+            SubscriptionFormat::Json => SubscriptionList::Podcasts(response.json()?),
+            SubscriptionFormat::Opml => SubscriptionList::Urls(opml::feed_urls(&response.text()?)?),
+            SubscriptionFormat::Txt | SubscriptionFormat::Xml => {
+                SubscriptionList::Raw(response.text()?)
+            }
+        })
     }
 }
 
@@ -204,28 +531,318 @@ impl GetAllSubscriptions for DeviceClient {
     fn get_all_subscriptions(&self) -> Result<Vec<Podcast>, Error> {
         self.as_ref().get_all_subscriptions()
     }
+
+    fn get_all_subscriptions_in_format(
+        &self,
+        format: SubscriptionFormat,
+    ) -> Result<SubscriptionList, Error> {
+        self.as_ref().get_all_subscriptions_in_format(format)
+    }
 }
 
 impl SubscriptionsOfDevice for DeviceClient {
     fn get_subscriptions_of_device(&self) -> Result<Vec<Url>, Error> {
-        Ok(self
-            .get(&format!(
-                "https://gpodder.net/subscriptions/{}/{}.json",
+        self.get(&self.url(
+            ApiArea::Subscriptions,
+            &format!(
+                "subscriptions/{}/{}.json",
                 self.authenticated_client.username, self.device_id
-            ))?
-            .json()?) // TODO handle response?
+            ),
+        ))?
+        .json() // TODO handle response?
+    }
+
+    fn get_subscriptions_of_device_in_format(
+        &self,
+        format: SubscriptionFormat,
+    ) -> Result<SubscriptionList, Error> {
+        let response = self.get(&self.url(
+            ApiArea::Subscriptions,
+            &format!(
+                "subscriptions/{}/{}.{}",
+                self.authenticated_client.username, self.device_id, format
+            ),
+        ))?;
+        Ok(match format {
+            SubscriptionFormat::Json => SubscriptionList::Urls(response.json()?),
+            SubscriptionFormat::Opml => SubscriptionList::Urls(opml::feed_urls(&response.text()?)?),
+            SubscriptionFormat::Txt | SubscriptionFormat::Xml => {
+                SubscriptionList::Raw(response.text()?)
+            }
+        })
     }
 
     fn upload_subscriptions_of_device(&self, subscriptions: &[Url]) -> Result<(), Error> {
+        validate_feed_urls(subscriptions, self.authenticated_client.strictness)?;
         self.put(
-            &format!(
-                "https://gpodder.net/subscriptions/{}/{}.json",
-                self.authenticated_client.username, self.device_id
+            &self.url(
+                ApiArea::Subscriptions,
+                &format!(
+                    "subscriptions/{}/{}.json",
+                    self.authenticated_client.username, self.device_id
+                ),
             ),
-            subscriptions,
+            &deduplicate(subscriptions),
         )?; // TODO handle response?
         Ok(())
     }
+
+    fn upload_subscriptions_of_device_if_unmodified(
+        &self,
+        subscriptions: &[Url],
+        timestamp: u64,
+    ) -> Result<(), Error> {
+        let changes = self.get_subscription_changes(timestamp)?;
+        if !changes.add.is_empty() || !changes.remove.is_empty() {
+            return Err(Error::ConcurrentModification(changes));
+        }
+        self.upload_subscriptions_of_device(subscriptions)
+    }
+}
+
+impl DeviceClient {
+    /// Make `target` the subscriptions of the device, uploading as little as possible
+    ///
+    /// Fetches the current subscriptions and uploads only the [SubscriptionDelta] if it has at most as many URLs as `target`, otherwise the whole list. Nothing is uploaded if the subscriptions already match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::DeviceClient;
+    /// use url::Url;
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// # let url = Url::parse("http://example.com/feed.rss").unwrap();
+    /// #
+    /// let update = client.set_subscriptions(&[url])?;
+    /// println!("added {:?}, removed {:?}", update.delta.add, update.delta.remove);
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn set_subscriptions(&self, target: &[Url]) -> Result<SubscriptionUpdate, Error> {
+        set_subscriptions(self, target)
+    }
+
+    /// Fetch the subscription changes since the last call and merge them into `local`
+    ///
+    /// `local` is only modified if the request succeeds. Returns the applied [SubscriptionDelta].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::DeviceClient;
+    /// use mygpoclient::subscription::LocalSubscriptions;
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// let mut local = LocalSubscriptions::default();
+    /// let delta = client.pull_and_apply(&mut local)?;
+    /// println!("{} subscriptions, {} changed", local.subscriptions.len(), delta.len());
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn pull_and_apply(
+        &self,
+        local: &mut LocalSubscriptions,
+    ) -> Result<SubscriptionDelta, Error> {
+        pull_and_apply(self, local)
+    }
+}
+
+fn pull_and_apply<C: SubscriptionChanges>(
+    client: &C,
+    local: &mut LocalSubscriptions,
+) -> Result<SubscriptionDelta, Error> {
+    let changes = client.get_subscription_changes(local.since)?;
+    local.since = changes.timestamp;
+    let delta = SubscriptionDelta::from(changes);
+    delta.apply_to(&mut local.subscriptions);
+    Ok(delta)
+}
+
+fn set_subscriptions<C: SubscriptionsOfDevice + SubscriptionChanges>(
+    client: &C,
+    target: &[Url],
+) -> Result<SubscriptionUpdate, Error> {
+    let target = deduplicate(target);
+    let delta = SubscriptionDelta::between(&client.get_subscriptions_of_device()?, &target);
+
+    let upload = if delta.is_empty() {
+        SubscriptionUpload::Unchanged
+    } else if delta.len() <= target.len() {
+        SubscriptionUpload::Changes(client.upload_subscription_changes(&delta.add, &delta.remove)?)
+    } else {
+        client.upload_subscriptions_of_device(&target)?;
+        SubscriptionUpload::Full
+    };
+
+    Ok(SubscriptionUpdate { delta, upload })
+}
+
+impl SubscriptionDelta {
+    /// Compute the changes turning `current` into `target`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::subscription::SubscriptionDelta;
+    /// use url::Url;
+    ///
+    /// let a = Url::parse("http://example.com/a.rss").unwrap();
+    /// let b = Url::parse("http://example.com/b.rss").unwrap();
+    ///
+    /// let delta = SubscriptionDelta::between(&[a.clone()], &[b.clone()]);
+    /// assert_eq!(vec![b], delta.add);
+    /// assert_eq!(vec![a], delta.remove);
+    /// ```
+    pub fn between(current: &[Url], target: &[Url]) -> SubscriptionDelta {
+        let current_set: HashSet<&Url> = current.iter().collect();
+        let target_set: HashSet<&Url> = target.iter().collect();
+        SubscriptionDelta {
+            add: deduplicate(target)
+                .into_iter()
+                .filter(|url| !current_set.contains(url))
+                .collect(),
+            remove: deduplicate(current)
+                .into_iter()
+                .filter(|url| !target_set.contains(url))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of URLs to add and to remove
+    pub fn len(&self) -> usize {
+        self.add.len() + self.remove.len()
+    }
+
+    /// Returns `true` if there is nothing to add or remove
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.remove.is_empty()
+    }
+
+    /// Remove the URLs of [remove](SubscriptionDelta::remove) from `subscriptions` and append those of [add](SubscriptionDelta::add) which are missing
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::subscription::SubscriptionDelta;
+    /// use url::Url;
+    ///
+    /// let a = Url::parse("http://example.com/a.rss").unwrap();
+    /// let b = Url::parse("http://example.com/b.rss").unwrap();
+    /// let mut subscriptions = vec![a.clone()];
+    ///
+    /// SubscriptionDelta::between(&[a.clone()], &[b.clone()]).apply_to(&mut subscriptions);
+    /// assert_eq!(vec![b], subscriptions);
+    /// ```
+    pub fn apply_to(&self, subscriptions: &mut Vec<Url>) {
+        let remove: HashSet<&Url> = self.remove.iter().collect();
+        subscriptions.retain(|url| !remove.contains(url));
+        for url in &self.add {
+            if !subscriptions.contains(url) {
+                subscriptions.push(url.clone());
+            }
+        }
+    }
+}
+
+impl From<GetSubscriptionChangesResponse> for SubscriptionDelta {
+    fn from(changes: GetSubscriptionChangesResponse) -> Self {
+        SubscriptionDelta {
+            add: changes.add,
+            remove: changes.remove,
+        }
+    }
+}
+
+impl GetPublicSubscriptions for PublicClient {
+    fn get_public_subscriptions(&self, username: &Username) -> Result<Vec<Url>, Error> {
+        let response = self.get(&self.url(
+            ApiArea::Subscriptions,
+            &format!("user/{}/subscriptions.opml", username),
+        ))?;
+        public_subscriptions(response, username.as_str())
+    }
+}
+
+impl GetPublicSubscriptions for AuthenticatedClient {
+    fn get_public_subscriptions(&self, username: &Username) -> Result<Vec<Url>, Error> {
+        self.public_client.get_public_subscriptions(username)
+    }
+}
+
+impl GetPublicSubscriptions for DeviceClient {
+    fn get_public_subscriptions(&self, username: &Username) -> Result<Vec<Url>, Error> {
+        self.authenticated_client.get_public_subscriptions(username)
+    }
+}
+
+impl AuthenticatedClient {
+    /// Returns the links for sharing the subscriptions of this user, constructed from the username and the [base URL](AuthenticatedClient::base_url) without contacting the server
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    ///
+    /// let client = AuthenticatedClient::new("username", "password");
+    /// let urls = client.public_subscription_urls()?;
+    ///
+    /// assert_eq!("https://gpodder.net/user/username/", urls.profile.as_str());
+    /// assert_eq!("https://gpodder.net/user/username/subscriptions.opml", urls.opml.as_str());
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn public_subscription_urls(&self) -> Result<PublicSubscriptionUrls, Error> {
+        let website = format!("{}/user", self.base_url());
+        let username: &str = &self.username;
+        Ok(PublicSubscriptionUrls {
+            profile: with_path_segments(&website, &[username, ""])?,
+            web: with_path_segments(&website, &[username, "subscriptions", ""])?,
+            opml: with_path_segments(
+                &self.url(ApiArea::Subscriptions, "user"),
+                &[username, "subscriptions.opml"],
+            )?,
+        })
+    }
+}
+
+impl DeviceClient {
+    /// Returns the links for sharing the subscriptions of this user, see [AuthenticatedClient::public_subscription_urls]
+    pub fn public_subscription_urls(&self) -> Result<PublicSubscriptionUrls, Error> {
+        self.authenticated_client.public_subscription_urls()
+    }
+}
+
+/// Appends percent-encoded `segments` to the path of `base`
+pub(crate) fn with_path_segments(base: &str, segments: &[&str]) -> Result<Url, Error> {
+    let mut url = Url::parse(base)?;
+    // only URLs which cannot be a base have no path segments
+    if let Ok(mut path_segments) = url.path_segments_mut() {
+        path_segments.extend(segments);
+    }
+    Ok(url)
+}
+
+fn public_subscriptions(response: ApiResponse, username: &str) -> Result<Vec<Url>, Error> {
+    if response.status == StatusCode::UNAUTHORIZED
+        || response.status == StatusCode::FORBIDDEN
+        || response.status == StatusCode::NOT_FOUND
+    {
+        return Err(Error::SubscriptionsNotPublic(username.to_owned()));
+    }
+    opml::feed_urls(&response.text()?)
 }
 
 impl SubscriptionChanges for DeviceClient {
@@ -234,61 +851,425 @@ impl SubscriptionChanges for DeviceClient {
         add: &[Url],
         remove: &[Url],
     ) -> Result<UploadSubscriptionChangesResponse, Error> {
-        let input = UploadSubscriptionChangesRequest {
-            add: add.to_owned(),
-            remove: remove.to_owned(),
-        };
-        Ok(self
-            .post(
+        let input = UploadSubscriptionChangesRequest::new(
+            add,
+            remove,
+            self.authenticated_client.strictness,
+        )?;
+        self.post(
+            &self.api_url(
+                ApiArea::Subscriptions,
                 &format!(
-                    "https://gpodder.net/api/2/subscriptions/{}/{}.json",
+                    "subscriptions/{}/{}.json",
                     self.authenticated_client.username, self.device_id
                 ),
-                &input,
-            )?
-            .json()?)
+            ),
+            &input,
+        )?
+        .json()
     }
 
     fn get_subscription_changes(
         &self,
         timestamp: u64,
     ) -> Result<GetSubscriptionChangesResponse, Error> {
-        Ok(self
-            .get_with_query(
+        self.get_with_query(
+            &self.api_url(
+                ApiArea::Subscriptions,
                 &format!(
-                    "https://gpodder.net/api/2/subscriptions/{}/{}.json",
+                    "subscriptions/{}/{}.json",
                     self.authenticated_client.username, self.device_id
                 ),
-                &[&("since", timestamp)],
-            )?
-            .json()?)
+            ),
+            &[&("since", timestamp)],
+        )?
+        .json()
     }
 }
 
-impl PartialEq for Podcast {
-    fn eq(&self, other: &Self) -> bool {
-        self.url == other.url
+impl Podcast {
+    /// Subscribe the device of `client` to this podcast, e.g. to a result of a [directory](crate::directory) search
+    ///
+    /// The feed URL is validated as [FeedUrl] and [tracking parameters](TRACKING_QUERY_PARAMETERS) are removed before uploading it with [upload_subscription_changes_checked](SubscriptionChanges::upload_subscription_changes_checked). Returns the URL stored by the server, i.e. the sanitized URL if the server rewrote it. Store this URL locally, as later [subscription changes](SubscriptionChanges::get_subscription_changes) refer to it.
+    ///
+    /// Returns [InvalidFeedUrl](ValidationError::InvalidFeedUrl) if the URL is invalid or the server rejected it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::DeviceClient;
+    /// use mygpoclient::directory::PodcastSearch;
+    ///
+    /// # use mygpoclient::reference::{DeviceId, Username};
+    /// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+    /// #
+    /// let client = DeviceClient::new(&username, &password, &deviceid);
+    ///
+    /// if let Some(podcast) = client.podcast_search("linux", None)?.first() {
+    ///     let stored_url = podcast.subscribe_with(&client)?;
+    ///     println!("subscribed to {}", stored_url);
+    /// }
+    /// #
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    pub fn subscribe_with<C: SubscriptionChanges>(&self, client: &C) -> Result<Url, Error> {
+        let mut url = FeedUrl::try_from(self.url.clone())?.into_url();
+        strip_tracking_parameters(&mut url);
+
+        let outcome = client.upload_subscription_changes_checked(&[url.clone()], &[])?;
+        if let Some((_, sanitized)) = outcome.rewritten.into_iter().next() {
+            return Ok(sanitized);
+        }
+        if !outcome.rejected.is_empty() {
+            return Err(ValidationError::InvalidFeedUrl(url.into()).into());
+        }
+        Ok(url)
+    }
+}
+
+#[cfg(feature = "zero-copy")]
+impl PodcastRef<'_> {
+    /// Convert into [Podcast], parsing all URLs
+    pub fn into_owned(self) -> Result<Podcast, url::ParseError> {
+        let parse_optional_url =
+            |url: Option<Cow<'_, str>>| url.map(|u| Url::parse(&u)).transpose();
+
+        Ok(Podcast {
+            url: Url::parse(&self.url)?,
+            title: self.title.into_owned(),
+            author: self.author.map(Cow::into_owned),
+            description: self.description.into_owned(),
+            subscribers: self.subscribers,
+            subscribers_last_week: self.subscribers_last_week,
+            logo_url: parse_optional_url(self.logo_url)?,
+            scaled_logo_url: parse_optional_url(self.scaled_logo_url)?,
+            website: parse_optional_url(self.website)?,
+            mygpo_link: Url::parse(&self.mygpo_link)?,
+        })
+    }
+}
+
+impl UploadSubscriptionChangesRequest {
+    pub(crate) fn new(
+        add: &[Url],
+        remove: &[Url],
+        strictness: Strictness,
+    ) -> Result<Self, ValidationError> {
+        validate_feed_urls(add, strictness)?;
+        validate_feed_urls(remove, strictness)?;
+
+        let add = deduplicate(add);
+        let remove = deduplicate(remove);
+
+        if let Some(url) = add.iter().find(|url| remove.contains(url)) {
+            return Err(ValidationError::ConflictingChange(url.clone()));
+        }
+
+        Ok(UploadSubscriptionChangesRequest { add, remove })
+    }
+}
+
+const PODCAST_SCHEMES: &[&str] = &["feed", "itpc", "pcast", "podcast"];
+
+/// Query parameters which only track where a link was shared and never select a different feed
+///
+/// Besides these, all parameters starting with `utm_` are considered tracking parameters.
+pub const TRACKING_QUERY_PARAMETERS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid"];
+
+/// Removes near-duplicates from a list of feed URLs
+///
+/// Hosts are lowercased and default ports are stripped, because [Url] already stores them that way. URLs which only differ in `http` and `https` are merged, keeping `https`. The order of first occurrences is preserved.
+///
+/// Use [normalize_without_tracking] to remove [tracking parameters](TRACKING_QUERY_PARAMETERS) as well.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::subscription::normalize;
+/// use url::Url;
+///
+/// let urls = vec![
+///     Url::parse("http://Example.com:80/feed.xml")?,
+///     Url::parse("https://example.com/feed.xml")?,
+/// ];
+///
+/// assert_eq!(vec![Url::parse("https://example.com/feed.xml")?], normalize(&urls));
+/// # Ok::<(), url::ParseError>(())
+/// ```
+pub fn normalize(list: &[Url]) -> Vec<Url> {
+    normalize_urls(list, false)
+}
+
+/// Like [normalize], but also removes [tracking parameters](TRACKING_QUERY_PARAMETERS) from the query
+pub fn normalize_without_tracking(list: &[Url]) -> Vec<Url> {
+    normalize_urls(list, true)
+}
+
+fn normalize_urls(list: &[Url], strip_tracking: bool) -> Vec<Url> {
+    let mut normalized: Vec<Url> = Vec::with_capacity(list.len());
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for url in list {
+        let mut url = url.clone();
+        if strip_tracking {
+            strip_tracking_parameters(&mut url);
+        }
+
+        match positions.get(&scheme_independent_key(&url)) {
+            Some(&position) => {
+                if normalized[position].scheme() == "http" && url.scheme() == "https" {
+                    normalized[position] = url;
+                }
+            }
+            None => {
+                positions.insert(scheme_independent_key(&url), normalized.len());
+                normalized.push(url);
+            }
+        }
+    }
+
+    normalized
+}
+
+fn scheme_independent_key(url: &Url) -> String {
+    match url.scheme() {
+        "http" | "https" => url[url::Position::AfterScheme..].to_owned(),
+        _ => url.as_str().to_owned(),
+    }
+}
+
+fn is_tracking_parameter(name: &str) -> bool {
+    name.starts_with("utm_") || TRACKING_QUERY_PARAMETERS.contains(&name)
+}
+
+fn strip_tracking_parameters(url: &mut Url) {
+    if !url
+        .query_pairs()
+        .any(|(name, _)| is_tracking_parameter(&name))
+    {
+        return;
+    }
+
+    let remaining: Vec<(String, String)> = url
+        .query_pairs()
+        .into_owned()
+        .filter(|(name, _)| !is_tracking_parameter(name))
+        .collect();
+
+    if remaining.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(remaining);
+    }
+}
+
+impl FeedUrl {
+    /// Parse and validate a feed URL
+    pub fn parse(input: &str) -> Result<FeedUrl, ValidationError> {
+        let invalid = || ValidationError::InvalidFeedUrl(input.to_owned());
+
+        if !input.is_ascii() {
+            return Err(invalid());
+        }
+
+        FeedUrl::try_from(Url::parse(input).map_err(|_| invalid())?)
+    }
+
+    /// Parse a feed URL as pasted by a user, correcting common mistakes
+    ///
+    /// Surrounding whitespace is removed, podcast schemes like `feed://`, `feed:https://`, `itpc://` and `pcast://` are replaced with `http://` or the embedded scheme, and `http://` is prepended if the scheme is missing. The returned [LenientFeedUrl] reports each correction, so applications can show what was changed. Inputs which are still invalid afterwards are rejected like by [parse](FeedUrl::parse).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::subscription::{FeedUrl, UrlFix};
+    ///
+    /// let lenient = FeedUrl::parse_lenient(" feed://Example.COM")?;
+    /// assert_eq!("http://example.com/", lenient.feed_url.as_url().as_str());
+    /// assert_eq!(
+    ///     vec![
+    ///         UrlFix::TrimmedWhitespace,
+    ///         UrlFix::ReplacedScheme(String::from("feed")),
+    ///         UrlFix::Lowercased,
+    ///         UrlFix::AddedTrailingSlash,
+    ///     ],
+    ///     lenient.fixes
+    /// );
+    /// # Ok::<(), mygpoclient::error::ValidationError>(())
+    /// ```
+    pub fn parse_lenient(input: &str) -> Result<LenientFeedUrl, ValidationError> {
+        let mut fixes = Vec::new();
+
+        let trimmed = input.trim();
+        if trimmed.len() != input.len() {
+            fixes.push(UrlFix::TrimmedWhitespace);
+        }
+
+        let candidate = match trimmed.split_once(':') {
+            Some((scheme, rest))
+                if PODCAST_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()) =>
+            {
+                fixes.push(UrlFix::ReplacedScheme(scheme.to_ascii_lowercase()));
+                let embedded = rest.get(..6).map(str::to_ascii_lowercase);
+                match embedded.as_deref() {
+                    Some("http:/") | Some("https:") => rest.to_owned(),
+                    _ => format!("http:{}", rest),
+                }
+            }
+            _ if !has_scheme(trimmed) => {
+                fixes.push(UrlFix::AddedScheme);
+                format!("http://{}", trimmed.trim_start_matches('/'))
+            }
+            _ => trimmed.to_owned(),
+        };
+
+        if let Some((scheme, rest)) = candidate.split_once("://") {
+            let authority = rest.split(&['/', '?', '#'][..]).next().unwrap_or_default();
+            if scheme
+                .chars()
+                .chain(authority.chars())
+                .any(|c| c.is_ascii_uppercase())
+            {
+                fixes.push(UrlFix::Lowercased);
+            }
+            if authority.len() == rest.len() || !rest[authority.len()..].starts_with('/') {
+                fixes.push(UrlFix::AddedTrailingSlash);
+            }
+        }
+
+        let feed_url = FeedUrl::parse(&candidate)
+            .map_err(|_| ValidationError::InvalidFeedUrl(input.to_owned()))?;
+        Ok(LenientFeedUrl {
+            input: input.to_owned(),
+            feed_url,
+            fixes,
+        })
+    }
+
+    /// Returns the validated URL
+    pub fn as_url(&self) -> &Url {
+        &self.0
+    }
+
+    /// Returns the validated URL
+    pub fn into_url(self) -> Url {
+        self.0
+    }
+}
+
+impl TryFrom<Url> for FeedUrl {
+    type Error = ValidationError;
+
+    fn try_from(url: Url) -> Result<Self, Self::Error> {
+        match url.scheme() {
+            "http" | "https" if url.has_host() => Ok(FeedUrl(url)),
+            _ => Err(ValidationError::InvalidFeedUrl(url.as_str().to_owned())),
+        }
+    }
+}
+
+impl FromStr for FeedUrl {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FeedUrl::parse(s)
+    }
+}
+
+impl From<FeedUrl> for Url {
+    fn from(feed_url: FeedUrl) -> Self {
+        feed_url.0
+    }
+}
+
+impl AsRef<Url> for FeedUrl {
+    fn as_ref(&self) -> &Url {
+        &self.0
     }
 }
 
-impl Ord for Podcast {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.url.cmp(&other.url)
+impl fmt::Display for FeedUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+fn has_scheme(input: &str) -> bool {
+    match input.split_once(':') {
+        // a colon followed by digits separates host and port instead
+        Some((scheme, rest)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+                && !rest.starts_with(|c: char| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// Parse feed URLs with [FeedUrl::parse_lenient], e.g. before [uploading](SubscriptionChanges::upload_subscription_changes) URLs entered by users
+///
+/// Fails on the first input which can't be corrected.
+pub fn parse_feed_urls_lenient<S: AsRef<str>>(
+    inputs: &[S],
+) -> Result<Vec<LenientFeedUrl>, ValidationError> {
+    inputs
+        .iter()
+        .map(|input| FeedUrl::parse_lenient(input.as_ref()))
+        .collect()
+}
+
+impl LenientFeedUrl {
+    /// Returns `true` if the input had to be corrected
+    pub fn is_corrected(&self) -> bool {
+        !self.fixes.is_empty()
+    }
+}
+
+fn validate_feed_urls(urls: &[Url], strictness: Strictness) -> Result<(), ValidationError> {
+    if strictness == Strictness::Strict {
+        for url in urls {
+            FeedUrl::try_from(url.clone())?;
+        }
     }
+    Ok(())
 }
 
-impl PartialOrd for Podcast {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl SubscriptionFormat {
+    /// File extension of the endpoint URL, without leading dot
+    pub fn extension(self) -> &'static str {
+        Format::from(self).extension()
     }
 }
 
-impl Hash for Podcast {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.url.hash(state);
+impl From<SubscriptionFormat> for Format {
+    fn from(format: SubscriptionFormat) -> Self {
+        match format {
+            SubscriptionFormat::Json => Format::Json,
+            SubscriptionFormat::Opml => Format::Opml,
+            SubscriptionFormat::Txt => Format::Txt,
+            SubscriptionFormat::Xml => Format::Xml,
+        }
+    }
+}
+
+impl fmt::Display for SubscriptionFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
     }
 }
 
+fn deduplicate(urls: &[Url]) -> Vec<Url> {
+    let mut seen = HashSet::new();
+    urls.iter()
+        .filter(|url| seen.insert(*url))
+        .cloned()
+        .collect()
+}
+
 impl fmt::Display for Podcast {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {} <{}>", self.title, self.description, self.url)
@@ -301,6 +1282,18 @@ impl fmt::Display for UploadSubscriptionChangesResponse {
     }
 }
 
+impl UrlRewrite {
+    /// Returns `true` if the server rejected the URL
+    pub fn is_rejected(&self) -> bool {
+        self.sanitized.is_none()
+    }
+
+    /// Returns the URL to use from now on, `None` if the server rejected the URL
+    pub fn resolved(&self) -> Option<&Url> {
+        self.sanitized.as_ref()
+    }
+}
+
 impl fmt::Display for GetSubscriptionChangesResponse {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -313,9 +1306,28 @@ impl fmt::Display for GetSubscriptionChangesResponse {
 
 #[cfg(test)]
 mod tests {
+    use super::normalize;
+    use super::normalize_without_tracking;
+    use super::public_subscriptions;
+    use super::AuthenticatedClient;
+    use super::FeedUrl;
     use super::GetSubscriptionChangesResponse;
     use super::Podcast;
+    use super::SubscriptionFormat;
+    use super::SubscriptionUploadOutcome;
+    use super::UploadSubscriptionChangesRequest;
     use super::UploadSubscriptionChangesResponse;
+    use super::UrlFix;
+    use super::UrlRewrite;
+    use super::{
+        pull_and_apply, set_subscriptions, LocalSubscriptions, SubscriptionChanges,
+        SubscriptionList, SubscriptionUpload, SubscriptionsOfDevice,
+    };
+    use crate::client::{ApiResponse, Strictness};
+    use crate::error::{Error, ValidationError};
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+    use std::cell::RefCell;
     use std::cmp::Ordering;
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
@@ -388,12 +1400,75 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "zero-copy")]
+    #[test]
+    fn podcast_ref_borrows_from_input() {
+        use super::PodcastRef;
+        use std::borrow::Cow;
+
+        let json = r#"{"url":"http://goinglinux.com/mp3podcast.xml","title":"Going Linux","author":null,"description":"Going Linux","subscribers":571,"subscribers_last_week":571,"logo_url":null,"scaled_logo_url":null,"website":"http://goinglinux.com","mygpo_link":"http://gpodder.net/podcast/11171"}"#;
+        let podcast_ref: PodcastRef<'_> = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(podcast_ref.title, Cow::Borrowed("Going Linux")));
+
+        let podcast = podcast_ref.into_owned().unwrap();
+        assert_eq!(
+            Url::parse("http://goinglinux.com/mp3podcast.xml").unwrap(),
+            podcast.url
+        );
+        assert_eq!(
+            Some(Url::parse("http://goinglinux.com").unwrap()),
+            podcast.website
+        );
+    }
+
+    #[test]
+    fn upload_subscription_changes_request_removes_duplicates() {
+        let url1 = Url::parse("http://example.com/feed.rss").unwrap();
+        let url2 = Url::parse("http://example.org/podcast.php").unwrap();
+        let url3 = Url::parse("http://example.net/foo.xml").unwrap();
+
+        let request = UploadSubscriptionChangesRequest::new(
+            &[url1.clone(), url2.clone(), url1.clone()],
+            &[url3.clone(), url3.clone()],
+            Strictness::Strict,
+        )
+        .unwrap();
+
+        assert_eq!(vec![url1, url2], request.add);
+        assert_eq!(vec![url3], request.remove);
+    }
+
+    #[test]
+    fn upload_subscription_changes_request_detects_conflict() {
+        let url1 = Url::parse("http://example.com/feed.rss").unwrap();
+        let url2 = Url::parse("http://example.org/podcast.php").unwrap();
+
+        assert_eq!(
+            Err(ValidationError::ConflictingChange(url2.clone())),
+            UploadSubscriptionChangesRequest::new(
+                &[url1, url2.clone()],
+                &[url2],
+                Strictness::Strict
+            )
+        );
+    }
+
+    #[test]
+    fn deserialize_upload_subscription_changes_response_with_string_timestamp() {
+        let response: UploadSubscriptionChangesResponse =
+            serde_json::from_str(r#"{"timestamp":"1337"}"#).unwrap();
+
+        assert_eq!(1337, response.timestamp);
+        assert!(response.update_urls.is_empty());
+    }
+
     #[test]
     fn display_upload_subscription_changes_response() {
-        let update_urls = vec![(
-            Url::parse("http://feeds2.feedburner.com/LinuxOutlaws?format=xml").unwrap(),
-            Url::parse("http://feeds.feedburner.com/LinuxOutlaws").unwrap(),
-        )];
+        let update_urls = vec![UrlRewrite {
+            original: Url::parse("http://feeds2.feedburner.com/LinuxOutlaws?format=xml").unwrap(),
+            sanitized: Some(Url::parse("http://feeds.feedburner.com/LinuxOutlaws").unwrap()),
+        }];
         let upload_response = UploadSubscriptionChangesResponse {
             timestamp: 100,
             update_urls: update_urls.clone(),
@@ -422,4 +1497,349 @@ mod tests {
             format!("{}", get_response)
         );
     }
+
+    #[test]
+    fn normalize_merges_near_duplicates() {
+        let urls = vec![
+            Url::parse("http://EXAMPLE.com:80/feed.xml").unwrap(),
+            Url::parse("ftp://example.com/feed.xml").unwrap(),
+            Url::parse("https://example.com:443/feed.xml").unwrap(),
+            Url::parse("http://example.com/feed.xml").unwrap(),
+            Url::parse("http://example.org/feed.xml?utm_source=app").unwrap(),
+        ];
+
+        assert_eq!(
+            vec![
+                Url::parse("https://example.com/feed.xml").unwrap(),
+                Url::parse("ftp://example.com/feed.xml").unwrap(),
+                Url::parse("http://example.org/feed.xml?utm_source=app").unwrap(),
+            ],
+            normalize(&urls)
+        );
+    }
+
+    #[test]
+    fn normalize_without_tracking_keeps_other_parameters() {
+        let urls = vec![
+            Url::parse("http://example.org/feed.php?utm_source=app&id=42&fbclid=abc").unwrap(),
+            Url::parse("http://example.org/feed.php?id=42").unwrap(),
+            Url::parse("http://example.net/feed.xml?utm_medium=share").unwrap(),
+        ];
+
+        assert_eq!(
+            vec![
+                Url::parse("http://example.org/feed.php?id=42").unwrap(),
+                Url::parse("http://example.net/feed.xml").unwrap(),
+            ],
+            normalize_without_tracking(&urls)
+        );
+    }
+
+    #[test]
+    fn subscription_format_extensions() {
+        assert_eq!("opml", SubscriptionFormat::Opml.extension());
+        assert_eq!("txt", format!("{}", SubscriptionFormat::Txt));
+    }
+
+    #[test]
+    fn feed_url_validation() {
+        assert_eq!(
+            "http://example.com/feed.xml",
+            FeedUrl::parse("http://example.com/feed.xml")
+                .unwrap()
+                .as_url()
+                .as_str()
+        );
+        assert_eq!(
+            Err(ValidationError::InvalidFeedUrl(String::from(
+                "mailto:podcast@example.com"
+            ))),
+            FeedUrl::parse("mailto:podcast@example.com")
+        );
+        assert!(FeedUrl::parse("http://exämple.com/feed.xml").is_err());
+        assert!(FeedUrl::parse("not a url").is_err());
+    }
+
+    #[test]
+    fn upload_subscription_changes_request_rejects_invalid_feed_urls_if_strict() {
+        let url = Url::parse("ftp://example.com/feed.rss").unwrap();
+
+        assert_eq!(
+            Err(ValidationError::InvalidFeedUrl(String::from(
+                "ftp://example.com/feed.rss"
+            ))),
+            UploadSubscriptionChangesRequest::new(
+                std::slice::from_ref(&url),
+                &[],
+                Strictness::Strict
+            )
+        );
+        assert!(UploadSubscriptionChangesRequest::new(&[url], &[], Strictness::Lenient).is_ok());
+    }
+
+    #[test]
+    fn deserialize_subscribers_beyond_u16() {
+        let podcast: Podcast = serde_json::from_str(r#"{"website":"http://goinglinux.com","mygpo_link":"http://gpodder.net/podcast/11171","description":"Going Linux","subscribers":70000,"title":"Going Linux","url":"http://goinglinux.com/mp3podcast.xml","subscribers_last_week":65536,"logo_url":null,"scaled_logo_url":null,"author":null}"#).unwrap();
+
+        assert_eq!(70000, podcast.subscribers);
+        assert_eq!(65536, podcast.subscribers_last_week);
+    }
+
+    fn response(status: StatusCode, body: &str) -> ApiResponse {
+        ApiResponse {
+            url: Url::parse("https://gpodder.net/user/username/subscriptions.opml").unwrap(),
+            status,
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn public_subscriptions_are_read_from_opml() {
+        let opml = r#"<opml version="2.0"><body><outline xmlUrl="http://example.com/feed.rss"/></body></opml>"#;
+
+        assert_eq!(
+            vec![Url::parse("http://example.com/feed.rss").unwrap()],
+            public_subscriptions(response(StatusCode::OK, opml), "username").unwrap()
+        );
+        match public_subscriptions(response(StatusCode::FORBIDDEN, "private"), "username") {
+            Err(Error::SubscriptionsNotPublic(username)) => assert_eq!("username", username),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn deserialize_rejected_update_url() {
+        let json = r#"{"timestamp":1337,"update_urls":[["http://example.com/feed.rss","http://example.com/feed.rss?format=xml"],["http://example.org/podcast.php",""]]}"#;
+
+        let response: UploadSubscriptionChangesResponse = serde_json::from_str(json).unwrap();
+
+        assert!(!response.update_urls[0].is_rejected());
+        assert!(response.update_urls[1].is_rejected());
+        assert_eq!(None, response.update_urls[1].resolved());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(json).unwrap(),
+            serde_json::to_value(&response).unwrap()
+        );
+    }
+
+    #[test]
+    fn subscription_upload_outcome_sorts_urls() {
+        let accepted = Url::parse("http://example.com/feed.rss").unwrap();
+        let rewritten = Url::parse("http://feeds2.feedburner.com/LinuxOutlaws?format=xml").unwrap();
+        let sanitized = Url::parse("http://feeds.feedburner.com/LinuxOutlaws").unwrap();
+        let rejected = Url::parse("http://example.org/p%C3%B6dcast.php").unwrap();
+        let response = UploadSubscriptionChangesResponse {
+            timestamp: 100,
+            update_urls: vec![
+                UrlRewrite {
+                    original: rewritten.clone(),
+                    sanitized: Some(sanitized.clone()),
+                },
+                UrlRewrite {
+                    original: rejected.clone(),
+                    sanitized: None,
+                },
+            ],
+        };
+
+        let outcome = SubscriptionUploadOutcome::new(
+            &[accepted.clone(), rewritten.clone()],
+            std::slice::from_ref(&rejected),
+            response,
+        );
+
+        assert_eq!(100, outcome.timestamp);
+        assert_eq!(vec![accepted], outcome.accepted);
+        assert_eq!(vec![(rewritten, sanitized)], outcome.rewritten);
+        assert_eq!(vec![rejected], outcome.rejected);
+        assert!(!outcome.is_fully_accepted());
+    }
+
+    struct FakeDevice {
+        subscriptions: Vec<Url>,
+        removed: Vec<Url>,
+        uploads: RefCell<Vec<String>>,
+    }
+
+    impl SubscriptionsOfDevice for FakeDevice {
+        fn get_subscriptions_of_device(&self) -> Result<Vec<Url>, Error> {
+            Ok(self.subscriptions.clone())
+        }
+
+        fn get_subscriptions_of_device_in_format(
+            &self,
+            _format: SubscriptionFormat,
+        ) -> Result<SubscriptionList, Error> {
+            Ok(SubscriptionList::Urls(self.subscriptions.clone()))
+        }
+
+        fn upload_subscriptions_of_device(&self, subscriptions: &[Url]) -> Result<(), Error> {
+            self.uploads
+                .borrow_mut()
+                .push(format!("full {}", subscriptions.len()));
+            Ok(())
+        }
+
+        fn upload_subscriptions_of_device_if_unmodified(
+            &self,
+            subscriptions: &[Url],
+            timestamp: u64,
+        ) -> Result<(), Error> {
+            self.uploads.borrow_mut().push(format!(
+                "full {} if unmodified since {}",
+                subscriptions.len(),
+                timestamp
+            ));
+            Ok(())
+        }
+    }
+
+    impl SubscriptionChanges for FakeDevice {
+        fn upload_subscription_changes(
+            &self,
+            add: &[Url],
+            remove: &[Url],
+        ) -> Result<UploadSubscriptionChangesResponse, Error> {
+            self.uploads
+                .borrow_mut()
+                .push(format!("changes +{} -{}", add.len(), remove.len()));
+            Ok(UploadSubscriptionChangesResponse::default())
+        }
+
+        fn get_subscription_changes(
+            &self,
+            timestamp: u64,
+        ) -> Result<GetSubscriptionChangesResponse, Error> {
+            Ok(GetSubscriptionChangesResponse {
+                timestamp: timestamp + 10,
+                add: self.subscriptions.clone(),
+                remove: self.removed.clone(),
+            })
+        }
+    }
+
+    #[test]
+    fn set_subscriptions_selects_smaller_upload() {
+        let url = |name: &str| Url::parse(&format!("http://example.com/{}.rss", name)).unwrap();
+        let device = FakeDevice {
+            subscriptions: vec![url("a"), url("b"), url("c")],
+            removed: Vec::new(),
+            uploads: RefCell::new(Vec::new()),
+        };
+
+        let update = set_subscriptions(&device, &[url("a"), url("b"), url("d")]).unwrap();
+        assert_eq!(vec![url("d")], update.delta.add);
+        assert_eq!(vec![url("c")], update.delta.remove);
+
+        let update = set_subscriptions(&device, &[url("e")]).unwrap();
+        assert_eq!(SubscriptionUpload::Full, update.upload);
+
+        let update = set_subscriptions(&device, &[url("c"), url("b"), url("a"), url("a")]).unwrap();
+        assert_eq!(SubscriptionUpload::Unchanged, update.upload);
+
+        assert_eq!(
+            vec![String::from("changes +1 -1"), String::from("full 1")],
+            device.uploads.into_inner()
+        );
+    }
+
+    #[test]
+    fn pull_and_apply_merges_changes() {
+        let url = |name: &str| Url::parse(&format!("http://example.com/{}.rss", name)).unwrap();
+        let device = FakeDevice {
+            subscriptions: vec![url("a"), url("b")],
+            removed: vec![url("c")],
+            uploads: RefCell::new(Vec::new()),
+        };
+        let mut local = LocalSubscriptions {
+            subscriptions: vec![url("c"), url("a"), url("d")],
+            since: 5,
+        };
+
+        let delta = pull_and_apply(&device, &mut local).unwrap();
+
+        assert_eq!(vec![url("a"), url("b")], delta.add);
+        assert_eq!(vec![url("c")], delta.remove);
+        assert_eq!(vec![url("a"), url("d"), url("b")], local.subscriptions);
+        assert_eq!(15, local.since);
+    }
+
+    #[test]
+    fn parse_lenient_reports_fixes() {
+        let lenient = FeedUrl::parse_lenient("example.com/feed.xml").unwrap();
+        assert_eq!(
+            "http://example.com/feed.xml",
+            lenient.feed_url.as_url().as_str()
+        );
+        assert_eq!(vec![UrlFix::AddedScheme], lenient.fixes);
+
+        let lenient = FeedUrl::parse_lenient("feed:https://example.com/feed.xml").unwrap();
+        assert_eq!(
+            "https://example.com/feed.xml",
+            lenient.feed_url.as_url().as_str()
+        );
+        assert_eq!(
+            vec![UrlFix::ReplacedScheme(String::from("feed"))],
+            lenient.fixes
+        );
+
+        let lenient = FeedUrl::parse_lenient("https://example.com/feed.xml").unwrap();
+        assert!(!lenient.is_corrected());
+
+        assert_eq!(
+            Err(ValidationError::InvalidFeedUrl(String::from(
+                "mailto:podcast@example.com"
+            ))),
+            FeedUrl::parse_lenient("mailto:podcast@example.com")
+        );
+    }
+
+    #[test]
+    fn subscribe_with_uploads_sanitized_url() {
+        let device = FakeDevice {
+            subscriptions: Vec::new(),
+            removed: Vec::new(),
+            uploads: RefCell::new(Vec::new()),
+        };
+        let podcast = Podcast {
+            url: Url::parse("http://goinglinux.com/mp3podcast.xml?utm_source=directory").unwrap(),
+            author: None,
+            website: None,
+            mygpo_link: Url::parse("http://gpodder.net/podcast/11171").unwrap(),
+            description: String::from("Going Linux"),
+            subscribers: 571,
+            title: String::from("Going Linux"),
+            subscribers_last_week: 571,
+            logo_url: None,
+            scaled_logo_url: None,
+        };
+
+        let stored_url = podcast.subscribe_with(&device).unwrap();
+
+        assert_eq!("http://goinglinux.com/mp3podcast.xml", stored_url.as_str());
+        assert_eq!(vec!["changes +1 -0"], *device.uploads.borrow());
+    }
+
+    #[test]
+    fn public_subscription_urls_encode_username() {
+        let urls = AuthenticatedClient::new("some user", "password")
+            .with_base_url("https://gpodder.example/")
+            .unwrap()
+            .public_subscription_urls()
+            .unwrap();
+
+        assert_eq!(
+            "https://gpodder.example/user/some%20user/",
+            urls.profile.as_str()
+        );
+        assert_eq!(
+            "https://gpodder.example/user/some%20user/subscriptions/",
+            urls.web.as_str()
+        );
+        assert_eq!(
+            "https://gpodder.example/user/some%20user/subscriptions.opml",
+            urls.opml.as_str()
+        );
+    }
 }
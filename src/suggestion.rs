@@ -1,34 +1,15 @@
 //! [Suggestions API](https://gpoddernet.readthedocs.io/en/latest/api/reference/suggestions.html)
 
+use crate::client::ApiArea;
 use crate::client::AuthenticatedClient;
 use crate::client::DeviceClient;
 use crate::error::Error;
-use serde::{Deserialize, Serialize};
-use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::fmt;
-use std::hash::{Hash, Hasher};
 use url::Url;
 
-/// A podcast suggestion as returned by [retrieve_suggested_podcasts](RetrieveSuggestedPodcasts::retrieve_suggested_podcasts)
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Suggestion {
-    /// website of podcast
-    pub website: Url,
-    /// service-internal feed link
-    pub mygpo_link: Url,
-    /// description of podcast
-    pub description: String,
-    /// number of subscribers on service
-    pub subscribers: u16,
-    /// title of podcast
-    pub title: String,
-    /// feed URL
-    pub url: Url,
-    /// number of subscribers on service one week before
-    pub subscribers_last_week: u16,
-    /// URL to logo of podcast
-    pub logo_url: Option<Url>,
-}
+pub use crate::protocol::suggestions::Suggestion;
 
 /// see [retrieve_suggested_podcasts](RetrieveSuggestedPodcasts::retrieve_suggested_podcasts)
 pub trait RetrieveSuggestedPodcasts {
@@ -61,16 +42,64 @@ pub trait RetrieveSuggestedPodcasts {
     ///
     /// - [Suggestions API: Retrieve Suggested Podcasts](https://gpoddernet.readthedocs.io/en/latest/api/reference/suggestions.html#retrieve-suggested-podcasts)
     fn retrieve_suggested_podcasts(&self, max_results: u8) -> Result<Vec<Suggestion>, Error>;
+
+    /// Retrieve suggested podcasts which aren't in `exclude`, e.g. because they are already added to the client application
+    ///
+    /// As the server can't filter, twice `max_results` suggestions are requested at first. The number is doubled until `max_results` suggestions remain after filtering or the server has no more suggestions. No more than `max_results` plus the number of excluded feeds are requested, as that many always suffice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mygpoclient::client::AuthenticatedClient;
+    /// use mygpoclient::suggestion::RetrieveSuggestedPodcasts;
+    /// use url::Url;
+    ///
+    /// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+    /// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+    /// #
+    /// let client = AuthenticatedClient::new(&username, &password);
+    /// let local_feeds = vec![Url::parse("http://example.com/feed.rss").unwrap()];
+    ///
+    /// let suggestions = client.retrieve_suggested_podcasts_filtered(3, &local_feeds)?;
+    ///
+    /// assert!(suggestions.len() <= 3);
+    /// assert!(suggestions.iter().all(|suggestion| !local_feeds.contains(&suggestion.url)));
+    ///
+    /// # Ok::<(), mygpoclient::error::Error>(())
+    /// ```
+    fn retrieve_suggested_podcasts_filtered(
+        &self,
+        max_results: u8,
+        exclude: &[Url],
+    ) -> Result<Vec<Suggestion>, Error> {
+        let exclude: HashSet<&Url> = exclude.iter().collect();
+        let limit = u8::try_from(usize::from(max_results) + exclude.len()).unwrap_or(u8::MAX);
+        let mut count = max_results.saturating_mul(2).min(limit);
+
+        loop {
+            let suggestions = self.retrieve_suggested_podcasts(count)?;
+            let exhausted = suggestions.len() < usize::from(count) || count == limit;
+            let novel: Vec<Suggestion> = suggestions
+                .into_iter()
+                .filter(|suggestion| !exclude.contains(&suggestion.url))
+                .take(usize::from(max_results))
+                .collect();
+
+            if exhausted || novel.len() == usize::from(max_results) {
+                return Ok(novel);
+            }
+            count = count.saturating_mul(2).min(limit);
+        }
+    }
 }
 
 impl RetrieveSuggestedPodcasts for AuthenticatedClient {
     fn retrieve_suggested_podcasts(&self, max_results: u8) -> Result<Vec<Suggestion>, Error> {
-        Ok(self
-            .get(&format!(
-                "https://gpodder.net/suggestions/{}.json",
-                max_results
-            ))?
-            .json()?)
+        self.get(&self.url(
+            ApiArea::Suggestions,
+            &format!("suggestions/{}.json", max_results),
+        ))?
+        .json()
     }
 }
 
@@ -80,32 +109,6 @@ impl RetrieveSuggestedPodcasts for DeviceClient {
     }
 }
 
-impl PartialEq for Suggestion {
-    fn eq(&self, other: &Self) -> bool {
-        self.url == other.url
-    }
-}
-
-impl Eq for Suggestion {}
-
-impl Ord for Suggestion {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.url.cmp(&other.url)
-    }
-}
-
-impl PartialOrd for Suggestion {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Hash for Suggestion {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.url.hash(state);
-    }
-}
-
 impl fmt::Display for Suggestion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {} <{}>", self.title, self.description, self.url)
@@ -114,7 +117,9 @@ impl fmt::Display for Suggestion {
 
 #[cfg(test)]
 mod tests {
-    use super::Suggestion;
+    use super::{RetrieveSuggestedPodcasts, Suggestion};
+    use crate::error::Error;
+    use std::cell::RefCell;
     use std::cmp::Ordering;
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
@@ -173,4 +178,86 @@ mod tests {
             format!("{}", suggestion)
         );
     }
+
+    struct FakeSuggestions {
+        available: u8,
+        requested: RefCell<Vec<u8>>,
+    }
+
+    impl RetrieveSuggestedPodcasts for FakeSuggestions {
+        fn retrieve_suggested_podcasts(&self, max_results: u8) -> Result<Vec<Suggestion>, Error> {
+            self.requested.borrow_mut().push(max_results);
+            Ok((0..max_results.min(self.available))
+                .map(|index| suggestion(&format!("http://example.com/{}.rss", index)))
+                .collect())
+        }
+    }
+
+    fn suggestion(url: &str) -> Suggestion {
+        Suggestion {
+            url: Url::parse(url).unwrap(),
+            website: Url::parse("http://example.com").unwrap(),
+            mygpo_link: Url::parse("http://gpodder.net/podcast/1").unwrap(),
+            description: String::new(),
+            subscribers: 1,
+            title: String::from("Podcast"),
+            subscribers_last_week: 1,
+            logo_url: None,
+        }
+    }
+
+    #[test]
+    fn filtered_suggestions_request_more_until_enough_remain() {
+        let client = FakeSuggestions {
+            available: 20,
+            requested: RefCell::new(Vec::new()),
+        };
+        let exclude: Vec<Url> = (0..4)
+            .map(|index| Url::parse(&format!("http://example.com/{}.rss", index)).unwrap())
+            .collect();
+
+        let suggestions = client
+            .retrieve_suggested_podcasts_filtered(3, &exclude[..1])
+            .unwrap();
+        assert_eq!(
+            vec![
+                "http://example.com/1.rss",
+                "http://example.com/2.rss",
+                "http://example.com/3.rss"
+            ],
+            suggestions
+                .iter()
+                .map(|s| s.url.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        client.requested.borrow_mut().clear();
+        let suggestions = client
+            .retrieve_suggested_podcasts_filtered(2, &exclude)
+            .unwrap();
+        assert_eq!(
+            vec!["http://example.com/4.rss", "http://example.com/5.rss"],
+            suggestions
+                .iter()
+                .map(|s| s.url.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![4, 6], *client.requested.borrow());
+    }
+
+    #[test]
+    fn filtered_suggestions_stop_when_server_is_exhausted() {
+        let client = FakeSuggestions {
+            available: 4,
+            requested: RefCell::new(Vec::new()),
+        };
+        let exclude = vec![Url::parse("http://example.com/0.rss").unwrap()];
+
+        let suggestions = client
+            .retrieve_suggested_podcasts_filtered(10, &exclude)
+            .unwrap();
+
+        assert_eq!(3, suggestions.len());
+        assert_eq!(vec![11], *client.requested.borrow());
+    }
 }
@@ -0,0 +1,849 @@
+//! Periodic background synchronization
+//!
+//! A [Scheduler] polls subscription changes and episode actions of a device at configurable intervals on a background thread and reports the results as [SyncEvent]s through a channel. Failed synchronizations are retried with exponential backoff. While the server is down for [maintenance](Error::Maintenance), all synchronizations are postponed until the announced time and reported as [SyncEvent::Maintenance]. Its progress is persisted as [SyncState] by a [SyncStore], e.g. one of the [store]s, so applications can resume later and show when data was last synchronized.
+//!
+//! [compare_devices] compares the subscriptions of all devices of a user, so they can be made identical with [equalize](DeviceDiffReport::equalize).
+
+#[cfg(feature = "devices")]
+use crate::client::AuthenticatedClient;
+use crate::client::DeviceClient;
+#[cfg(feature = "devices")]
+use crate::device::ListDevices;
+use crate::episode::{GetEpisodeActions, GetEpisodeActionsResponse};
+use crate::error::Error;
+#[cfg(feature = "devices")]
+use crate::subscription::SubscriptionsOfDevice;
+use crate::subscription::{GetSubscriptionChangesResponse, SubscriptionChanges};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "devices")]
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+#[cfg(feature = "devices")]
+use url::Url;
+
+pub mod store;
+
+/// Data synchronized by a [Scheduler]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum SyncKind {
+    /// see [get_subscription_changes](SubscriptionChanges::get_subscription_changes)
+    Subscriptions,
+    /// see [get_episode_actions](GetEpisodeActions::get_episode_actions)
+    EpisodeActions,
+}
+
+/// Result of a single synchronization, as sent by a running [Scheduler]
+#[derive(Debug)]
+pub enum SyncEvent {
+    /// subscription changes since the previous synchronization
+    Subscriptions(GetSubscriptionChangesResponse),
+    /// episode actions since the previous synchronization
+    EpisodeActions(GetEpisodeActionsResponse),
+    /// synchronization failed and will be retried
+    Failed {
+        /// data which failed to synchronize
+        kind: SyncKind,
+        /// cause of the failure
+        error: Error,
+        /// number of consecutive failures
+        failures: u32,
+        /// delay until the next attempt
+        retry_in: Duration,
+    },
+    /// server is down for maintenance, so all synchronizations are postponed
+    ///
+    /// Applications can show that the service is in maintenance until the next [Subscriptions](SyncEvent::Subscriptions) or [EpisodeActions](SyncEvent::EpisodeActions) event.
+    Maintenance {
+        /// data which failed to synchronize
+        kind: SyncKind,
+        /// time until the server is expected to be available again, if announced
+        retry_after: Option<Duration>,
+        /// delay until the next attempt
+        retry_in: Duration,
+    },
+}
+
+/// Progress of the synchronizations of a [Scheduler], as persisted by a [SyncStore]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SyncState {
+    /// timestamp returned by the last synchronization of subscriptions
+    pub subscriptions_since: u64,
+    /// timestamp returned by the last synchronization of episode actions
+    pub episode_actions_since: u64,
+    /// UTC time of the last successful synchronization of subscriptions
+    pub last_synced_subscriptions: Option<NaiveDateTime>,
+    /// UTC time of the last successful synchronization of episode actions
+    pub last_synced_episode_actions: Option<NaiveDateTime>,
+}
+
+/// Persistence of the [SyncState] of a [Scheduler]
+pub trait SyncStore: fmt::Debug + Send + Sync {
+    /// Returns the saved state, or the [default](SyncState::default) if nothing has been saved yet
+    fn load(&self) -> Result<SyncState, Error>;
+
+    /// Replace the saved state
+    fn save(&self, state: &SyncState) -> Result<(), Error>;
+}
+
+/// [SyncStore] keeping the state in memory only
+///
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySyncStore(Arc<Mutex<SyncState>>);
+
+/// Numbers of items exchanged by a running [Scheduler] since it was started
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SyncReport {
+    /// number of received subscriptions to add
+    pub subscriptions_added: usize,
+    /// number of received subscriptions to remove
+    pub subscriptions_removed: usize,
+    /// number of received episode actions
+    pub episode_actions: usize,
+    /// number of failed synchronizations
+    pub failures: usize,
+}
+
+/// Runs synchronizations of a [DeviceClient] periodically on a background thread
+///
+/// # Examples
+///
+/// ```no_run
+/// use mygpoclient::client::DeviceClient;
+/// use mygpoclient::sync::{Scheduler, SyncEvent};
+/// use std::time::Duration;
+///
+/// # use mygpoclient::reference::{DeviceId, Username};
+/// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+/// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+/// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+/// #
+/// let client = DeviceClient::new(&username, &password, &deviceid);
+///
+/// let (handle, events) = Scheduler::new(client, Duration::from_secs(15 * 60)).start();
+///
+/// for event in events.iter().take(2) {
+///     if let SyncEvent::Subscriptions(changes) = event {
+///         println!("{}", changes);
+///     }
+/// }
+///
+/// handle.stop();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    client: DeviceClient,
+    subscriptions_interval: Duration,
+    episode_actions_interval: Duration,
+    max_backoff: Duration,
+    state: SyncState,
+    store: Arc<dyn SyncStore>,
+}
+
+/// Handle to a running [Scheduler]
+#[derive(Debug)]
+pub struct SchedulerHandle {
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+    progress: Arc<Mutex<Progress>>,
+}
+
+#[derive(Debug, Default)]
+struct Progress {
+    state: SyncState,
+    report: SyncReport,
+}
+
+#[derive(Debug)]
+struct Task {
+    kind: SyncKind,
+    interval: Duration,
+    since: u64,
+    failures: u32,
+    next_due: NaiveDateTime,
+}
+
+impl Scheduler {
+    /// Create [Scheduler] synchronizing subscriptions and episode actions every `interval`
+    ///
+    /// The maximum backoff defaults to ten times `interval`. All data is requested on the first synchronization.
+    pub fn new(client: DeviceClient, interval: Duration) -> Scheduler {
+        Scheduler {
+            client,
+            subscriptions_interval: interval,
+            episode_actions_interval: interval,
+            max_backoff: interval * 10,
+            state: SyncState::default(),
+            store: Arc::new(MemorySyncStore::default()),
+        }
+    }
+
+    /// Synchronize subscriptions every `interval`
+    pub fn with_subscriptions_interval(mut self, interval: Duration) -> Scheduler {
+        self.subscriptions_interval = interval;
+        self
+    }
+
+    /// Synchronize episode actions every `interval`
+    pub fn with_episode_actions_interval(mut self, interval: Duration) -> Scheduler {
+        self.episode_actions_interval = interval;
+        self
+    }
+
+    /// Never wait longer than `max_backoff` before retrying a failed synchronization
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Scheduler {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Continue from timestamps returned by previous synchronizations
+    pub fn with_timestamps(
+        mut self,
+        subscriptions_since: u64,
+        episode_actions_since: u64,
+    ) -> Scheduler {
+        self.state.subscriptions_since = subscriptions_since;
+        self.state.episode_actions_since = episode_actions_since;
+        self
+    }
+
+    /// Continue from the state saved in `store` and save the progress of every successful synchronization there
+    ///
+    /// Fails if the saved state can't be loaded. The default store keeps the state in memory only.
+    pub fn with_store<S: SyncStore + 'static>(mut self, store: S) -> Result<Scheduler, Error> {
+        self.state = store.load()?;
+        self.store = Arc::new(store);
+        Ok(self)
+    }
+
+    /// Start synchronizing on a background thread
+    ///
+    /// The first synchronization starts immediately. Due times are read from the [clock](crate::client::DeviceClient::with_clock) of the client. The thread stops when [stop](SchedulerHandle::stop) is called or the returned [Receiver] is dropped.
+    pub fn start(self) -> (SchedulerHandle, Receiver<SyncEvent>) {
+        let (stop_sender, stop_receiver) = mpsc::channel();
+        let (event_sender, event_receiver) = mpsc::channel();
+        let progress = Arc::new(Mutex::new(Progress {
+            state: self.state.clone(),
+            report: SyncReport::default(),
+        }));
+
+        let shared = Arc::clone(&progress);
+        let thread = thread::spawn(move || self.run(&shared, &stop_receiver, &event_sender));
+
+        (
+            SchedulerHandle {
+                stop: stop_sender,
+                thread,
+                progress,
+            },
+            event_receiver,
+        )
+    }
+
+    fn run(self, progress: &Mutex<Progress>, stop: &Receiver<()>, events: &Sender<SyncEvent>) {
+        let now = self.client.now();
+        let mut tasks = self.tasks(now);
+
+        loop {
+            let next_due = tasks.iter().map(|task| task.next_due).min().unwrap_or(now);
+            let wait = (next_due - self.client.now()).to_std().unwrap_or_default();
+            match stop.recv_timeout(wait) {
+                Err(RecvTimeoutError::Timeout) => {}
+                _ => return,
+            }
+
+            if !self.run_due(&mut tasks, progress, events) {
+                return;
+            }
+        }
+    }
+
+    /// Returns the tasks, all due at `now`
+    fn tasks(&self, now: NaiveDateTime) -> [Task; 2] {
+        [
+            Task::new(
+                SyncKind::Subscriptions,
+                self.subscriptions_interval,
+                self.state.subscriptions_since,
+                now,
+            ),
+            Task::new(
+                SyncKind::EpisodeActions,
+                self.episode_actions_interval,
+                self.state.episode_actions_since,
+                now,
+            ),
+        ]
+    }
+
+    /// Run the tasks due at the current time of the clock of the client
+    ///
+    /// Returns `false` once the receiver of the events has been dropped.
+    fn run_due(
+        &self,
+        tasks: &mut [Task],
+        progress: &Mutex<Progress>,
+        events: &Sender<SyncEvent>,
+    ) -> bool {
+        let now = self.client.now();
+        let mut maintenance_until = None;
+        for task in tasks.iter_mut().filter(|task| task.next_due <= now) {
+            if maintenance_until.is_some() {
+                continue;
+            }
+
+            let event = match self.sync(task, progress) {
+                Ok(event) => {
+                    task.failures = 0;
+                    task.next_due = after(now, task.interval);
+                    event
+                }
+                Err(error) => self.fail(task, error, now, progress),
+            };
+
+            if let SyncEvent::Maintenance { .. } = event {
+                maintenance_until = Some(task.next_due);
+            }
+            if events.send(event).is_err() {
+                return false;
+            }
+        }
+
+        // the server is unavailable for every kind of data, so the other tasks wait as well
+        if let Some(until) = maintenance_until {
+            for task in tasks.iter_mut() {
+                task.next_due = task.next_due.max(until);
+            }
+        }
+        true
+    }
+
+    /// Schedule the retry of a failed task
+    ///
+    /// During [maintenance](Error::Maintenance), the announced `Retry-After` delay is waited instead of the backoff, but never longer than the maximum backoff.
+    fn fail(
+        &self,
+        task: &mut Task,
+        error: Error,
+        now: NaiveDateTime,
+        progress: &Mutex<Progress>,
+    ) -> SyncEvent {
+        lock(progress).report.failures += 1;
+        task.failures = task.failures.saturating_add(1);
+        let backoff = backoff(task.interval, task.failures, self.max_backoff);
+        match error {
+            Error::Maintenance { retry_after, .. } => {
+                let retry_in =
+                    retry_after.map_or(backoff, |retry_after| retry_after.min(self.max_backoff));
+                task.next_due = after(now, retry_in);
+                SyncEvent::Maintenance {
+                    kind: task.kind,
+                    retry_after,
+                    retry_in,
+                }
+            }
+            error => {
+                task.next_due = after(now, backoff);
+                SyncEvent::Failed {
+                    kind: task.kind,
+                    error,
+                    failures: task.failures,
+                    retry_in: backoff,
+                }
+            }
+        }
+    }
+
+    fn sync(&self, task: &mut Task, progress: &Mutex<Progress>) -> Result<SyncEvent, Error> {
+        let now = self.client.now();
+        match task.kind {
+            SyncKind::Subscriptions => {
+                let changes = self.client.get_subscription_changes(task.since)?;
+                self.commit(progress, |state, report| {
+                    state.subscriptions_since = changes.timestamp;
+                    state.last_synced_subscriptions = Some(now);
+                    report.subscriptions_added += changes.add.len();
+                    report.subscriptions_removed += changes.remove.len();
+                })?;
+                task.since = changes.timestamp;
+                Ok(SyncEvent::Subscriptions(changes))
+            }
+            SyncKind::EpisodeActions => {
+                let actions =
+                    self.client
+                        .as_ref()
+                        .get_episode_actions(None, Some(task.since), false)?;
+                self.commit(progress, |state, report| {
+                    state.episode_actions_since = actions.timestamp;
+                    state.last_synced_episode_actions = Some(now);
+                    report.episode_actions += actions.actions.len();
+                })?;
+                task.since = actions.timestamp;
+                Ok(SyncEvent::EpisodeActions(actions))
+            }
+        }
+    }
+
+    /// Apply `update` to the progress, but only once the new state has been saved, so failed saves are retried like failed requests
+    fn commit<F: FnOnce(&mut SyncState, &mut SyncReport)>(
+        &self,
+        progress: &Mutex<Progress>,
+        update: F,
+    ) -> Result<(), Error> {
+        let mut progress = lock(progress);
+        let mut state = progress.state.clone();
+        let mut report = progress.report;
+        update(&mut state, &mut report);
+        self.store.save(&state)?;
+        progress.state = state;
+        progress.report = report;
+        Ok(())
+    }
+}
+
+impl MemorySyncStore {
+    /// Create [MemorySyncStore] holding `state`
+    pub fn new(state: SyncState) -> MemorySyncStore {
+        MemorySyncStore(Arc::new(Mutex::new(state)))
+    }
+}
+
+impl SyncStore for MemorySyncStore {
+    fn load(&self) -> Result<SyncState, Error> {
+        Ok(lock(&self.0).clone())
+    }
+
+    fn save(&self, state: &SyncState) -> Result<(), Error> {
+        *lock(&self.0) = state.clone();
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // state is only replaced as a whole, so a panic while holding the lock can't leave it inconsistent
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl SchedulerHandle {
+    /// Returns the UTC time of the last successful synchronization of subscriptions, including those of previous runs loaded from the [SyncStore]
+    pub fn last_synced_subscriptions(&self) -> Option<NaiveDateTime> {
+        lock(&self.progress).state.last_synced_subscriptions
+    }
+
+    /// Returns the UTC time of the last successful synchronization of episode actions, including those of previous runs loaded from the [SyncStore]
+    pub fn last_synced_episode_actions(&self) -> Option<NaiveDateTime> {
+        lock(&self.progress).state.last_synced_episode_actions
+    }
+
+    /// Returns the current [SyncState]
+    pub fn state(&self) -> SyncState {
+        lock(&self.progress).state.clone()
+    }
+
+    /// Returns the numbers of items exchanged since the [Scheduler] was started
+    pub fn report(&self) -> SyncReport {
+        lock(&self.progress).report
+    }
+
+    /// Stop synchronizing and wait for a running synchronization to finish
+    pub fn stop(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
+
+impl Task {
+    fn new(kind: SyncKind, interval: Duration, since: u64, next_due: NaiveDateTime) -> Task {
+        Task {
+            kind,
+            interval,
+            since,
+            failures: 0,
+            next_due,
+        }
+    }
+}
+
+/// Returns the time `delay` after `now`
+fn after(now: NaiveDateTime, delay: Duration) -> NaiveDateTime {
+    // delays are bounded by the intervals and the maximum backoff, so only absurd configurations overflow and run again immediately
+    chrono::Duration::from_std(delay)
+        .ok()
+        .and_then(|delay| now.checked_add_signed(delay))
+        .unwrap_or(now)
+}
+
+fn backoff(interval: Duration, failures: u32, max_backoff: Duration) -> Duration {
+    interval
+        .checked_mul(2u32.saturating_pow(failures))
+        .map_or(max_backoff, |delay| delay.min(max_backoff))
+}
+
+/// Subscriptions of all devices of a user, as returned by [compare_devices]
+#[cfg(feature = "devices")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceDiffReport {
+    /// subscriptions by device ID
+    pub subscriptions: BTreeMap<String, BTreeSet<Url>>,
+}
+
+/// Feed which some devices are subscribed to and others aren't, see [DeviceDiffReport::differences]
+#[cfg(feature = "devices")]
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct FeedDiff {
+    /// feed URL of the podcast
+    pub url: Url,
+    /// IDs of the devices subscribed to the feed
+    pub present_on: Vec<String>,
+    /// IDs of the devices not subscribed to the feed
+    pub missing_on: Vec<String>,
+}
+
+/// Target of [equalize](DeviceDiffReport::equalize)
+#[cfg(feature = "devices")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum Equalization {
+    /// subscribe every device to every feed any device is subscribed to
+    Union,
+    /// unsubscribe every device from all feeds not every device is subscribed to
+    Intersection,
+}
+
+/// Fetch the subscriptions of all devices of the user
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::client::AuthenticatedClient;
+/// use mygpoclient::sync::{compare_devices, Equalization};
+///
+/// # let username = std::env::var("GPODDER_NET_USERNAME").unwrap();
+/// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+/// #
+/// let client = AuthenticatedClient::new(&username, &password);
+///
+/// let report = compare_devices(&client)?;
+/// for diff in report.differences() {
+///     println!("{} is missing on {:?}", diff.url, diff.missing_on);
+/// }
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+#[cfg(feature = "devices")]
+pub fn compare_devices(client: &AuthenticatedClient) -> Result<DeviceDiffReport, Error> {
+    let mut report = DeviceDiffReport::default();
+    for device in client.list_devices()? {
+        let subscriptions = client
+            .device_client(&device.id)
+            .get_subscriptions_of_device()?;
+        report
+            .subscriptions
+            .insert(device.id, subscriptions.into_iter().collect());
+    }
+    Ok(report)
+}
+
+#[cfg(feature = "devices")]
+impl DeviceDiffReport {
+    /// Returns all feeds any device is subscribed to
+    pub fn union(&self) -> BTreeSet<Url> {
+        self.subscriptions.values().flatten().cloned().collect()
+    }
+
+    /// Returns the feeds every device is subscribed to
+    pub fn intersection(&self) -> BTreeSet<Url> {
+        let mut devices = self.subscriptions.values();
+        let first = devices.next().cloned().unwrap_or_default();
+        devices.fold(first, |intersection, subscriptions| {
+            intersection.intersection(subscriptions).cloned().collect()
+        })
+    }
+
+    /// Returns the feeds which some devices are subscribed to and others aren't
+    pub fn differences(&self) -> Vec<FeedDiff> {
+        self.union()
+            .into_iter()
+            .filter_map(|url| {
+                let (present_on, missing_on): (Vec<&String>, Vec<&String>) = self
+                    .subscriptions
+                    .keys()
+                    .partition(|device_id| self.subscriptions[*device_id].contains(&url));
+                if missing_on.is_empty() {
+                    None
+                } else {
+                    Some(FeedDiff {
+                        url,
+                        present_on: present_on.into_iter().cloned().collect(),
+                        missing_on: missing_on.into_iter().cloned().collect(),
+                    })
+                }
+            })
+            .collect()
+    }
+
+    /// Returns `true` if all devices are subscribed to the same feeds
+    pub fn is_identical(&self) -> bool {
+        self.differences().is_empty()
+    }
+
+    /// Upload the changes necessary to give every device the same subscriptions
+    ///
+    /// Only devices which differ from the target are changed. Stops at the first failed upload.
+    pub fn equalize(
+        &self,
+        client: &AuthenticatedClient,
+        equalization: Equalization,
+    ) -> Result<(), Error> {
+        for (device_id, (add, remove)) in self.changes(equalization) {
+            client
+                .device_client(&device_id)
+                .upload_subscription_changes(&add, &remove)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the URLs to add and to remove for each device which differs from the target
+    fn changes(&self, equalization: Equalization) -> BTreeMap<String, (Vec<Url>, Vec<Url>)> {
+        let target = match equalization {
+            Equalization::Union => self.union(),
+            Equalization::Intersection => self.intersection(),
+        };
+
+        self.subscriptions
+            .iter()
+            .filter_map(|(device_id, subscriptions)| {
+                let add: Vec<Url> = target.difference(subscriptions).cloned().collect();
+                let remove: Vec<Url> = subscriptions.difference(&target).cloned().collect();
+                if add.is_empty() && remove.is_empty() {
+                    None
+                } else {
+                    Some((device_id.clone(), (add, remove)))
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "devices")]
+#[cfg(test)]
+mod tests {
+    use super::{
+        backoff, MemorySyncStore, Progress, Scheduler, SyncEvent, SyncKind, SyncState, SyncStore,
+        Task,
+    };
+    use crate::client::DeviceClient;
+    use crate::clock::ManualClock;
+    use crate::error::Error;
+    use crate::reference::{DeviceId, Username};
+    use chrono::NaiveDate;
+    use std::sync::mpsc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    struct ReadOnlyStore;
+
+    impl SyncStore for ReadOnlyStore {
+        fn load(&self) -> Result<SyncState, Error> {
+            Ok(SyncState::default())
+        }
+
+        fn save(&self, _state: &SyncState) -> Result<(), Error> {
+            Err(Error::InvalidConfiguration {
+                reason: String::from("read-only"),
+            })
+        }
+    }
+
+    #[test]
+    fn progress_is_only_committed_after_saving() {
+        let synced = NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0);
+        let store = MemorySyncStore::new(SyncState {
+            subscriptions_since: 10,
+            ..Default::default()
+        });
+        let client = DeviceClient::new(
+            &Username::from("username"),
+            "password",
+            &DeviceId::from("device"),
+        );
+        let scheduler = Scheduler::new(client, Duration::from_secs(60))
+            .with_store(store.clone())
+            .unwrap();
+        assert_eq!(10, scheduler.state.subscriptions_since);
+
+        let progress = Mutex::new(Progress::default());
+        scheduler
+            .commit(&progress, |state, report| {
+                state.subscriptions_since = 20;
+                state.last_synced_subscriptions = Some(synced);
+                report.subscriptions_added += 2;
+            })
+            .unwrap();
+        assert_eq!(20, store.load().unwrap().subscriptions_since);
+        assert_eq!(
+            Some(synced),
+            progress.lock().unwrap().state.last_synced_subscriptions
+        );
+        assert_eq!(2, progress.lock().unwrap().report.subscriptions_added);
+
+        let scheduler = scheduler.with_store(ReadOnlyStore).unwrap();
+        assert!(scheduler
+            .commit(&progress, |_, report| report.episode_actions += 1)
+            .is_err());
+        assert_eq!(0, progress.lock().unwrap().report.episode_actions);
+    }
+
+    #[test]
+    fn backoff_doubles_until_maximum() {
+        let interval = Duration::from_secs(60);
+        let max_backoff = Duration::from_secs(600);
+
+        assert_eq!(Duration::from_secs(120), backoff(interval, 1, max_backoff));
+        assert_eq!(Duration::from_secs(480), backoff(interval, 3, max_backoff));
+        assert_eq!(max_backoff, backoff(interval, 4, max_backoff));
+        assert_eq!(max_backoff, backoff(interval, u32::MAX, max_backoff));
+    }
+
+    #[test]
+    fn postpone_during_maintenance() {
+        let client = DeviceClient::new(
+            &Username::from("username"),
+            "password",
+            &DeviceId::from("device"),
+        );
+        let scheduler = Scheduler::new(client, Duration::from_secs(60))
+            .with_max_backoff(Duration::from_secs(3600));
+        let progress = Mutex::new(Progress::default());
+        let now = NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0);
+        let mut task = Task::new(SyncKind::Subscriptions, Duration::from_secs(60), 0, now);
+        let maintenance = |retry_after| Error::Maintenance {
+            endpoint: String::from("/api/2/updates/username/device.json"),
+            retry_after,
+        };
+
+        match scheduler.fail(
+            &mut task,
+            maintenance(Some(Duration::from_secs(900))),
+            now,
+            &progress,
+        ) {
+            SyncEvent::Maintenance {
+                kind,
+                retry_after,
+                retry_in,
+            } => {
+                assert_eq!(SyncKind::Subscriptions, kind);
+                assert_eq!(Some(Duration::from_secs(900)), retry_after);
+                assert_eq!(Duration::from_secs(900), retry_in);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(now + chrono::Duration::seconds(900), task.next_due);
+
+        let event = scheduler.fail(
+            &mut task,
+            maintenance(Some(Duration::from_secs(86400))),
+            now,
+            &progress,
+        );
+        assert!(matches!(
+            event,
+            SyncEvent::Maintenance { retry_in, .. } if retry_in == Duration::from_secs(3600)
+        ));
+
+        let event = scheduler.fail(&mut task, maintenance(None), now, &progress);
+        assert!(matches!(
+            event,
+            SyncEvent::Maintenance { retry_in, .. } if retry_in == Duration::from_secs(480)
+        ));
+        assert_eq!(3, progress.lock().unwrap().report.failures);
+    }
+
+    #[test]
+    fn run_tasks_when_clock_reaches_them() {
+        let start = NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0);
+        let clock = ManualClock::new(start);
+        // nothing listens on port 1, so every synchronization fails right away
+        let client = DeviceClient::new(
+            &Username::from("username"),
+            "password",
+            &DeviceId::from("device"),
+        )
+        .with_base_url("http://127.0.0.1:1")
+        .unwrap()
+        .with_clock(clock.clone());
+        let scheduler = Scheduler::new(client, Duration::from_secs(60));
+        let progress = Mutex::new(Progress::default());
+        let (sender, events) = mpsc::channel();
+        let mut tasks = scheduler.tasks(start);
+
+        assert!(scheduler.run_due(&mut tasks, &progress, &sender));
+        assert_eq!(2, events.try_iter().count());
+        assert!(tasks
+            .iter()
+            .all(|task| task.next_due == start + chrono::Duration::seconds(120)));
+
+        clock.advance(chrono::Duration::seconds(60));
+        assert!(scheduler.run_due(&mut tasks, &progress, &sender));
+        assert_eq!(0, events.try_iter().count());
+
+        clock.advance(chrono::Duration::seconds(60));
+        assert!(scheduler.run_due(&mut tasks, &progress, &sender));
+        assert_eq!(2, events.try_iter().count());
+        assert_eq!(4, progress.lock().unwrap().report.failures);
+
+        drop(events);
+        clock.advance(chrono::Duration::seconds(240));
+        assert!(!scheduler.run_due(&mut tasks, &progress, &sender));
+    }
+
+    #[cfg(feature = "devices")]
+    #[test]
+    fn compare_and_equalize_devices() {
+        use super::{DeviceDiffReport, Equalization, FeedDiff};
+        use url::Url;
+
+        let url = |name: &str| Url::parse(&format!("http://example.com/{}.rss", name)).unwrap();
+        let mut report = DeviceDiffReport::default();
+        report.subscriptions.insert(
+            String::from("laptop"),
+            vec![url("a"), url("b")].into_iter().collect(),
+        );
+        report.subscriptions.insert(
+            String::from("phone"),
+            vec![url("b"), url("c")].into_iter().collect(),
+        );
+
+        assert!(!report.is_identical());
+        assert_eq!(
+            vec![
+                FeedDiff {
+                    url: url("a"),
+                    present_on: vec![String::from("laptop")],
+                    missing_on: vec![String::from("phone")],
+                },
+                FeedDiff {
+                    url: url("c"),
+                    present_on: vec![String::from("phone")],
+                    missing_on: vec![String::from("laptop")],
+                },
+            ],
+            report.differences()
+        );
+
+        let union = report.changes(Equalization::Union);
+        assert_eq!((vec![url("c")], vec![]), union["laptop"]);
+        assert_eq!((vec![url("a")], vec![]), union["phone"]);
+
+        let intersection = report.changes(Equalization::Intersection);
+        assert_eq!((vec![], vec![url("a")]), intersection["laptop"]);
+        assert_eq!((vec![], vec![url("c")]), intersection["phone"]);
+    }
+}
@@ -0,0 +1,220 @@
+//! Persistent [SyncStore]s
+//!
+//! [JsonFileStore] is always available. Stores backed by [sled](https://docs.rs/sled) and [SQLite](https://docs.rs/rusqlite) are enabled by the Cargo features `sled` and `rusqlite`, so applications can keep the [SyncState] next to their other data.
+
+use crate::error::Error;
+#[cfg(feature = "rusqlite")]
+use crate::sync::lock;
+use crate::sync::{SyncState, SyncStore};
+use std::fs;
+#[cfg(any(feature = "sled", feature = "rusqlite"))]
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "rusqlite")]
+use std::sync::Mutex;
+
+/// [SyncStore] saving the state as JSON file
+///
+/// The state is written to a temporary file next to the target first and then renamed, so an interrupted save keeps the previous state.
+///
+/// # Examples
+///
+/// ```no_run
+/// use mygpoclient::client::DeviceClient;
+/// use mygpoclient::sync::store::JsonFileStore;
+/// use mygpoclient::sync::Scheduler;
+/// use std::time::Duration;
+///
+/// # use mygpoclient::reference::{DeviceId, Username};
+/// # let username = Username::from(std::env::var("GPODDER_NET_USERNAME").unwrap());
+/// # let password = std::env::var("GPODDER_NET_PASSWORD").unwrap();
+/// # let deviceid = DeviceId::from(std::env::var("GPODDER_NET_DEVICEID").unwrap());
+/// #
+/// let client = DeviceClient::new(&username, &password, &deviceid);
+///
+/// let scheduler = Scheduler::new(client, Duration::from_secs(15 * 60))
+///     .with_store(JsonFileStore::new("sync-state.json"))?;
+/// #
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    /// Create [JsonFileStore] saving to `path`
+    ///
+    /// The file is created on the first [save](SyncStore::save).
+    pub fn new<P: Into<PathBuf>>(path: P) -> JsonFileStore {
+        JsonFileStore { path: path.into() }
+    }
+
+    /// Returns the path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl SyncStore for JsonFileStore {
+    fn load(&self) -> Result<SyncState, Error> {
+        match fs::read(&self.path) {
+            Ok(content) => Ok(serde_json::from_slice(&content)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(SyncState::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, state: &SyncState) -> Result<(), Error> {
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        fs::write(&temporary, serde_json::to_vec(state)?)?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+}
+
+/// [SyncStore] saving the state as JSON value of a single key in a [sled::Tree]
+#[cfg(feature = "sled")]
+#[derive(Debug, Clone)]
+pub struct SledStore {
+    tree: sled::Tree,
+}
+
+#[cfg(feature = "sled")]
+impl SledStore {
+    /// Key of the saved state
+    pub const KEY: &'static str = "mygpoclient.sync_state";
+
+    /// Create [SledStore] saving to `tree`, e.g. the default tree of a [sled::Db] or one opened with [open_tree](sled::Db::open_tree)
+    pub fn new(tree: sled::Tree) -> SledStore {
+        SledStore { tree }
+    }
+
+    /// Open or create the database at `path` and save to its default tree
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SledStore, Error> {
+        let db = sled::open(path).map_err(io::Error::from)?;
+        Ok(SledStore::new((*db).clone()))
+    }
+}
+
+#[cfg(feature = "sled")]
+impl SyncStore for SledStore {
+    fn load(&self) -> Result<SyncState, Error> {
+        match self.tree.get(Self::KEY).map_err(io::Error::from)? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(SyncState::default()),
+        }
+    }
+
+    fn save(&self, state: &SyncState) -> Result<(), Error> {
+        self.tree
+            .insert(Self::KEY, serde_json::to_vec(state)?)
+            .map_err(io::Error::from)?;
+        self.tree.flush().map_err(io::Error::from)?;
+        Ok(())
+    }
+}
+
+/// [SyncStore] saving the state as JSON in a single-row table of an SQLite database
+///
+/// The table [TABLE](SqliteStore::TABLE) is created if it doesn't exist yet.
+#[cfg(feature = "rusqlite")]
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "rusqlite")]
+impl SqliteStore {
+    /// Name of the table holding the saved state
+    pub const TABLE: &'static str = "mygpoclient_sync_state";
+
+    /// Create [SqliteStore] saving to the database of `connection`
+    pub fn new(connection: rusqlite::Connection) -> Result<SqliteStore, Error> {
+        connection
+            .execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (id INTEGER PRIMARY KEY CHECK (id = 0), state TEXT NOT NULL)",
+                Self::TABLE
+            ))
+            .map_err(sqlite_error)?;
+        Ok(SqliteStore {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Open or create the database at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteStore, Error> {
+        SqliteStore::new(rusqlite::Connection::open(path).map_err(sqlite_error)?)
+    }
+}
+
+#[cfg(feature = "rusqlite")]
+impl SyncStore for SqliteStore {
+    fn load(&self) -> Result<SyncState, Error> {
+        use rusqlite::OptionalExtension;
+
+        let state: Option<String> = lock(&self.connection)
+            .query_row(
+                &format!("SELECT state FROM {} WHERE id = 0", Self::TABLE),
+                rusqlite::NO_PARAMS,
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(sqlite_error)?;
+        match state {
+            Some(state) => Ok(serde_json::from_str(&state)?),
+            None => Ok(SyncState::default()),
+        }
+    }
+
+    fn save(&self, state: &SyncState) -> Result<(), Error> {
+        let state = serde_json::to_string(state)?;
+        lock(&self.connection)
+            .execute(
+                &format!(
+                    "INSERT OR REPLACE INTO {} (id, state) VALUES (0, ?1)",
+                    Self::TABLE
+                ),
+                rusqlite::params![state],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rusqlite")]
+fn sqlite_error(error: rusqlite::Error) -> io::Error {
+    io::Error::other(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonFileStore;
+    use crate::sync::{SyncState, SyncStore};
+    use chrono::NaiveDate;
+    use std::fs;
+
+    #[test]
+    fn json_file_store_roundtrip() {
+        let path = std::env::temp_dir().join(format!(
+            "mygpoclient-sync-state-{}.json",
+            std::process::id()
+        ));
+        let store = JsonFileStore::new(&path);
+        assert_eq!(SyncState::default(), store.load().unwrap());
+
+        let state = SyncState {
+            subscriptions_since: 12,
+            episode_actions_since: 34,
+            last_synced_subscriptions: Some(NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0)),
+            last_synced_episode_actions: None,
+        };
+        store.save(&state).unwrap();
+        let loaded = store.load();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(state, loaded.unwrap());
+    }
+}
@@ -0,0 +1,858 @@
+//! Fixtures and a fake client for testing applications without a server
+//!
+//! Sample data like [podcast] and [play_action] fills in all fields with plausible values, the `*_json` builders produce response bodies as sent by gpodder.net, e.g. for stubbing HTTP in integration tests.
+//!
+//! [FakeClient] keeps the state of an account in memory and implements the traits of the subscription, episode, device, settings, favorite and suggestion APIs as well as [RetrievePodcastData] and [RetrieveEpisodeData], so sync logic written against these traits can be unit tested without mocks. For end-to-end tests of the real clients, [FakeGpodderServer] serves the same state over HTTP on a local port.
+//!
+//! # Examples
+//!
+//! ```
+//! use mygpoclient::subscription::{GetAllSubscriptions, SubscriptionChanges};
+//! use mygpoclient::test_support::FakeClient;
+//! use url::Url;
+//!
+//! let phone = FakeClient::new("username", "phone");
+//! let laptop = phone.with_device("laptop");
+//!
+//! let url = Url::parse("http://example.com/feed.rss").unwrap();
+//! phone.upload_subscription_changes(&[url.clone()], &[])?;
+//!
+//! let subscriptions = laptop.get_all_subscriptions()?;
+//! assert_eq!(url, subscriptions[0].url);
+//! # Ok::<(), mygpoclient::error::Error>(())
+//! ```
+
+use crate::device::{
+    Device, DeviceType, DeviceUpdates, EpisodeUpdate, GetDeviceUpdates, ListDevices,
+    UpdateDeviceData,
+};
+use crate::directory::{Episode, RetrieveEpisodeData, RetrievePodcastData};
+use crate::episode::{
+    ActionList, EpisodeAction, EpisodeActionType, GetEpisodeActions, GetEpisodeActionsResponse,
+    UploadEpisodeActions, UploadEpisodeActionsResponse,
+};
+use crate::error::Error;
+use crate::favorite::GetFavoriteEpisodes;
+use crate::health::{CheckConnection, ConnectionStatus};
+use crate::reference::{DeviceId, EpisodeRef, Username};
+use crate::settings::{
+    GetAccountSettings, GetDeviceSettings, GetEpisodeSettings, GetPodcastSettings,
+    GetSettingsOfDevice, SaveAccountSettings, SaveDeviceSettings, SaveEpisodeSettings,
+    SavePodcastSettings, SaveSettingsOfDevice,
+};
+use crate::subscription::{
+    GetAllSubscriptions, GetPublicSubscriptions, GetSubscriptionChangesResponse, Podcast,
+    SubscriptionChanges, SubscriptionFormat, SubscriptionList, SubscriptionsOfDevice,
+    UploadSubscriptionChangesResponse, UrlRewrite,
+};
+use crate::suggestion::{RetrieveSuggestedPodcasts, Suggestion};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use url::Url;
+
+mod server;
+
+pub use server::FakeGpodderServer;
+
+/// Returns the fixed UTC time of all sample data
+pub fn sample_time() -> NaiveDateTime {
+    NaiveDate::from_ymd(2020, 1, 1).and_hms(9, 0, 0)
+}
+
+/// Returns a sample podcast with the given feed URL
+pub fn podcast(url: &Url) -> Podcast {
+    Podcast {
+        url: url.clone(),
+        title: String::from("Podcast"),
+        author: Some(String::from("Author")),
+        description: String::from("Description of the podcast"),
+        subscribers: 10,
+        subscribers_last_week: 9,
+        logo_url: None,
+        scaled_logo_url: None,
+        website: Some(url.clone()),
+        mygpo_link: url.clone(),
+    }
+}
+
+/// Returns a sample episode with the given feed URL of its podcast and media URL
+pub fn episode(podcast: &Url, url: &Url) -> Episode {
+    Episode {
+        title: String::from("Episode"),
+        url: url.clone(),
+        podcast_title: String::from("Podcast"),
+        podcast_url: podcast.clone(),
+        description: String::from("Description of the episode"),
+        website: None,
+        mygpo_link: None,
+        released: Some(sample_time()),
+    }
+}
+
+/// Returns a sample device with the given ID and no subscriptions
+pub fn device(id: &str, device_type: DeviceType) -> Device {
+    Device {
+        id: id.to_owned(),
+        caption: id.to_owned(),
+        device_type,
+        subscriptions: 0,
+        last_seen: None,
+        user_agent: None,
+    }
+}
+
+/// Returns a sample [Play](EpisodeActionType::Play) action stopped at `position` seconds of a one hour episode
+pub fn play_action(podcast: &Url, episode: &Url, position: u32) -> EpisodeAction {
+    EpisodeAction {
+        podcast: podcast.clone(),
+        episode: episode.clone(),
+        device: None,
+        action: EpisodeActionType::Play {
+            position,
+            started: Some(0),
+            total: Some(3600),
+        },
+        timestamp: Some(sample_time()),
+    }
+}
+
+/// Returns the response body of [get_subscription_changes](SubscriptionChanges::get_subscription_changes)
+pub fn subscription_changes_json(
+    add: &[Url],
+    remove: &[Url],
+    timestamp: u64,
+) -> Result<String, Error> {
+    Ok(serde_json::to_string(&GetSubscriptionChangesResponse {
+        timestamp,
+        add: add.to_vec(),
+        remove: remove.to_vec(),
+    })?)
+}
+
+/// Returns the response body of [upload_subscription_changes](SubscriptionChanges::upload_subscription_changes) and [upload_episode_actions](UploadEpisodeActions::upload_episode_actions)
+pub fn upload_response_json(timestamp: u64, update_urls: &[UrlRewrite]) -> Result<String, Error> {
+    Ok(serde_json::to_string(&UploadSubscriptionChangesResponse {
+        timestamp,
+        update_urls: update_urls.to_vec(),
+    })?)
+}
+
+/// Returns the response body of [get_episode_actions](GetEpisodeActions::get_episode_actions)
+pub fn episode_actions_json(actions: &[EpisodeAction], timestamp: u64) -> Result<String, Error> {
+    Ok(serde_json::to_string(&GetEpisodeActionsResponse {
+        actions: ActionList::from(actions.to_vec()),
+        timestamp,
+    })?)
+}
+
+/// Returns the response body of [list_devices](ListDevices::list_devices)
+pub fn devices_json(devices: &[Device]) -> Result<String, Error> {
+    Ok(serde_json::to_string(devices)?)
+}
+
+/// In-memory client of a single device of an account
+///
+/// Every change advances the timestamp of the account by one, starting at 0. Devices are registered on first use, like gpodder.net does. Clones and clients created with [with_device](FakeClient::with_device) share the same account.
+///
+/// Podcast and episode metadata is taken from [add_podcast](FakeClient::add_podcast) and [add_episode](FakeClient::add_episode), otherwise from [podcast] and [episode]. [Device updates](GetDeviceUpdates::get_device_updates) report the latest action of each episode of a subscribed podcast as [status](EpisodeUpdate::status).
+#[derive(Debug, Clone)]
+pub struct FakeClient {
+    username: String,
+    device_id: String,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    timestamp: u64,
+    offline: bool,
+    devices: BTreeMap<String, Device>,
+    subscriptions: BTreeMap<String, Vec<SubscriptionChange>>,
+    actions: Vec<(u64, EpisodeAction)>,
+    settings: HashMap<String, HashMap<String, String>>,
+    favorites: Vec<Episode>,
+    podcasts: HashMap<Url, Podcast>,
+    episodes: HashMap<EpisodeRef, Episode>,
+}
+
+#[derive(Debug)]
+struct SubscriptionChange {
+    timestamp: u64,
+    url: Url,
+    subscribed: bool,
+}
+
+impl FakeClient {
+    /// Create client of a new account with a single device
+    pub fn new(username: &str, device_id: &str) -> FakeClient {
+        let client = FakeClient {
+            username: username.to_owned(),
+            device_id: device_id.to_owned(),
+            state: Arc::new(Mutex::new(State::default())),
+        };
+        client.lock().register(device_id);
+        client
+    }
+
+    /// Create client of another device of the same account
+    pub fn with_device(&self, device_id: &str) -> FakeClient {
+        self.lock().register(device_id);
+        FakeClient {
+            username: self.username.clone(),
+            device_id: device_id.to_owned(),
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    /// Let all requests of the account fail with [Timeout](Error::Timeout), e.g. to test offline handling
+    pub fn set_offline(&self, offline: bool) {
+        self.lock().offline = offline;
+    }
+
+    /// Provide metadata of a podcast
+    pub fn add_podcast(&self, podcast: Podcast) {
+        self.lock().podcasts.insert(podcast.url.clone(), podcast);
+    }
+
+    /// Provide metadata of an episode
+    pub fn add_episode(&self, episode: Episode) {
+        self.lock()
+            .episodes
+            .insert(EpisodeRef::from(&episode), episode);
+    }
+
+    /// Mark an episode as favorite
+    pub fn add_favorite(&self, episode: Episode) {
+        self.lock().favorites.push(episode);
+    }
+
+    /// Returns the current timestamp of the account
+    pub fn timestamp(&self) -> u64 {
+        self.lock().timestamp
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // a panic while holding the lock can't leave the state in an inconsistent state
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the state unless the account is [offline](FakeClient::set_offline)
+    fn state(&self, endpoint: &str) -> Result<MutexGuard<'_, State>, Error> {
+        let state = self.lock();
+        if state.offline {
+            return Err(Error::Timeout {
+                endpoint: format!("/api/2/{}", endpoint),
+                elapsed: Duration::from_secs(0),
+            });
+        }
+        Ok(state)
+    }
+
+    fn subscriptions_endpoint(&self) -> String {
+        format!("subscriptions/{}/{}.json", self.username, self.device_id)
+    }
+
+    fn device_settings_scope(device_id: &str) -> String {
+        format!("device {}", device_id)
+    }
+
+    fn save_settings(
+        &self,
+        scope: String,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+    ) -> Result<HashMap<String, String>, Error> {
+        let mut state = self.state(&format!("settings/{}.json", self.username))?;
+        let settings = state.settings.entry(scope).or_default();
+        settings.extend(set);
+        for key in &remove {
+            settings.remove(key);
+        }
+        Ok(settings.clone())
+    }
+
+    fn settings(&self, scope: &str) -> Result<HashMap<String, String>, Error> {
+        let state = self.state(&format!("settings/{}.json", self.username))?;
+        Ok(state.settings.get(scope).cloned().unwrap_or_default())
+    }
+}
+
+impl State {
+    fn tick(&mut self) -> u64 {
+        self.timestamp += 1;
+        self.timestamp
+    }
+
+    fn register(&mut self, device_id: &str) {
+        self.devices
+            .entry(device_id.to_owned())
+            .or_insert_with(|| device(device_id, DeviceType::Other));
+    }
+
+    fn podcast(&self, url: &Url) -> Podcast {
+        self.podcasts
+            .get(url)
+            .cloned()
+            .unwrap_or_else(|| podcast(url))
+    }
+
+    fn episode(&self, key: &EpisodeRef) -> Episode {
+        self.episodes
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| episode(&key.podcast, &key.episode))
+    }
+
+    fn subscriptions_of(&self, device_id: &str) -> BTreeSet<Url> {
+        self.changes_of(device_id, 0)
+            .into_iter()
+            .filter(|(_, subscribed)| *subscribed)
+            .map(|(url, _)| url)
+            .collect()
+    }
+
+    fn all_subscriptions(&self) -> BTreeSet<Url> {
+        self.devices
+            .keys()
+            .flat_map(|device_id| self.subscriptions_of(device_id))
+            .collect()
+    }
+
+    /// Returns the latest change of each URL after `since`
+    fn changes_of(&self, device_id: &str, since: u64) -> BTreeMap<Url, bool> {
+        self.subscriptions
+            .get(device_id)
+            .into_iter()
+            .flatten()
+            .filter(|change| change.timestamp > since)
+            .map(|change| (change.url.clone(), change.subscribed))
+            .collect()
+    }
+
+    fn subscription_changes(&self, device_id: &str, since: u64) -> GetSubscriptionChangesResponse {
+        let changes = self.changes_of(device_id, since);
+        GetSubscriptionChangesResponse {
+            timestamp: self.timestamp,
+            add: filter_keys(&changes, true),
+            remove: filter_keys(&changes, false),
+        }
+    }
+
+    fn change_subscriptions(&mut self, device_id: &str, add: &[Url], remove: &[Url]) -> u64 {
+        self.register(device_id);
+        let timestamp = self.tick();
+        let log = self.subscriptions.entry(device_id.to_owned()).or_default();
+        for (urls, subscribed) in &[(remove, false), (add, true)] {
+            log.extend(urls.iter().map(|url| SubscriptionChange {
+                timestamp,
+                url: url.clone(),
+                subscribed: *subscribed,
+            }));
+        }
+        timestamp
+    }
+
+    fn in_format(&self, urls: BTreeSet<Url>, format: SubscriptionFormat) -> SubscriptionList {
+        match format {
+            SubscriptionFormat::Json => {
+                SubscriptionList::Podcasts(urls.iter().map(|url| self.podcast(url)).collect())
+            }
+            SubscriptionFormat::Opml => SubscriptionList::Urls(urls.into_iter().collect()),
+            SubscriptionFormat::Txt | SubscriptionFormat::Xml => SubscriptionList::Raw(
+                urls.iter()
+                    .map(|url| format!("{}\n", url))
+                    .collect::<String>(),
+            ),
+        }
+    }
+}
+
+fn filter_keys(changes: &BTreeMap<Url, bool>, subscribed: bool) -> Vec<Url> {
+    changes
+        .iter()
+        .filter(|(_, value)| **value == subscribed)
+        .map(|(url, _)| url.clone())
+        .collect()
+}
+
+impl GetAllSubscriptions for FakeClient {
+    fn get_all_subscriptions(&self) -> Result<Vec<Podcast>, Error> {
+        let state = self.state(&format!("subscriptions/{}.json", self.username))?;
+        Ok(state
+            .all_subscriptions()
+            .iter()
+            .map(|url| state.podcast(url))
+            .collect())
+    }
+
+    fn get_all_subscriptions_in_format(
+        &self,
+        format: SubscriptionFormat,
+    ) -> Result<SubscriptionList, Error> {
+        let state = self.state(&format!("subscriptions/{}.{}", self.username, format))?;
+        Ok(state.in_format(state.all_subscriptions(), format))
+    }
+}
+
+impl SubscriptionsOfDevice for FakeClient {
+    fn get_subscriptions_of_device(&self) -> Result<Vec<Url>, Error> {
+        let state = self.state(&self.subscriptions_endpoint())?;
+        Ok(state
+            .subscriptions_of(&self.device_id)
+            .into_iter()
+            .collect())
+    }
+
+    fn get_subscriptions_of_device_in_format(
+        &self,
+        format: SubscriptionFormat,
+    ) -> Result<SubscriptionList, Error> {
+        let state = self.state(&self.subscriptions_endpoint())?;
+        Ok(state.in_format(state.subscriptions_of(&self.device_id), format))
+    }
+
+    fn upload_subscriptions_of_device(&self, subscriptions: &[Url]) -> Result<(), Error> {
+        let mut state = self.state(&self.subscriptions_endpoint())?;
+        let current = state.subscriptions_of(&self.device_id);
+        let target: BTreeSet<Url> = subscriptions.iter().cloned().collect();
+        let add: Vec<Url> = target.difference(&current).cloned().collect();
+        let remove: Vec<Url> = current.difference(&target).cloned().collect();
+        state.change_subscriptions(&self.device_id, &add, &remove);
+        Ok(())
+    }
+
+    fn upload_subscriptions_of_device_if_unmodified(
+        &self,
+        subscriptions: &[Url],
+        timestamp: u64,
+    ) -> Result<(), Error> {
+        let changes = self.get_subscription_changes(timestamp)?;
+        if !changes.add.is_empty() || !changes.remove.is_empty() {
+            return Err(Error::ConcurrentModification(changes));
+        }
+        self.upload_subscriptions_of_device(subscriptions)
+    }
+}
+
+impl GetPublicSubscriptions for FakeClient {
+    fn get_public_subscriptions(&self, username: &Username) -> Result<Vec<Url>, Error> {
+        let state = self.state(&format!("subscriptions/{}.json", username))?;
+        if username.as_str() != self.username {
+            return Err(Error::SubscriptionsNotPublic(username.to_string()));
+        }
+        Ok(state.all_subscriptions().into_iter().collect())
+    }
+}
+
+impl SubscriptionChanges for FakeClient {
+    fn upload_subscription_changes(
+        &self,
+        add: &[Url],
+        remove: &[Url],
+    ) -> Result<UploadSubscriptionChangesResponse, Error> {
+        let endpoint = format!("subscriptions/{}/{}.json", self.username, self.device_id);
+        let mut state = self.state(&endpoint)?;
+        if add.iter().any(|url| remove.contains(url)) {
+            return Err(Error::UnexpectedStatus {
+                url: format!("/api/2/{}", endpoint),
+                status: 400,
+            });
+        }
+        Ok(UploadSubscriptionChangesResponse {
+            timestamp: state.change_subscriptions(&self.device_id, add, remove),
+            update_urls: Vec::new(),
+        })
+    }
+
+    fn get_subscription_changes(
+        &self,
+        timestamp: u64,
+    ) -> Result<GetSubscriptionChangesResponse, Error> {
+        let state = self.state(&self.subscriptions_endpoint())?;
+        Ok(state.subscription_changes(&self.device_id, timestamp))
+    }
+}
+
+impl UploadEpisodeActions for FakeClient {
+    fn upload_episode_actions(
+        &self,
+        actions: &[EpisodeAction],
+    ) -> Result<UploadEpisodeActionsResponse, Error> {
+        let mut state = self.state(&format!("episodes/{}.json", self.username))?;
+        let timestamp = state.tick();
+        for action in actions {
+            if let Some(device_id) = &action.device {
+                state.register(device_id);
+            }
+            state.actions.push((timestamp, action.clone()));
+        }
+        Ok(UploadEpisodeActionsResponse {
+            timestamp,
+            update_urls: Vec::new(),
+        })
+    }
+}
+
+impl GetEpisodeActions for FakeClient {
+    fn get_episode_actions(
+        &self,
+        podcast: Option<Url>,
+        since: Option<u64>,
+        aggregated: bool,
+    ) -> Result<GetEpisodeActionsResponse, Error> {
+        let state = self.state(&format!("episodes/{}.json", self.username))?;
+        let mut actions: Vec<EpisodeAction> = state
+            .actions
+            .iter()
+            .filter(|(timestamp, action)| {
+                *timestamp > since.unwrap_or(0)
+                    && podcast.as_ref().is_none_or(|url| action.podcast == *url)
+            })
+            .map(|(_, action)| action.clone())
+            .collect();
+        if aggregated {
+            let mut latest: HashMap<EpisodeRef, usize> = HashMap::new();
+            for (index, action) in actions.iter().enumerate() {
+                latest.insert(EpisodeRef::from(action), index);
+            }
+            actions = actions
+                .into_iter()
+                .enumerate()
+                .filter(|(index, action)| latest.get(&EpisodeRef::from(action)) == Some(index))
+                .map(|(_, action)| action)
+                .collect();
+        }
+        Ok(GetEpisodeActionsResponse {
+            actions: ActionList::from(actions),
+            timestamp: state.timestamp,
+        })
+    }
+}
+
+impl UpdateDeviceData for FakeClient {
+    fn update_device_data<T: Into<Option<String>>, U: Into<Option<DeviceType>>>(
+        &self,
+        caption: T,
+        device_type: U,
+    ) -> Result<(), Error> {
+        let mut state = self.state(&format!(
+            "devices/{}/{}.json",
+            self.username, self.device_id
+        ))?;
+        state.register(&self.device_id);
+        if let Some(device) = state.devices.get_mut(&self.device_id) {
+            if let Some(caption) = caption.into() {
+                device.caption = caption;
+            }
+            if let Some(device_type) = device_type.into() {
+                device.device_type = device_type;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ListDevices for FakeClient {
+    fn list_devices(&self) -> Result<Vec<Device>, Error> {
+        let state = self.state(&format!("devices/{}.json", self.username))?;
+        Ok(state
+            .devices
+            .values()
+            .map(|device| Device {
+                subscriptions: u32::try_from(state.subscriptions_of(&device.id).len())
+                    .unwrap_or(u32::MAX),
+                ..device.clone()
+            })
+            .collect())
+    }
+}
+
+impl GetDeviceUpdates for FakeClient {
+    fn get_device_updates(
+        &self,
+        since: u64,
+        include_actions: bool,
+    ) -> Result<DeviceUpdates, Error> {
+        let state = self.state(&format!(
+            "updates/{}/{}.json",
+            self.username, self.device_id
+        ))?;
+        let changes = state.subscription_changes(&self.device_id, since);
+        let mut updates: BTreeMap<EpisodeRef, EpisodeActionType> = BTreeMap::new();
+        if include_actions {
+            let subscriptions = state.subscriptions_of(&self.device_id);
+            for (_, action) in state.actions.iter().filter(|(timestamp, action)| {
+                *timestamp > since && subscriptions.contains(&action.podcast)
+            }) {
+                updates.insert(EpisodeRef::from(action), action.action.clone());
+            }
+        }
+        Ok(DeviceUpdates {
+            add: changes.add.iter().map(|url| state.podcast(url)).collect(),
+            rem: changes.remove,
+            updates: updates
+                .into_iter()
+                .map(|(key, status)| EpisodeUpdate {
+                    episode: state.episode(&key),
+                    status: Some(status),
+                })
+                .collect(),
+            timestamp: changes.timestamp,
+        })
+    }
+}
+
+impl SaveAccountSettings for FakeClient {
+    fn save_account_settings(
+        &self,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+    ) -> Result<HashMap<String, String>, Error> {
+        self.save_settings(String::from("account"), set, remove)
+    }
+}
+
+impl SaveDeviceSettings for FakeClient {
+    fn save_device_settings(
+        &self,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+    ) -> Result<HashMap<String, String>, Error> {
+        self.save_settings(Self::device_settings_scope(&self.device_id), set, remove)
+    }
+}
+
+impl SaveSettingsOfDevice for FakeClient {
+    fn save_settings_of_device(
+        &self,
+        device_id: &DeviceId,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+    ) -> Result<HashMap<String, String>, Error> {
+        self.save_settings(Self::device_settings_scope(device_id.as_str()), set, remove)
+    }
+}
+
+impl SavePodcastSettings for FakeClient {
+    fn save_podcast_settings(
+        &self,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+        podcast: Url,
+    ) -> Result<HashMap<String, String>, Error> {
+        self.save_settings(format!("podcast {}", podcast), set, remove)
+    }
+}
+
+impl SaveEpisodeSettings for FakeClient {
+    fn save_episode_settings(
+        &self,
+        set: HashMap<String, String>,
+        remove: Vec<String>,
+        episode: EpisodeRef,
+    ) -> Result<HashMap<String, String>, Error> {
+        self.save_settings(
+            format!("episode {} {}", episode.podcast, episode.episode),
+            set,
+            remove,
+        )
+    }
+}
+
+impl GetAccountSettings for FakeClient {
+    fn get_account_settings(&self) -> Result<HashMap<String, String>, Error> {
+        self.settings("account")
+    }
+}
+
+impl GetDeviceSettings for FakeClient {
+    fn get_device_settings(&self) -> Result<HashMap<String, String>, Error> {
+        self.settings(&Self::device_settings_scope(&self.device_id))
+    }
+}
+
+impl GetSettingsOfDevice for FakeClient {
+    fn get_settings_of_device(
+        &self,
+        device_id: &DeviceId,
+    ) -> Result<HashMap<String, String>, Error> {
+        self.settings(&Self::device_settings_scope(device_id.as_str()))
+    }
+}
+
+impl GetPodcastSettings for FakeClient {
+    fn get_podcast_settings(&self, podcast: Url) -> Result<HashMap<String, String>, Error> {
+        self.settings(&format!("podcast {}", podcast))
+    }
+}
+
+impl GetEpisodeSettings for FakeClient {
+    fn get_episode_settings(&self, episode: EpisodeRef) -> Result<HashMap<String, String>, Error> {
+        self.settings(&format!("episode {} {}", episode.podcast, episode.episode))
+    }
+}
+
+impl GetFavoriteEpisodes for FakeClient {
+    fn get_favorite_episodes(&self) -> Result<Vec<Episode>, Error> {
+        let state = self.state(&format!("favorites/{}.json", self.username))?;
+        Ok(state.favorites.clone())
+    }
+}
+
+impl RetrievePodcastData for FakeClient {
+    fn retrieve_podcast_data(&self, url: Url) -> Result<Podcast, Error> {
+        let state = self.state("data/podcast.json")?;
+        state
+            .podcasts
+            .get(&url)
+            .cloned()
+            .ok_or_else(|| Error::UnexpectedStatus {
+                url: format!("/api/2/data/podcast.json?url={}", url),
+                status: 404,
+            })
+    }
+}
+
+impl RetrieveEpisodeData for FakeClient {
+    fn retrieve_episode_data(&self, episode: EpisodeRef) -> Result<Episode, Error> {
+        let state = self.state("data/episode.json")?;
+        state
+            .episodes
+            .get(&episode)
+            .cloned()
+            .ok_or_else(|| Error::UnexpectedStatus {
+                url: format!(
+                    "/api/2/data/episode.json?podcast={}&url={}",
+                    episode.podcast, episode.episode
+                ),
+                status: 404,
+            })
+    }
+}
+
+/// Suggests the [added podcasts](FakeClient::add_podcast) which aren't subscribed on any device, most subscribed first
+impl RetrieveSuggestedPodcasts for FakeClient {
+    fn retrieve_suggested_podcasts(&self, max_results: u8) -> Result<Vec<Suggestion>, Error> {
+        let state = self.state(&format!("suggestions/{}.json", max_results))?;
+        let subscriptions = state.all_subscriptions();
+        let mut podcasts: Vec<&Podcast> = state
+            .podcasts
+            .values()
+            .filter(|podcast| !subscriptions.contains(&podcast.url))
+            .collect();
+        podcasts.sort_by(|a, b| b.subscribers.cmp(&a.subscribers).then(a.url.cmp(&b.url)));
+        Ok(podcasts
+            .into_iter()
+            .take(usize::from(max_results))
+            .map(|podcast| Suggestion {
+                website: podcast
+                    .website
+                    .clone()
+                    .unwrap_or_else(|| podcast.mygpo_link.clone()),
+                mygpo_link: podcast.mygpo_link.clone(),
+                description: podcast.description.clone(),
+                subscribers: podcast.subscribers,
+                title: podcast.title.clone(),
+                url: podcast.url.clone(),
+                subscribers_last_week: podcast.subscribers_last_week,
+                logo_url: podcast.logo_url.clone(),
+            })
+            .collect())
+    }
+}
+
+impl CheckConnection for FakeClient {
+    fn check_connection(&self) -> ConnectionStatus {
+        match self.state(&format!("devices/{}.json", self.username)) {
+            Ok(_) => ConnectionStatus::Ok,
+            Err(_) => ConnectionStatus::NetworkError,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{episode, play_action, subscription_changes_json, FakeClient};
+    use crate::device::{GetDeviceUpdates, ListDevices};
+    use crate::episode::{GetEpisodeActions, UploadEpisodeActions};
+    use crate::error::Error;
+    use crate::subscription::{SubscriptionChanges, SubscriptionsOfDevice};
+    use url::Url;
+
+    #[test]
+    fn sync_subscriptions_and_actions_between_devices() {
+        let phone = FakeClient::new("username", "phone");
+        let laptop = phone.with_device("laptop");
+        let feed = Url::parse("http://example.com/feed.rss").unwrap();
+        let media = Url::parse("http://example.com/episode.mp3").unwrap();
+        phone.add_episode(episode(&feed, &media));
+
+        laptop
+            .upload_subscriptions_of_device(std::slice::from_ref(&feed))
+            .unwrap();
+        let since = phone.timestamp();
+        phone
+            .upload_episode_actions(&[play_action(&feed, &media, 60)])
+            .unwrap();
+        phone
+            .upload_episode_actions(&[play_action(&feed, &media, 120)])
+            .unwrap();
+
+        assert_eq!(
+            vec![feed.clone()],
+            laptop.get_subscriptions_of_device().unwrap()
+        );
+        assert!(phone.get_subscription_changes(0).unwrap().add.is_empty());
+        assert_eq!(
+            2,
+            phone
+                .get_episode_actions(None, Some(since), false)
+                .unwrap()
+                .actions
+                .len()
+        );
+        assert_eq!(
+            1,
+            phone
+                .get_episode_actions(None, Some(since), true)
+                .unwrap()
+                .actions
+                .len()
+        );
+        let updates = laptop.get_device_updates(since, true).unwrap();
+        assert_eq!(1, updates.updates.len());
+        assert_eq!(media, updates.updates[0].episode.url);
+        let devices = phone.list_devices().unwrap();
+        assert_eq!(
+            vec!["laptop", "phone"],
+            devices
+                .iter()
+                .map(|device| device.id.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, devices[0].subscriptions);
+
+        laptop.set_offline(true);
+        match phone.get_subscriptions_of_device() {
+            Err(Error::Timeout { endpoint, .. }) => {
+                assert_eq!("/api/2/subscriptions/username/phone.json", endpoint)
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn build_response_json() {
+        let feed = Url::parse("http://example.com/feed.rss").unwrap();
+
+        assert_eq!(
+            "{\"timestamp\":12347,\"add\":[\"http://example.com/feed.rss\"],\"remove\":[]}",
+            subscription_changes_json(&[feed], &[], 12347).unwrap()
+        );
+    }
+}
@@ -0,0 +1,696 @@
+//! Local HTTP server speaking the gpodder.net API
+
+use super::{FakeClient, State};
+use crate::client::{AuthenticatedClient, DeviceClient};
+use crate::device::{DeviceData, GetDeviceUpdates, ListDevices, UpdateDeviceData};
+use crate::episode::{EpisodeAction, GetEpisodeActions, UploadEpisodeActions};
+use crate::error::Error;
+use crate::favorite::GetFavoriteEpisodes;
+use crate::reference::{DeviceId, EpisodeRef, Username};
+use crate::settings::{
+    GetAccountSettings, GetEpisodeSettings, GetPodcastSettings, GetSettingsOfDevice,
+    SaveAccountSettings, SaveEpisodeSettings, SavePodcastSettings, SaveSettingsOfDevice,
+    SaveSettingsRequest,
+};
+use crate::subscription::{
+    GetAllSubscriptions, SubscriptionChanges, SubscriptionFormat, SubscriptionList,
+    SubscriptionsOfDevice, UploadSubscriptionChangesRequest,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use url::Url;
+
+/// In-memory server implementing the gpodder.net API on a local port, for end-to-end tests
+///
+/// Each user has the state of a [FakeClient]. Requests are answered like gpodder.net does for the devices, subscriptions, episode actions, settings and favorites APIs; all other endpoints respond with `404 Not Found`. Requests must carry the Basic credentials of a user [added](FakeGpodderServer::add_user) before and may only access the data of this user.
+///
+/// Each connection is handled by its own thread and closed after one response. The server stops when dropped.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::subscription::{GetAllSubscriptions, SubscriptionChanges};
+/// use mygpoclient::test_support::FakeGpodderServer;
+/// use url::Url;
+///
+/// let server = FakeGpodderServer::start()?;
+/// server.add_user("username", "password");
+///
+/// let phone = server.device_client("username", "password", "phone")?;
+/// let url = Url::parse("http://example.com/feed.rss").unwrap();
+/// phone.upload_subscription_changes(&[url.clone()], &[])?;
+///
+/// let laptop = server.device_client("username", "password", "laptop")?;
+/// assert_eq!(url, laptop.get_all_subscriptions()?[0].url);
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct FakeGpodderServer {
+    address: SocketAddr,
+    accounts: Arc<Mutex<HashMap<String, Account>>>,
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug, Clone)]
+struct Account {
+    password: String,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    url: Url,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl FakeGpodderServer {
+    /// Start a server without users on a free port of `127.0.0.1`
+    pub fn start() -> Result<FakeGpodderServer, Error> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let accounts: Arc<Mutex<HashMap<String, Account>>> = Arc::default();
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let accounts = Arc::clone(&accounts);
+            let stopped = Arc::clone(&stopped);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let accounts = Arc::clone(&accounts);
+                        thread::spawn(move || handle(stream, &accounts));
+                    }
+                }
+            })
+        };
+
+        Ok(FakeGpodderServer {
+            address,
+            accounts,
+            stopped,
+            thread: Some(thread),
+        })
+    }
+
+    /// Returns the base URL of the server, e.g. for [with_base_url](crate::client::PublicClient::with_base_url)
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Add a user without devices, replacing an existing user of the same name
+    pub fn add_user(&self, username: &str, password: &str) {
+        lock(&self.accounts).insert(
+            username.to_owned(),
+            Account {
+                password: password.to_owned(),
+                state: Arc::default(),
+            },
+        );
+    }
+
+    /// Returns a [FakeClient] sharing the state of the given device of a user, e.g. to prepare or inspect data
+    ///
+    /// Returns `None` if the user wasn't [added](FakeGpodderServer::add_user).
+    pub fn fake_client(&self, username: &str, device_id: &str) -> Option<FakeClient> {
+        let account = lock(&self.accounts).get(username).cloned()?;
+        Some(account.fake_client(username, device_id))
+    }
+
+    /// Create [AuthenticatedClient] talking to this server
+    pub fn authenticated_client(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<AuthenticatedClient, Error> {
+        AuthenticatedClient::new(username, password).with_base_url(&self.base_url())
+    }
+
+    /// Create [DeviceClient] talking to this server
+    pub fn device_client(
+        &self,
+        username: &str,
+        password: &str,
+        device_id: &str,
+    ) -> Result<DeviceClient, Error> {
+        DeviceClient::new(
+            &Username::from(username),
+            password,
+            &DeviceId::from(device_id),
+        )
+        .with_base_url(&self.base_url())
+    }
+}
+
+impl Drop for FakeGpodderServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // wake up the listener blocked in accept
+        let _ = TcpStream::connect(self.address);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Account {
+    /// Returns a [FakeClient] of the device, registering it unless `device_id` is empty
+    fn fake_client(&self, username: &str, device_id: &str) -> FakeClient {
+        if !device_id.is_empty() {
+            lock(&self.state).register(device_id);
+        }
+        FakeClient {
+            username: username.to_owned(),
+            device_id: device_id.to_owned(),
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // a panic while holding the lock can't leave the state in an inconsistent state
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn handle(mut stream: TcpStream, accounts: &Mutex<HashMap<String, Account>>) {
+    let response = match read_request(&stream) {
+        Ok(request) => respond(&request, accounts),
+        Err(_) => Response::status(400),
+    };
+    let challenge = if response.status == 401 {
+        "WWW-Authenticate: Basic realm=\"gpodder.net\"\r\n"
+    } else {
+        ""
+    };
+    let message = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len(),
+        challenge,
+        response.body
+    );
+    // the client may have given up already, so there's nobody to report errors to
+    let _ = stream
+        .write_all(message.as_bytes())
+        .and_then(|_| stream.flush());
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed request");
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or_else(invalid)?.to_owned();
+    let target = parts.next().ok_or_else(invalid)?;
+    let url = Url::parse(&format!("http://localhost{}", target)).map_err(|_| invalid())?;
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(index) = line.find(':') {
+            let value = line[index + 1..].trim();
+            match line[..index].to_ascii_lowercase().as_str() {
+                "authorization" => authorization = Some(value.to_owned()),
+                "content-length" => content_length = value.parse().map_err(|_| invalid())?,
+                _ => {}
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Request {
+        method,
+        url,
+        authorization,
+        body,
+    })
+}
+
+fn respond(request: &Request, accounts: &Mutex<HashMap<String, Account>>) -> Response {
+    let segments: Vec<&str> = match request.url.path_segments() {
+        Some(segments) => segments.collect(),
+        None => return Response::status(404),
+    };
+    let (area, path) = match segments.as_slice() {
+        ["api", "2", area, path @ ..] => (*area, path),
+        [area, path @ ..] => (*area, path),
+        [] => return Response::status(404),
+    };
+    // the username is always the first path segment after the area
+    let username = match path.first() {
+        Some(segment) => split_extension(segment).0,
+        None => return Response::status(404),
+    };
+
+    let account = match lock(accounts).get(username) {
+        Some(account) if authorized(request, username, &account.password) => account.clone(),
+        _ => return Response::status(401),
+    };
+    let device_id = match area {
+        "devices" | "updates" | "subscriptions" => {
+            path.get(1).map_or("", |segment| split_extension(segment).0)
+        }
+        _ => "",
+    };
+    let client = account.fake_client(username, device_id);
+    let query: HashMap<String, String> = request.url.query_pairs().into_owned().collect();
+    let is_simple_api = segments.first() != Some(&"api");
+
+    let result = match (request.method.as_str(), area, path.len(), is_simple_api) {
+        ("GET", "devices", 1, false) => json(client.list_devices()),
+        ("POST", "devices", 2, false) => parse::<DeviceData>(&request.body).and_then(|data| {
+            client.update_device_data(data.caption, data.device_type)?;
+            Ok(Response::json(String::new()))
+        }),
+        ("GET", "updates", 2, false) => json(client.get_device_updates(
+            query_parameter(&query, "since").unwrap_or(0),
+            query_parameter(&query, "include_actions").unwrap_or(false),
+        )),
+        ("GET", "subscriptions", 1, true) => {
+            subscription_list(client.get_all_subscriptions_in_format(subscription_format(path)))
+        }
+        ("GET", "subscriptions", 2, true) => subscription_list(
+            client.get_subscriptions_of_device_in_format(subscription_format(path)),
+        ),
+        ("PUT", "subscriptions", 2, true) => parse::<Vec<Url>>(&request.body).and_then(|urls| {
+            client.upload_subscriptions_of_device(&urls)?;
+            Ok(Response::json(String::new()))
+        }),
+        ("GET", "subscriptions", 2, false) => {
+            json(client.get_subscription_changes(query_parameter(&query, "since").unwrap_or(0)))
+        }
+        ("POST", "subscriptions", 2, false) => {
+            parse::<UploadSubscriptionChangesRequest>(&request.body).and_then(|changes| {
+                json(client.upload_subscription_changes(&changes.add, &changes.remove))
+            })
+        }
+        ("GET", "episodes", 1, false) => json(client.get_episode_actions(
+            query.get("podcast").and_then(|url| Url::parse(url).ok()),
+            query_parameter(&query, "since"),
+            query_parameter(&query, "aggregated").unwrap_or(false),
+        )),
+        ("POST", "episodes", 1, false) => parse::<Vec<EpisodeAction>>(&request.body)
+            .and_then(|actions| json(client.upload_episode_actions(&actions))),
+        ("GET", "settings", 2, false) | ("POST", "settings", 2, false) => {
+            settings(&client, request, &query, split_extension(path[1]).0)
+        }
+        ("GET", "favorites", 1, false) => json(client.get_favorite_episodes()),
+        _ => Ok(Response::status(404)),
+    };
+    result.unwrap_or_else(|error| Response::error(&error))
+}
+
+fn settings(
+    client: &FakeClient,
+    request: &Request,
+    query: &HashMap<String, String>,
+    scope: &str,
+) -> Result<Response, Error> {
+    let save = if request.method == "POST" {
+        Some(parse::<SaveSettingsRequest>(&request.body)?)
+    } else {
+        None
+    };
+    let url = |name: &str| -> Result<Url, Error> {
+        Ok(Url::parse(query.get(name).map_or("", String::as_str))?)
+    };
+
+    match (scope, save) {
+        ("account", None) => json(client.get_account_settings()),
+        ("account", Some(save)) => json(client.save_account_settings(save.set, save.remove)),
+        ("device", save) => {
+            let device_id = DeviceId::from(query.get("device").map_or("", String::as_str));
+            match save {
+                None => json(client.get_settings_of_device(&device_id)),
+                Some(save) => {
+                    json(client.save_settings_of_device(&device_id, save.set, save.remove))
+                }
+            }
+        }
+        ("podcast", None) => json(client.get_podcast_settings(url("podcast")?)),
+        ("podcast", Some(save)) => {
+            json(client.save_podcast_settings(save.set, save.remove, url("podcast")?))
+        }
+        ("episode", save) => {
+            let episode = EpisodeRef::new(url("podcast")?, url("episode")?);
+            match save {
+                None => json(client.get_episode_settings(episode)),
+                Some(save) => json(client.save_episode_settings(save.set, save.remove, episode)),
+            }
+        }
+        _ => Ok(Response::status(404)),
+    }
+}
+
+/// Returns the name and the extension of the last segment of a path, e.g. `("username", "json")`
+fn split_extension(segment: &str) -> (&str, &str) {
+    match segment.rfind('.') {
+        Some(index) => (&segment[..index], &segment[index + 1..]),
+        None => (segment, ""),
+    }
+}
+
+fn subscription_format(path: &[&str]) -> SubscriptionFormat {
+    match path.last().map(|segment| split_extension(segment).1) {
+        Some("opml") => SubscriptionFormat::Opml,
+        Some("txt") => SubscriptionFormat::Txt,
+        Some("xml") => SubscriptionFormat::Xml,
+        _ => SubscriptionFormat::Json,
+    }
+}
+
+fn query_parameter<T: FromStr>(query: &HashMap<String, String>, name: &str) -> Option<T> {
+    query.get(name).and_then(|value| value.parse().ok())
+}
+
+fn authorized(request: &Request, username: &str, password: &str) -> bool {
+    request
+        .authorization
+        .as_deref()
+        .and_then(|authorization| authorization.strip_prefix("Basic "))
+        .and_then(decode_base64)
+        .is_some_and(|credentials| credentials == format!("{}:{}", username, password).into_bytes())
+}
+
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut bits: u32 = 0;
+    let mut count = 0;
+    for byte in input.trim_end_matches('=').bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        bits = (bits << 6) | u32::from(value);
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            output.push(((bits >> count) & 0xff) as u8);
+            bits &= (1 << count) - 1;
+        }
+    }
+    Some(output)
+}
+
+fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, Error> {
+    serde_json::from_slice(body).map_err(|_| Error::UnexpectedStatus {
+        url: String::from("request body"),
+        status: 400,
+    })
+}
+
+fn json<T: Serialize>(result: Result<T, Error>) -> Result<Response, Error> {
+    Ok(Response::json(serde_json::to_string(&result?)?))
+}
+
+fn subscription_list(result: Result<SubscriptionList, Error>) -> Result<Response, Error> {
+    Ok(match result? {
+        SubscriptionList::Podcasts(podcasts) => Response::json(serde_json::to_string(&podcasts)?),
+        SubscriptionList::Urls(urls) => Response {
+            status: 200,
+            content_type: "text/x-opml",
+            body: opml(&urls),
+        },
+        SubscriptionList::Raw(text) => Response {
+            status: 200,
+            content_type: "text/plain",
+            body: text,
+        },
+    })
+}
+
+fn opml(urls: &[Url]) -> String {
+    let outlines: String = urls
+        .iter()
+        .map(|url| {
+            format!(
+                "<outline type=\"rss\" xmlUrl=\"{}\" />",
+                url.as_str().replace('&', "&amp;").replace('"', "&quot;")
+            )
+        })
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?><opml version=\"2.0\"><head><title>Subscriptions</title></head><body>{}</body></opml>",
+        outlines
+    )
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+impl Response {
+    fn json(body: String) -> Response {
+        Response {
+            status: 200,
+            content_type: "application/json",
+            body,
+        }
+    }
+
+    fn status(status: u16) -> Response {
+        Response {
+            status,
+            content_type: "text/plain",
+            body: String::from(reason(status)),
+        }
+    }
+
+    fn error(error: &Error) -> Response {
+        Response::status(match error {
+            Error::UnexpectedStatus { status, .. } => *status,
+            Error::ConcurrentModification(_) => 409,
+            Error::SubscriptionsNotPublic(_) => 401,
+            Error::Timeout { .. } => 503,
+            Error::UrlError(_) => 400,
+            _ => 500,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base64, FakeGpodderServer};
+    use crate::client::{AuthenticationMode, ServerProfile};
+    use crate::debug::DebugRecorder;
+    use crate::device::{Deregistration, ListDevices};
+    use crate::episode::{GetEpisodeActions, UploadEpisodeActions};
+    use crate::settings::{GetAccountSettings, SaveAccountSettings, PUBLIC_SUBSCRIPTIONS_KEY};
+    use crate::subscription::{GetAllSubscriptions, SubscriptionChanges, SubscriptionsOfDevice};
+    use crate::test_support::play_action;
+    use std::collections::HashMap;
+    use url::Url;
+
+    #[test]
+    fn decode_basic_credentials() {
+        assert_eq!(
+            Some(b"username:password".to_vec()),
+            decode_base64("dXNlcm5hbWU6cGFzc3dvcmQ=")
+        );
+        assert_eq!(None, decode_base64("not base64!"));
+    }
+
+    #[test]
+    fn serve_real_endpoints() {
+        let server = FakeGpodderServer::start().unwrap();
+        server.add_user("username", "password");
+        let phone = server
+            .device_client("username", "password", "phone")
+            .unwrap();
+        let laptop = server
+            .device_client("username", "password", "laptop")
+            .unwrap();
+        let feed = Url::parse("http://example.com/feed.rss").unwrap();
+        let media = Url::parse("http://example.com/episode.mp3").unwrap();
+
+        let since = laptop.get_subscription_changes(0).unwrap().timestamp;
+        phone
+            .upload_subscription_changes(std::slice::from_ref(&feed), &[])
+            .unwrap();
+        phone
+            .as_ref()
+            .upload_episode_actions(&[play_action(&feed, &media, 60)])
+            .unwrap();
+        let mut settings = HashMap::new();
+        settings.insert(String::from("theme"), String::from("dark"));
+        phone.save_account_settings(settings, Vec::new()).unwrap();
+
+        assert_eq!(feed, laptop.get_all_subscriptions().unwrap()[0].url);
+        assert_eq!(
+            1,
+            laptop
+                .as_ref()
+                .get_episode_actions(None, Some(since), false)
+                .unwrap()
+                .actions
+                .len()
+        );
+        assert_eq!(
+            Some(&String::from("dark")),
+            laptop.get_account_settings().unwrap().get("theme")
+        );
+        assert_eq!(2, laptop.list_devices().unwrap().len());
+        assert_eq!(
+            1,
+            server
+                .fake_client("username", "phone")
+                .unwrap()
+                .get_all_subscriptions()
+                .unwrap()
+                .len()
+        );
+
+        let intruder = server
+            .device_client("username", "wrong password", "phone")
+            .unwrap();
+        assert!(intruder.list_devices().is_err());
+    }
+
+    #[test]
+    fn deregister_clears_subscriptions_for_every_profile() {
+        let server = FakeGpodderServer::start().unwrap();
+        let feed = Url::parse("http://example.com/feed.rss").unwrap();
+
+        for profile in [
+            ServerProfile::GpodderNet,
+            ServerProfile::MicroGpodderServer,
+            ServerProfile::Opodsync,
+            ServerProfile::Nextcloud,
+        ] {
+            server.add_user("username", "password");
+            let phone = server
+                .device_client("username", "password", "phone")
+                .unwrap()
+                .with_server_profile(profile);
+            phone
+                .upload_subscriptions_of_device(std::slice::from_ref(&feed))
+                .unwrap();
+
+            assert_eq!(
+                Deregistration::SubscriptionsCleared,
+                phone.deregister().unwrap(),
+                "{:?}",
+                profile
+            );
+            assert!(server
+                .fake_client("username", "phone")
+                .unwrap()
+                .get_subscriptions_of_device()
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn send_credentials_after_challenge() {
+        let server = FakeGpodderServer::start().unwrap();
+        server.add_user("username", "password");
+        let has_authorization = |headers: &[(String, String)]| {
+            headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+        };
+
+        let client = server
+            .authenticated_client("username", "password")
+            .unwrap()
+            .with_authentication_mode(AuthenticationMode::Challenge)
+            .with_debug_recorder(DebugRecorder::new(10));
+        assert!(client.list_devices().unwrap().is_empty());
+
+        let log = client.debug_log();
+        assert_eq!(2, log.len());
+        assert_eq!(Some(401), log[0].status);
+        assert!(!has_authorization(&log[0].request_headers));
+        assert_eq!(Some(200), log[1].status);
+        assert!(has_authorization(&log[1].request_headers));
+
+        let client = server
+            .authenticated_client("username", "wrong password")
+            .unwrap()
+            .with_authentication_mode(AuthenticationMode::Challenge)
+            .with_debug_recorder(DebugRecorder::new(10));
+        assert!(client.list_devices().is_err());
+        assert_eq!(2, client.debug_log().len());
+
+        let client = server
+            .authenticated_client("username", "password")
+            .unwrap()
+            .with_debug_recorder(DebugRecorder::new(10));
+        assert!(client.list_devices().unwrap().is_empty());
+        let log = client.debug_log();
+        assert_eq!(1, log.len());
+        assert!(has_authorization(&log[0].request_headers));
+    }
+
+    #[test]
+    fn store_profile_visibility_in_public_subscriptions() {
+        let server = FakeGpodderServer::start().unwrap();
+        server.add_user("username", "password");
+        let client = server.authenticated_client("username", "password").unwrap();
+        assert!(client.is_profile_public().unwrap());
+
+        client.set_profile_public(false).unwrap();
+        assert!(!client.is_profile_public().unwrap());
+        assert_eq!(
+            Some(&String::from("false")),
+            client
+                .get_account_settings()
+                .unwrap()
+                .get(PUBLIC_SUBSCRIPTIONS_KEY)
+        );
+        assert_eq!(
+            Some(&String::from("false")),
+            server
+                .fake_client("username", "")
+                .unwrap()
+                .get_account_settings()
+                .unwrap()
+                .get("public_subscriptions")
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Helpers for applications built on top of this crate
+
+use crate::error::Error;
+use serde::Serialize;
+
+/// Formatting of response types as human-readable JSON, e.g. for command-line tools
+///
+/// Implemented for every type implementing [Serialize], which includes all response types of this crate.
+///
+/// # Examples
+///
+/// ```
+/// use mygpoclient::directory::Tag;
+/// use mygpoclient::util::ToPrettyJson;
+///
+/// let tag = Tag {
+///     title: String::from("Technology"),
+///     tag: String::from("technology"),
+///     usage: 530,
+/// };
+///
+/// println!("{}", tag.to_pretty_json()?);
+/// # Ok::<(), mygpoclient::error::Error>(())
+/// ```
+pub trait ToPrettyJson {
+    /// Serialize as indented JSON
+    fn to_pretty_json(&self) -> Result<String, Error>;
+}
+
+impl<T: Serialize + ?Sized> ToPrettyJson for T {
+    fn to_pretty_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(all(test, feature = "subscriptions"))]
+mod tests {
+    use super::ToPrettyJson;
+    use crate::health::ConnectionStatus;
+    use crate::subscription::GetSubscriptionChangesResponse;
+
+    #[test]
+    fn pretty_json() {
+        let response = GetSubscriptionChangesResponse {
+            timestamp: 12347,
+            add: Vec::new(),
+            remove: Vec::new(),
+        };
+
+        assert_eq!(
+            "{\n  \"timestamp\": 12347,\n  \"add\": [],\n  \"remove\": []\n}",
+            response.to_pretty_json().unwrap()
+        );
+        assert_eq!(
+            "\"AuthFailed\"",
+            ConnectionStatus::AuthFailed.to_pretty_json().unwrap()
+        );
+    }
+}
@@ -11,6 +11,7 @@ use mygpoclient::directory::RetrievePodcastData;
 use mygpoclient::directory::RetrievePodcastsForTag;
 use mygpoclient::directory::RetrieveTopTags;
 use mygpoclient::error::Error;
+use mygpoclient::reference::{DeviceId, EpisodeRef, TagName, Username};
 
 #[test]
 fn test_retrieve_top_tags_device_client() -> Result<(), Error> {
@@ -26,7 +27,7 @@ fn test_retrieve_top_tags_device_client() -> Result<(), Error> {
 fn test_retrieve_podcasts_for_tag_device_client() -> Result<(), Error> {
     let client = get_device_client();
     let max_results = 5;
-    let podcasts = client.retrieve_podcasts_for_tag("new", max_results)?;
+    let podcasts = client.retrieve_podcasts_for_tag(TagName::from("new"), max_results)?;
     assert!(podcasts.len() <= max_results as usize);
 
     Ok(())
@@ -49,7 +50,7 @@ fn test_retrieve_episode_data_device_client() -> Result<(), Error> {
     )
     .unwrap();
     let podcast = Url::parse("http://feeds.wnyc.org/onthemedia?format=xml").unwrap();
-    client.retrieve_episode_data(url, podcast)?;
+    client.retrieve_episode_data(EpisodeRef::new(podcast, url))?;
 
     Ok(())
 }
@@ -78,5 +79,9 @@ fn get_device_client() -> DeviceClient {
     let password = env::var("GPODDER_NET_PASSWORD").unwrap();
     let deviceid = env::var("GPODDER_NET_DEVICEID").unwrap();
 
-    DeviceClient::new(&username, &password, &deviceid)
+    DeviceClient::new(
+        &Username::from(username),
+        &password,
+        &DeviceId::from(deviceid),
+    )
 }
@@ -0,0 +1,39 @@
+extern crate mygpoclient;
+
+use std::env;
+
+use url::Url;
+
+use mygpoclient::client::AuthenticatedClient;
+use mygpoclient::episode::{
+    EpisodeAction, EpisodeActionType, GetEpisodeActions, UploadEpisodeActions,
+};
+use mygpoclient::error::Error;
+
+const DUMMY_PODCAST_URL: &'static str = "http://example.com/feed1.rss";
+const DUMMY_EPISODE_URL: &'static str = "http://example.com/files/s01e20.mp3";
+
+#[test]
+fn test_upload_flattr_episode_action() -> Result<(), Error> {
+    let client = get_authenticated_client();
+    let podcast = Url::parse(DUMMY_PODCAST_URL).unwrap();
+    let episode = Url::parse(DUMMY_EPISODE_URL).unwrap();
+
+    let flattr = EpisodeAction::new_flattr(podcast.clone(), episode.clone(), None);
+    let response = client.upload_episode_actions(&[flattr])?;
+
+    let actions = client.get_episode_actions(Some(podcast), Some(response.timestamp - 1), false)?;
+    assert!(actions
+        .actions
+        .iter()
+        .any(|action| action.episode == episode && action.action == EpisodeActionType::Flattr));
+
+    Ok(())
+}
+
+fn get_authenticated_client() -> AuthenticatedClient {
+    let username = env::var("GPODDER_NET_USERNAME").unwrap();
+    let password = env::var("GPODDER_NET_PASSWORD").unwrap();
+
+    AuthenticatedClient::new(&username, &password)
+}
@@ -5,6 +5,7 @@ use std::env;
 use mygpoclient::client::DeviceClient;
 use mygpoclient::error::Error;
 use mygpoclient::favorite::GetFavoriteEpisodes;
+use mygpoclient::reference::{DeviceId, Username};
 
 #[test]
 fn test_get_favorite_episodes_device_client() -> Result<(), Error> {
@@ -12,7 +13,11 @@ fn test_get_favorite_episodes_device_client() -> Result<(), Error> {
     let password = env::var("GPODDER_NET_PASSWORD").unwrap();
     let deviceid = env::var("GPODDER_NET_DEVICEID").unwrap();
 
-    let client = DeviceClient::new(&username, &password, &deviceid);
+    let client = DeviceClient::new(
+        &Username::from(username),
+        &password,
+        &DeviceId::from(deviceid),
+    );
     client.get_favorite_episodes()?;
 
     Ok(())
@@ -6,6 +6,7 @@ use url::Url;
 
 use mygpoclient::client::DeviceClient;
 use mygpoclient::error::Error;
+use mygpoclient::reference::{DeviceId, EpisodeRef, Username};
 use mygpoclient::settings::GetAccountSettings;
 use mygpoclient::settings::GetEpisodeSettings;
 use mygpoclient::settings::GetPodcastSettings;
@@ -60,8 +61,10 @@ fn test_save_episode_settings_device_client() -> Result<(), Error> {
     let settings = client.save_episode_settings(
         set.clone(),
         remove.clone(),
-        Url::parse("http://example.com/feed1.rss").unwrap(),
-        Url::parse("http://example.com/files/s01e20.mp3").unwrap(),
+        EpisodeRef::new(
+            Url::parse("http://example.com/feed1.rss").unwrap(),
+            Url::parse("http://example.com/files/s01e20.mp3").unwrap(),
+        ),
     )?;
     assert!(set
         .iter()
@@ -87,10 +90,10 @@ fn test_get_podcast_settings_device_client() -> Result<(), Error> {
 #[test]
 fn test_get_episode_settings_device_client() -> Result<(), Error> {
     let client = get_device_client();
-    client.get_episode_settings(
+    client.get_episode_settings(EpisodeRef::new(
         Url::parse("http://example.com/feed1.rss").unwrap(),
         Url::parse("http://example.com/files/s01e20.mp3").unwrap(),
-    )?;
+    ))?;
     Ok(())
 }
 
@@ -99,5 +102,9 @@ fn get_device_client() -> DeviceClient {
     let password = env::var("GPODDER_NET_PASSWORD").unwrap();
     let deviceid = env::var("GPODDER_NET_DEVICEID").unwrap();
 
-    DeviceClient::new(&username, &password, &deviceid)
+    DeviceClient::new(
+        &Username::from(username),
+        &password,
+        &DeviceId::from(deviceid),
+    )
 }
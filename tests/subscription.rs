@@ -7,6 +7,7 @@ use url::Url;
 
 use mygpoclient::client::DeviceClient;
 use mygpoclient::error::Error;
+use mygpoclient::reference::{DeviceId, Username};
 use mygpoclient::subscription::{GetAllSubscriptions, SubscriptionChanges, SubscriptionsOfDevice};
 
 const DUMMY_PODCAST_URL: &'static str = "http://ubuntupodcast.org/feed/";
@@ -17,7 +18,11 @@ fn test_subscription() -> Result<(), Error> {
     let password = env::var("GPODDER_NET_PASSWORD").unwrap();
     let deviceid = env::var("GPODDER_NET_DEVICEID").unwrap();
 
-    let client = DeviceClient::new(&username, &password, &deviceid);
+    let client = DeviceClient::new(
+        &Username::from(username),
+        &password,
+        &DeviceId::from(deviceid),
+    );
 
     let subscriptions = client.get_subscriptions_of_device()?;
 
@@ -76,7 +81,11 @@ fn test_subscription_changes() -> Result<(), Error> {
     let password = env::var("GPODDER_NET_PASSWORD").unwrap();
     let deviceid = env::var("GPODDER_NET_DEVICEID").unwrap();
 
-    let client = DeviceClient::new(&username, &password, &deviceid);
+    let client = DeviceClient::new(
+        &Username::from(username),
+        &password,
+        &DeviceId::from(deviceid),
+    );
 
     let subscriptions = client.get_subscriptions_of_device()?;
 
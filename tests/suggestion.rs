@@ -4,6 +4,7 @@ use std::env;
 
 use mygpoclient::client::DeviceClient;
 use mygpoclient::error::Error;
+use mygpoclient::reference::{DeviceId, Username};
 use mygpoclient::suggestion::RetrieveSuggestedPodcasts;
 
 #[test]
@@ -13,7 +14,11 @@ fn test_retrieve_suggested_podcasts_device_client() -> Result<(), Error> {
     let deviceid = env::var("GPODDER_NET_DEVICEID").unwrap();
 
     let max_results = 3;
-    let client = DeviceClient::new(&username, &password, &deviceid);
+    let client = DeviceClient::new(
+        &Username::from(username),
+        &password,
+        &DeviceId::from(deviceid),
+    );
     let suggestions = client.retrieve_suggested_podcasts(max_results)?;
     assert!(suggestions.len() <= max_results as usize);
 